@@ -0,0 +1,30 @@
+fn main() {
+    #[cfg(feature = "ffi")]
+    generate_header();
+}
+
+/// Generates `nebulauth.h` from `src/ffi.rs` into `OUT_DIR`. The checked-in copy under
+/// `include/` is compared against it by `tests/ffi_tests.rs`, which also updates it.
+#[cfg(feature = "ffi")]
+fn generate_header() {
+    let crate_dir = std::env::var("CARGO_MANIFEST_DIR").expect("CARGO_MANIFEST_DIR is set");
+    println!("cargo:rerun-if-changed=src/ffi.rs");
+    println!("cargo:rerun-if-changed=cbindgen.toml");
+
+    let config = cbindgen::Config::from_file(format!("{crate_dir}/cbindgen.toml"))
+        .expect("cbindgen.toml should be valid");
+    let mut header = Vec::new();
+    cbindgen::Builder::new()
+        .with_config(config)
+        .with_src(format!("{crate_dir}/src/ffi.rs"))
+        .generate()
+        .expect("src/ffi.rs should produce a C header")
+        .write(&mut header);
+
+    // cbindgen splits `NebulAuth` into two words for enum constants.
+    let header = String::from_utf8(header)
+        .expect("header is UTF-8")
+        .replace("NEBUL_AUTH_", "NEBULAUTH_");
+    let out_dir = std::env::var("OUT_DIR").expect("OUT_DIR is set");
+    std::fs::write(format!("{out_dir}/nebulauth.h"), header).expect("OUT_DIR should be writable");
+}
@@ -0,0 +1,136 @@
+/* Generated from src/ffi.rs by cbindgen (cargo build --features ffi). Do not edit. */
+
+#ifndef NEBULAUTH_H
+#define NEBULAUTH_H
+
+#include <stdarg.h>
+#include <stdbool.h>
+#include <stdint.h>
+#include <stdlib.h>
+
+typedef enum NebulAuthErrorCode {
+  NEBULAUTH_ERROR_CODE_OK = 0,
+  // A required pointer was null or a string was not valid UTF-8.
+  NEBULAUTH_ERROR_CODE_INVALID_ARGUMENT = 1,
+  NEBULAUTH_ERROR_CODE_CONFIG = 2,
+  NEBULAUTH_ERROR_CODE_REQUEST = 3,
+  NEBULAUTH_ERROR_CODE_TIMEOUT = 4,
+  NEBULAUTH_ERROR_CODE_URL = 5,
+  NEBULAUTH_ERROR_CODE_CRYPTO = 6,
+  NEBULAUTH_ERROR_CODE_IO = 7,
+  NEBULAUTH_ERROR_CODE_API = 8,
+  NEBULAUTH_ERROR_CODE_DECODE = 9,
+  // The call panicked, e.g. because it was made from inside a Tokio runtime.
+  NEBULAUTH_ERROR_CODE_PANIC = 10,
+} NebulAuthErrorCode;
+
+// Values for `NebulAuthClientConfig::replay_protection`.
+enum NebulAuthReplayProtection
+#if defined(__cplusplus) || __STDC_VERSION__ >= 202311L
+  : uint32_t
+#endif // defined(__cplusplus) || __STDC_VERSION__ >= 202311L
+ {
+  NEBULAUTH_REPLAY_PROTECTION_STRICT = 0,
+  NEBULAUTH_REPLAY_PROTECTION_NONCE = 1,
+  NEBULAUTH_REPLAY_PROTECTION_NONE = 2,
+};
+#ifndef __cplusplus
+#if __STDC_VERSION__ >= 202311L
+typedef enum NebulAuthReplayProtection NebulAuthReplayProtection;
+#else
+typedef uint32_t NebulAuthReplayProtection;
+#endif // __STDC_VERSION__ >= 202311L
+#endif // __cplusplus
+
+// Opaque client handle. Safe to share between threads.
+typedef struct NebulAuthClient NebulAuthClient;
+
+// Null strings and a zero `timeout_ms` use the `NebulAuthClientOptions` defaults.
+typedef struct NebulAuthClientConfig {
+  const char *base_url;
+  const char *bearer_token;
+  const char *signing_secret;
+  const char *service_slug;
+  // One of `NebulAuthReplayProtection`.
+  uint32_t replay_protection;
+  uint64_t timeout_ms;
+} NebulAuthClientConfig;
+
+// `status_code` is set for `API` errors, otherwise 0. Release `message` with
+// `nebulauth_free_error`.
+typedef struct NebulAuthError {
+  enum NebulAuthErrorCode code;
+  uint16_t status_code;
+  char *message;
+} NebulAuthError;
+
+// `body` is the JSON response body. Release it with `nebulauth_free_response`.
+typedef struct NebulAuthResponse {
+  uint16_t status_code;
+  bool ok;
+  char *body;
+} NebulAuthResponse;
+
+#ifdef __cplusplus
+extern "C" {
+#endif // __cplusplus
+
+// Creates a client and stores it in `*out_client` (null on failure). Release it with
+// `nebulauth_free_client`.
+//
+// # Safety
+// `config` must point to a valid config whose strings are null or NUL-terminated.
+// `out_client` must be valid for writes and `out_error` null or valid for writes.
+enum NebulAuthErrorCode nebulauth_client_new(const struct NebulAuthClientConfig *config,
+                                             struct NebulAuthClient **out_client,
+                                             struct NebulAuthError *out_error);
+
+// Calls `/keys/verify`. `hwid` and `request_id` may be null. A response is written for any
+// HTTP status; check `ok` or `status_code` for rejected keys.
+//
+// # Safety
+// `client` must come from `nebulauth_client_new` and not be freed yet. String arguments
+// must be null or NUL-terminated; `out_response` and `out_error` null or valid for writes.
+enum NebulAuthErrorCode nebulauth_verify_key(const struct NebulAuthClient *client,
+                                             const char *key,
+                                             const char *hwid,
+                                             const char *request_id,
+                                             struct NebulAuthResponse *out_response,
+                                             struct NebulAuthError *out_error);
+
+// Calls `/keys/reset-hwid`. At least one of `discord_id` and `key` is required;
+// `request_id` may be null.
+//
+// # Safety
+// Same as `nebulauth_verify_key`.
+enum NebulAuthErrorCode nebulauth_reset_hwid(const struct NebulAuthClient *client,
+                                             const char *discord_id,
+                                             const char *key,
+                                             const char *request_id,
+                                             struct NebulAuthResponse *out_response,
+                                             struct NebulAuthError *out_error);
+
+// Frees a client. Null is ignored.
+//
+// # Safety
+// `client` must be null or come from `nebulauth_client_new`, and must not be used or freed
+// again afterwards.
+void nebulauth_free_client(struct NebulAuthClient *client);
+
+// Frees the response body and sets it to null, so freeing twice is harmless.
+//
+// # Safety
+// `response` must be null or point to a response written by this library.
+void nebulauth_free_response(struct NebulAuthResponse *response);
+
+// Frees the error message and sets it to null, so freeing twice is harmless.
+//
+// # Safety
+// `error` must be null or point to an error written by this library.
+void nebulauth_free_error(struct NebulAuthError *error);
+
+#ifdef __cplusplus
+}  // extern "C"
+#endif  // __cplusplus
+
+#endif  /* NEBULAUTH_H */
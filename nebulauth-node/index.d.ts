@@ -0,0 +1,115 @@
+/* tslint:disable */
+/* eslint-disable */
+
+/* auto-generated by NAPI-RS */
+
+/** Value of `code` on a `NebulAuthError`; the names match the Python exceptions. */
+export const enum ErrorCode {
+  ConfigError = 'ConfigError',
+  RequestError = 'RequestError',
+  RequestTimeoutError = 'RequestTimeoutError',
+  UrlError = 'UrlError',
+  CryptoError = 'CryptoError',
+  IoError = 'IoError',
+  ApiError = 'ApiError',
+  DecodeError = 'DecodeError',
+  /** An error kind this version of the addon does not know yet. */
+  NebulAuthError = 'NebulAuthError'
+}
+/** Properties of the `Error` a rejected promise carries. */
+export interface NebulAuthError {
+  name: string
+  message: string
+  code: ErrorCode
+  statusCode?: number
+}
+/** Whether `error` is a rejection from this module. */
+export declare function isNebulAuthError(error: unknown): error is NebulAuthError
+export interface Response {
+  statusCode: number
+  ok: boolean
+  /** Decoded JSON body. */
+  data: any
+  headers: Record<string, string>
+}
+/** Returns `response` if it is 2xx, otherwise throws an `ApiError` with its status and message. */
+export declare function checkStatus(response: Response): Response
+export interface ClientOptions {
+  baseUrl?: string
+  bearerToken?: string
+  signingSecret?: string
+  serviceSlug?: string
+  /** Defaults to `strict`. */
+  replayProtection?: 'strict' | 'nonce' | 'none'
+  timeoutMs?: number
+}
+/** Proof-of-possession signing; `usePop` defaults to `true` when this is given. */
+export interface PopOptions {
+  usePop?: boolean
+  accessToken?: string
+  popKey?: string
+}
+export interface VerifyKeyInput {
+  key: string
+  hwid?: string
+  requestId?: string
+  pop?: PopOptions
+}
+export interface AuthVerifyInput {
+  key: string
+  hwid?: string
+  requestId?: string
+}
+export interface RedeemKeyInput {
+  key: string
+  discordId: string
+  serviceSlug?: string
+  requestId?: string
+  pop?: PopOptions
+}
+export interface ResetHwidInput {
+  discordId?: string
+  key?: string
+  requestId?: string
+  pop?: PopOptions
+}
+export class NebulAuthClient {
+  constructor(options: ClientOptions)
+  verifyKey(input: VerifyKeyInput): Promise<Response>
+  authVerify(input: AuthVerifyInput): Promise<Response>
+  redeemKey(input: RedeemKeyInput): Promise<Response>
+  resetHwid(input: ResetHwidInput): Promise<Response>
+}
+/**
+ * `bearerToken` and `sessionCookie` are mutually exclusive. With neither, a session is read
+ * from `sessionFile` if given, or created by `login`.
+ */
+export interface DashboardClientOptions {
+  baseUrl?: string
+  bearerToken?: string
+  sessionCookie?: string
+  sessionFile?: string
+  autoRelogin?: boolean
+  timeoutMs?: number
+}
+export interface KeyInput {
+  label?: string
+  durationHours?: number
+  metadata?: any
+}
+export class NebulAuthDashboardClient {
+  constructor(options?: DashboardClientOptions | undefined | null)
+  get sessionCookie(): string | null
+  login(email: string, password: string): Promise<Response>
+  logout(): Promise<Response>
+  me(): Promise<Response>
+  listKeys(query?: Record<string, string> | undefined | null): Promise<Response>
+  getKey(id: string): Promise<Response>
+  createKey(input?: KeyInput | undefined | null): Promise<Response>
+  updateKey(id: string, input: KeyInput): Promise<Response>
+  deleteKey(id: string, reason?: string | undefined | null): Promise<Response>
+  resetKeyHwid(id: string): Promise<Response>
+  analyticsSummary(days?: number | undefined | null): Promise<Response>
+  /** Any dashboard endpoint, for calls without a dedicated method. */
+  request(method: string, path: string, body?: any | undefined | null, query?: Record<string, string> | undefined | null): Promise<Response>
+}
@@ -0,0 +1,16 @@
+// Loads the addon built by `npm run build`, e.g. nebulauth.linux-x64-gnu.node, falling back
+// to a plain nebulauth.node.
+const { existsSync, readdirSync } = require('node:fs')
+const { join } = require('node:path')
+
+const prefix = `nebulauth.${process.platform}-${process.arch}`
+const candidates = readdirSync(__dirname)
+  .filter((file) => file.startsWith(prefix) && file.endsWith('.node'))
+  .concat(['nebulauth.node'])
+const addon = candidates.map((file) => join(__dirname, file)).find(existsSync)
+
+if (!addon) {
+  throw new Error(`nebulauth: no native addon for ${process.platform}-${process.arch}; run \`npm run build\``)
+}
+
+module.exports = require(addon)
@@ -0,0 +1,567 @@
+//! Node.js bindings, built with napi-rs (`npm run build`; see `package.json`). `index.d.ts` is generated from
+//! the `#[napi]` items in this file.
+//!
+//! Every method returns a promise. Failures reject with an `Error` whose `name` is
+//! `NebulAuthError` and whose `code` is one of [`ErrorCode`]; `ApiError`s also carry
+//! `statusCode`.
+
+use napi::bindgen_prelude::*;
+use napi::{Env, JsObject, JsUnknown, ValueType};
+use napi_derive::napi;
+use serde_json::Value;
+use std::collections::HashMap;
+use std::future::Future;
+use std::path::PathBuf;
+use std::sync::Arc;
+
+use nebulauth_sdk::{
+    DashboardAuth, DashboardRequestOptions, DashboardResponse, KeyCreateRequest, KeyRevokeRequest,
+    KeyUpdateRequest, LoginRequest, NebulAuthClientOptions, NebulAuthDashboardClientOptions,
+    NebulAuthError, NebulAuthResponse, ReplayProtectionMode,
+};
+
+/// Value of `code` on a `NebulAuthError`; the names match the Python exceptions.
+#[napi(string_enum)]
+#[allow(clippy::enum_variant_names)]
+pub enum ErrorCode {
+    ConfigError,
+    RequestError,
+    RequestTimeoutError,
+    UrlError,
+    CryptoError,
+    IoError,
+    ApiError,
+    DecodeError,
+    /// An error kind this version of the addon does not know yet.
+    NebulAuthError,
+}
+
+impl ErrorCode {
+    fn as_str(&self) -> &'static str {
+        match self {
+            ErrorCode::ConfigError => "ConfigError",
+            ErrorCode::RequestError => "RequestError",
+            ErrorCode::RequestTimeoutError => "RequestTimeoutError",
+            ErrorCode::UrlError => "UrlError",
+            ErrorCode::CryptoError => "CryptoError",
+            ErrorCode::IoError => "IoError",
+            ErrorCode::ApiError => "ApiError",
+            ErrorCode::DecodeError => "DecodeError",
+            ErrorCode::NebulAuthError => "NebulAuthError",
+        }
+    }
+}
+
+/// Properties of the `Error` a rejected promise carries.
+#[napi(object, js_name = "NebulAuthError")]
+pub struct ErrorDetails {
+    pub name: String,
+    pub message: String,
+    pub code: ErrorCode,
+    pub status_code: Option<u32>,
+}
+
+impl From<&NebulAuthError> for ErrorDetails {
+    fn from(error: &NebulAuthError) -> Self {
+        let (code, status_code) = match error {
+            NebulAuthError::Config(_) => (ErrorCode::ConfigError, None),
+            NebulAuthError::Request(e) if e.is_timeout() => (ErrorCode::RequestTimeoutError, None),
+            NebulAuthError::Request(_) => (ErrorCode::RequestError, None),
+            NebulAuthError::Url(_) => (ErrorCode::UrlError, None),
+            NebulAuthError::Crypto(_) => (ErrorCode::CryptoError, None),
+            NebulAuthError::Io(_) => (ErrorCode::IoError, None),
+            NebulAuthError::Api { status_code, .. } => {
+                (ErrorCode::ApiError, Some(u32::from(*status_code)))
+            }
+            NebulAuthError::Decode(_) => (ErrorCode::DecodeError, None),
+            _ => (ErrorCode::NebulAuthError, None),
+        };
+        Self {
+            name: "NebulAuthError".to_string(),
+            message: error.to_string(),
+            code,
+            status_code,
+        }
+    }
+}
+
+/// Builds the JS `Error` for `error`. Must run on the JS thread.
+fn js_error(env: &Env, error: &NebulAuthError) -> Error {
+    let details = ErrorDetails::from(error);
+    let build = || -> Result<JsObject> {
+        let mut object = env.create_error(Error::from_reason(details.message.clone()))?;
+        object.set_named_property("name", env.create_string(&details.name)?)?;
+        object.set_named_property("code", env.create_string(details.code.as_str())?)?;
+        if let Some(status_code) = details.status_code {
+            object.set_named_property("statusCode", env.create_uint32(status_code)?)?;
+        }
+        Ok(object)
+    };
+    match build() {
+        Ok(object) => Error::from(object.into_unknown()),
+        Err(e) => e,
+    }
+}
+
+/// Whether `error` is a rejection from this module.
+#[napi(
+    js_name = "isNebulAuthError",
+    ts_args_type = "error: unknown",
+    ts_return_type = "error is NebulAuthError"
+)]
+pub fn is_nebulauth_error(error: JsUnknown) -> Result<bool> {
+    if error.get_type()? != ValueType::Object || !error.is_error()? {
+        return Ok(false);
+    }
+    let object = error.coerce_to_object()?;
+    if !object.has_named_property("name")? {
+        return Ok(false);
+    }
+    let name = object
+        .get_named_property::<JsUnknown>("name")?
+        .coerce_to_string()?
+        .into_utf8()?;
+    Ok(name.as_str()? == "NebulAuthError")
+}
+
+#[napi(object)]
+pub struct Response {
+    pub status_code: u32,
+    pub ok: bool,
+    /// Decoded JSON body.
+    pub data: Value,
+    pub headers: HashMap<String, String>,
+}
+
+impl From<NebulAuthResponse> for Response {
+    fn from(response: NebulAuthResponse) -> Self {
+        Self {
+            status_code: u32::from(response.status_code),
+            ok: response.ok,
+            data: response.data,
+            headers: response.headers,
+        }
+    }
+}
+
+impl From<DashboardResponse> for Response {
+    fn from(response: DashboardResponse) -> Self {
+        Self {
+            status_code: u32::from(response.status_code),
+            ok: response.ok,
+            data: response.data,
+            headers: response.headers,
+        }
+    }
+}
+
+/// Returns `response` if it is 2xx, otherwise throws an `ApiError` with its status and message.
+#[napi(js_name = "checkStatus")]
+pub fn check_status(env: Env, response: Response) -> Result<Response> {
+    let checked = DashboardResponse {
+        status_code: u16::try_from(response.status_code).unwrap_or(u16::MAX),
+        ok: response.ok,
+        data: response.data,
+        headers: response.headers,
+    }
+    .error_for_status();
+    throw(&env, checked).map(Response::from)
+}
+
+/// Runs `future` on the napi Tokio runtime and settles the returned promise with its result.
+fn promise<F, R>(env: &Env, future: F) -> Result<JsObject>
+where
+    F: Future<Output = std::result::Result<R, NebulAuthError>> + Send + 'static,
+    R: Into<Response> + Send + 'static,
+{
+    env.execute_tokio_future(
+        async move { Ok(future.await) },
+        |env, result| match result {
+            Ok(response) => Ok(response.into()),
+            Err(error) => Err(js_error(env, &error)),
+        },
+    )
+}
+
+#[napi(object)]
+pub struct ClientOptions {
+    pub base_url: Option<String>,
+    pub bearer_token: Option<String>,
+    pub signing_secret: Option<String>,
+    pub service_slug: Option<String>,
+    /// Defaults to `strict`.
+    #[napi(ts_type = "'strict' | 'nonce' | 'none'")]
+    pub replay_protection: Option<String>,
+    pub timeout_ms: Option<u32>,
+}
+
+/// Proof-of-possession signing; `usePop` defaults to `true` when this is given.
+#[napi(object)]
+pub struct PopOptions {
+    pub use_pop: Option<bool>,
+    pub access_token: Option<String>,
+    pub pop_key: Option<String>,
+}
+
+#[napi(object)]
+pub struct VerifyKeyInput {
+    pub key: String,
+    pub hwid: Option<String>,
+    pub request_id: Option<String>,
+    pub pop: Option<PopOptions>,
+}
+
+#[napi(object)]
+pub struct AuthVerifyInput {
+    pub key: String,
+    pub hwid: Option<String>,
+    pub request_id: Option<String>,
+}
+
+#[napi(object)]
+pub struct RedeemKeyInput {
+    pub key: String,
+    pub discord_id: String,
+    pub service_slug: Option<String>,
+    pub request_id: Option<String>,
+    pub pop: Option<PopOptions>,
+}
+
+#[napi(object)]
+pub struct ResetHwidInput {
+    pub discord_id: Option<String>,
+    pub key: Option<String>,
+    pub request_id: Option<String>,
+    pub pop: Option<PopOptions>,
+}
+
+fn replay_protection(
+    value: Option<&str>,
+) -> std::result::Result<ReplayProtectionMode, NebulAuthError> {
+    match value.map(str::to_lowercase).as_deref() {
+        None | Some("strict") => Ok(ReplayProtectionMode::Strict),
+        Some("nonce") => Ok(ReplayProtectionMode::Nonce),
+        Some("none") => Ok(ReplayProtectionMode::None),
+        Some(other) => Err(NebulAuthError::Config(format!(
+            "replayProtection must be 'strict', 'nonce' or 'none', not '{other}'"
+        ))),
+    }
+}
+
+fn pop_parts(pop: Option<PopOptions>) -> (bool, Option<String>, Option<String>) {
+    match pop {
+        Some(pop) => (pop.use_pop.unwrap_or(true), pop.access_token, pop.pop_key),
+        None => (false, None, None),
+    }
+}
+
+/// Throws the same `NebulAuthError` a rejected promise would carry.
+fn throw<T>(env: &Env, result: std::result::Result<T, NebulAuthError>) -> Result<T> {
+    result.map_err(|error| js_error(env, &error))
+}
+
+#[napi(js_name = "NebulAuthClient")]
+pub struct JsNebulAuthClient {
+    inner: Arc<nebulauth_sdk::NebulAuthClient>,
+}
+
+#[napi]
+impl JsNebulAuthClient {
+    #[napi(constructor)]
+    pub fn new(env: Env, options: ClientOptions) -> Result<Self> {
+        let defaults = NebulAuthClientOptions::default();
+        let replay_protection = throw(
+            &env,
+            replay_protection(options.replay_protection.as_deref()),
+        )?;
+        let client = throw(
+            &env,
+            nebulauth_sdk::NebulAuthClient::new(NebulAuthClientOptions {
+                base_url: options.base_url.unwrap_or(defaults.base_url.clone()),
+                bearer_token: options.bearer_token,
+                signing_secret: options.signing_secret,
+                service_slug: options.service_slug,
+                replay_protection,
+                timeout_ms: options
+                    .timeout_ms
+                    .map(u64::from)
+                    .unwrap_or(defaults.timeout_ms),
+                ..defaults
+            }),
+        )?;
+        Ok(Self {
+            inner: Arc::new(client),
+        })
+    }
+
+    #[napi(ts_return_type = "Promise<Response>")]
+    pub fn verify_key(&self, env: Env, input: VerifyKeyInput) -> Result<JsObject> {
+        let (use_pop, access_token, pop_key) = pop_parts(input.pop);
+        let input = nebulauth_sdk::VerifyKeyInput {
+            key: input.key,
+            request_id: input.request_id,
+            hwid: input.hwid,
+            use_pop,
+            access_token,
+            pop_key,
+        };
+        let client = self.inner.clone();
+        promise(&env, async move { client.verify_key(input).await })
+    }
+
+    #[napi(ts_return_type = "Promise<Response>")]
+    pub fn auth_verify(&self, env: Env, input: AuthVerifyInput) -> Result<JsObject> {
+        let input = nebulauth_sdk::AuthVerifyInput {
+            key: input.key,
+            hwid: input.hwid,
+            request_id: input.request_id,
+        };
+        let client = self.inner.clone();
+        promise(&env, async move { client.auth_verify(input).await })
+    }
+
+    #[napi(ts_return_type = "Promise<Response>")]
+    pub fn redeem_key(&self, env: Env, input: RedeemKeyInput) -> Result<JsObject> {
+        let (use_pop, access_token, pop_key) = pop_parts(input.pop);
+        let input = nebulauth_sdk::RedeemKeyInput {
+            key: input.key,
+            discord_id: input.discord_id,
+            service_slug: input.service_slug,
+            request_id: input.request_id,
+            use_pop,
+            access_token,
+            pop_key,
+        };
+        let client = self.inner.clone();
+        promise(&env, async move { client.redeem_key(input).await })
+    }
+
+    #[napi(ts_return_type = "Promise<Response>")]
+    pub fn reset_hwid(&self, env: Env, input: ResetHwidInput) -> Result<JsObject> {
+        let (use_pop, access_token, pop_key) = pop_parts(input.pop);
+        let input = nebulauth_sdk::ResetHwidInput {
+            discord_id: input.discord_id,
+            key: input.key,
+            request_id: input.request_id,
+            use_pop,
+            access_token,
+            pop_key,
+        };
+        let client = self.inner.clone();
+        promise(&env, async move { client.reset_hwid(input).await })
+    }
+}
+
+/// `bearerToken` and `sessionCookie` are mutually exclusive. With neither, a session is read
+/// from `sessionFile` if given, or created by `login`.
+#[napi(object)]
+pub struct DashboardClientOptions {
+    pub base_url: Option<String>,
+    pub bearer_token: Option<String>,
+    pub session_cookie: Option<String>,
+    pub session_file: Option<String>,
+    pub auto_relogin: Option<bool>,
+    pub timeout_ms: Option<u32>,
+}
+
+#[napi(object)]
+pub struct KeyInput {
+    pub label: Option<String>,
+    pub duration_hours: Option<i64>,
+    pub metadata: Option<Value>,
+}
+
+fn request_options(query: Option<HashMap<String, String>>) -> DashboardRequestOptions {
+    DashboardRequestOptions {
+        query: query.unwrap_or_default(),
+        ..Default::default()
+    }
+}
+
+#[napi(js_name = "NebulAuthDashboardClient")]
+pub struct JsNebulAuthDashboardClient {
+    inner: Arc<nebulauth_sdk::NebulAuthDashboardClient>,
+}
+
+#[napi]
+impl JsNebulAuthDashboardClient {
+    #[napi(constructor)]
+    pub fn new(env: Env, options: Option<DashboardClientOptions>) -> Result<Self> {
+        let options = options.unwrap_or(DashboardClientOptions {
+            base_url: None,
+            bearer_token: None,
+            session_cookie: None,
+            session_file: None,
+            auto_relogin: None,
+            timeout_ms: None,
+        });
+        let auth = match (options.bearer_token, options.session_cookie) {
+            (Some(_), Some(_)) => {
+                return throw(
+                    &env,
+                    Err(NebulAuthError::Config(
+                        "pass either bearerToken or sessionCookie, not both".to_string(),
+                    )),
+                )
+            }
+            (Some(bearer_token), None) => Some(DashboardAuth::Bearer { bearer_token }),
+            (None, Some(session_cookie)) => Some(DashboardAuth::Session { session_cookie }),
+            (None, None) => None,
+        };
+        let defaults = NebulAuthDashboardClientOptions::default();
+        let client = throw(
+            &env,
+            nebulauth_sdk::NebulAuthDashboardClient::new(NebulAuthDashboardClientOptions {
+                base_url: options.base_url.unwrap_or(defaults.base_url.clone()),
+                auth,
+                timeout_ms: options
+                    .timeout_ms
+                    .map(u64::from)
+                    .unwrap_or(defaults.timeout_ms),
+                session_file: options.session_file.map(PathBuf::from),
+                auto_relogin: options.auto_relogin.unwrap_or(defaults.auto_relogin),
+                ..defaults
+            }),
+        )?;
+        Ok(Self {
+            inner: Arc::new(client),
+        })
+    }
+
+    #[napi(getter)]
+    pub fn session_cookie(&self) -> Option<String> {
+        self.inner.session_cookie()
+    }
+
+    #[napi(ts_return_type = "Promise<Response>")]
+    pub fn login(&self, env: Env, email: String, password: String) -> Result<JsObject> {
+        let client = self.inner.clone();
+        promise(&env, async move {
+            client
+                .login(
+                    LoginRequest { email, password },
+                    DashboardRequestOptions::default(),
+                )
+                .await
+        })
+    }
+
+    #[napi(ts_return_type = "Promise<Response>")]
+    pub fn logout(&self, env: Env) -> Result<JsObject> {
+        let client = self.inner.clone();
+        promise(&env, async move {
+            client.logout(DashboardRequestOptions::default()).await
+        })
+    }
+
+    #[napi(ts_return_type = "Promise<Response>")]
+    pub fn me(&self, env: Env) -> Result<JsObject> {
+        let client = self.inner.clone();
+        promise(&env, async move {
+            client.me(DashboardRequestOptions::default()).await
+        })
+    }
+
+    #[napi(ts_return_type = "Promise<Response>")]
+    pub fn list_keys(&self, env: Env, query: Option<HashMap<String, String>>) -> Result<JsObject> {
+        let client = self.inner.clone();
+        promise(&env, async move {
+            client.list_keys(request_options(query)).await
+        })
+    }
+
+    #[napi(ts_return_type = "Promise<Response>")]
+    pub fn get_key(&self, env: Env, id: String) -> Result<JsObject> {
+        let client = self.inner.clone();
+        promise(&env, async move {
+            client
+                .get_key(&id, DashboardRequestOptions::default())
+                .await
+        })
+    }
+
+    #[napi(ts_return_type = "Promise<Response>")]
+    pub fn create_key(&self, env: Env, input: Option<KeyInput>) -> Result<JsObject> {
+        let payload = match input {
+            Some(input) => KeyCreateRequest {
+                label: input.label,
+                duration_hours: input.duration_hours,
+                metadata: input.metadata,
+            },
+            None => KeyCreateRequest::default(),
+        };
+        let client = self.inner.clone();
+        promise(&env, async move {
+            client
+                .create_key(payload, DashboardRequestOptions::default())
+                .await
+        })
+    }
+
+    #[napi(ts_return_type = "Promise<Response>")]
+    pub fn update_key(&self, env: Env, id: String, input: KeyInput) -> Result<JsObject> {
+        let payload = KeyUpdateRequest {
+            label: input.label,
+            duration_hours: input.duration_hours,
+            metadata: input.metadata,
+        };
+        let client = self.inner.clone();
+        promise(&env, async move {
+            client
+                .update_key(&id, payload, DashboardRequestOptions::default())
+                .await
+        })
+    }
+
+    #[napi(ts_return_type = "Promise<Response>")]
+    pub fn delete_key(&self, env: Env, id: String, reason: Option<String>) -> Result<JsObject> {
+        let client = self.inner.clone();
+        promise(&env, async move {
+            client
+                .delete_key(
+                    &id,
+                    KeyRevokeRequest { reason },
+                    DashboardRequestOptions::default(),
+                )
+                .await
+        })
+    }
+
+    #[napi(ts_return_type = "Promise<Response>")]
+    pub fn reset_key_hwid(&self, env: Env, id: String) -> Result<JsObject> {
+        let client = self.inner.clone();
+        promise(&env, async move {
+            client
+                .reset_key_hwid(&id, DashboardRequestOptions::default())
+                .await
+        })
+    }
+
+    #[napi(ts_return_type = "Promise<Response>")]
+    pub fn analytics_summary(&self, env: Env, days: Option<i64>) -> Result<JsObject> {
+        let client = self.inner.clone();
+        promise(&env, async move {
+            client
+                .analytics_summary(days, DashboardRequestOptions::default())
+                .await
+        })
+    }
+
+    /// Any dashboard endpoint, for calls without a dedicated method.
+    #[napi(ts_return_type = "Promise<Response>")]
+    pub fn request(
+        &self,
+        env: Env,
+        method: String,
+        path: String,
+        body: Option<Value>,
+        query: Option<HashMap<String, String>>,
+    ) -> Result<JsObject> {
+        let client = self.inner.clone();
+        promise(&env, async move {
+            client
+                .request(&method, &path, body, request_options(query))
+                .await
+        })
+    }
+}
@@ -0,0 +1,274 @@
+from os import PathLike
+from typing import (
+    Any,
+    Awaitable,
+    Dict,
+    Generic,
+    List,
+    Literal,
+    Mapping,
+    Optional,
+    TypedDict,
+    TypeVar,
+    Union,
+)
+
+__version__: str
+
+ReplayProtection = Literal["strict", "nonce", "none"]
+
+_T = TypeVar("_T")
+
+# Response payloads. Every field is optional: error responses carry only `error`/`message`,
+# and fields the API adds later still decode.
+
+class ErrorData(TypedDict, total=False):
+    error: str
+    message: str
+
+class VerifyKeyData(ErrorData, total=False):
+    valid: bool
+
+class AuthVerifyData(ErrorData, total=False):
+    valid: bool
+
+class RedeemKeyData(ErrorData, total=False):
+    discordId: str
+
+class ResetHwidData(ErrorData, total=False):
+    reset: bool
+
+class KeyData(ErrorData, total=False):
+    id: str
+    key: str
+    label: str
+    duration_hours: int
+    expires_at: str
+    metadata: Any
+    hwid: str
+    discord_id: str
+    revoked: bool
+    status: str
+    created_at: str
+
+class KeyEnvelope(TypedDict):
+    key: KeyData
+
+class KeyListEnvelope(TypedDict, total=False):
+    data: List[KeyData]
+    items: List[KeyData]
+    keys: List[KeyData]
+
+class KeySessionData(TypedDict, total=False):
+    id: str
+    key_id: str
+    token_id: str
+    hwid: str
+    ip: str
+    discord_id: str
+    country: str
+    created_at: str
+    last_seen_at: str
+    expires_at: str
+
+class SessionListEnvelope(TypedDict, total=False):
+    data: List[KeySessionData]
+    items: List[KeySessionData]
+    sessions: List[KeySessionData]
+
+KeyPayload = Union[KeyData, KeyEnvelope]
+KeyListPayload = Union[List[KeyData], KeyListEnvelope]
+SessionListPayload = Union[List[KeySessionData], SessionListEnvelope]
+
+class NebulAuthError(Exception): ...
+class ConfigError(NebulAuthError): ...
+class RequestError(NebulAuthError): ...
+class RequestTimeoutError(RequestError): ...
+class UrlError(NebulAuthError): ...
+class CryptoError(NebulAuthError): ...
+class IoError(NebulAuthError): ...
+class DecodeError(NebulAuthError): ...
+class ApiError(NebulAuthError):
+    status_code: int
+
+class Response(Generic[_T]):
+    @property
+    def status_code(self) -> int: ...
+    @property
+    def ok(self) -> bool: ...
+    @property
+    def data(self) -> _T: ...
+    @property
+    def headers(self) -> Dict[str, str]: ...
+    def raise_for_status(self) -> "Response[_T]": ...
+    def to_dict(self) -> Dict[str, Any]: ...
+
+class NebulAuthClient:
+    def __init__(
+        self,
+        *,
+        base_url: Optional[str] = None,
+        bearer_token: Optional[str] = None,
+        signing_secret: Optional[str] = None,
+        service_slug: Optional[str] = None,
+        replay_protection: ReplayProtection = "strict",
+        timeout_ms: int = 15000,
+    ) -> None: ...
+    def verify_key(
+        self,
+        key: str,
+        *,
+        hwid: Optional[str] = None,
+        request_id: Optional[str] = None,
+        use_pop: bool = False,
+        access_token: Optional[str] = None,
+        pop_key: Optional[str] = None,
+    ) -> Response[VerifyKeyData]: ...
+    def verify_key_async(
+        self,
+        key: str,
+        *,
+        hwid: Optional[str] = None,
+        request_id: Optional[str] = None,
+        use_pop: bool = False,
+        access_token: Optional[str] = None,
+        pop_key: Optional[str] = None,
+    ) -> Awaitable[Response[VerifyKeyData]]: ...
+    def auth_verify(
+        self, key: str, *, hwid: Optional[str] = None, request_id: Optional[str] = None
+    ) -> Response[AuthVerifyData]: ...
+    def auth_verify_async(
+        self, key: str, *, hwid: Optional[str] = None, request_id: Optional[str] = None
+    ) -> Awaitable[Response[AuthVerifyData]]: ...
+    def redeem_key(
+        self,
+        key: str,
+        discord_id: str,
+        *,
+        service_slug: Optional[str] = None,
+        request_id: Optional[str] = None,
+        use_pop: bool = False,
+        access_token: Optional[str] = None,
+        pop_key: Optional[str] = None,
+    ) -> Response[RedeemKeyData]: ...
+    def redeem_key_async(
+        self,
+        key: str,
+        discord_id: str,
+        *,
+        service_slug: Optional[str] = None,
+        request_id: Optional[str] = None,
+        use_pop: bool = False,
+        access_token: Optional[str] = None,
+        pop_key: Optional[str] = None,
+    ) -> Awaitable[Response[RedeemKeyData]]: ...
+    def reset_hwid(
+        self,
+        *,
+        discord_id: Optional[str] = None,
+        key: Optional[str] = None,
+        request_id: Optional[str] = None,
+        use_pop: bool = False,
+        access_token: Optional[str] = None,
+        pop_key: Optional[str] = None,
+    ) -> Response[ResetHwidData]: ...
+    def reset_hwid_async(
+        self,
+        *,
+        discord_id: Optional[str] = None,
+        key: Optional[str] = None,
+        request_id: Optional[str] = None,
+        use_pop: bool = False,
+        access_token: Optional[str] = None,
+        pop_key: Optional[str] = None,
+    ) -> Awaitable[Response[ResetHwidData]]: ...
+
+class NebulAuthDashboardClient:
+    def __init__(
+        self,
+        *,
+        base_url: Optional[str] = None,
+        bearer_token: Optional[str] = None,
+        session_cookie: Optional[str] = None,
+        session_file: Optional[Union[str, PathLike[str]]] = None,
+        auto_relogin: bool = True,
+        timeout_ms: int = 15000,
+    ) -> None: ...
+    @property
+    def session_cookie(self) -> Optional[str]: ...
+    def login(self, email: str, password: str) -> Response[Any]: ...
+    def login_async(self, email: str, password: str) -> Awaitable[Response[Any]]: ...
+    def logout(self) -> Response[Any]: ...
+    def logout_async(self) -> Awaitable[Response[Any]]: ...
+    def me(self) -> Response[Any]: ...
+    def me_async(self) -> Awaitable[Response[Any]]: ...
+    def list_keys(
+        self, *, query: Optional[Mapping[str, str]] = None
+    ) -> Response[KeyListPayload]: ...
+    def list_keys_async(
+        self, *, query: Optional[Mapping[str, str]] = None
+    ) -> Awaitable[Response[KeyListPayload]]: ...
+    def list_key_sessions(
+        self, *, query: Optional[Mapping[str, str]] = None
+    ) -> Response[SessionListPayload]: ...
+    def list_key_sessions_async(
+        self, *, query: Optional[Mapping[str, str]] = None
+    ) -> Awaitable[Response[SessionListPayload]]: ...
+    def get_key(self, id: str) -> Response[KeyPayload]: ...
+    def get_key_async(self, id: str) -> Awaitable[Response[KeyPayload]]: ...
+    def create_key(
+        self,
+        *,
+        label: Optional[str] = None,
+        duration_hours: Optional[int] = None,
+        metadata: Any = None,
+    ) -> Response[KeyPayload]: ...
+    def create_key_async(
+        self,
+        *,
+        label: Optional[str] = None,
+        duration_hours: Optional[int] = None,
+        metadata: Any = None,
+    ) -> Awaitable[Response[KeyPayload]]: ...
+    def update_key(
+        self,
+        id: str,
+        *,
+        label: Optional[str] = None,
+        duration_hours: Optional[int] = None,
+        metadata: Any = None,
+    ) -> Response[KeyPayload]: ...
+    def update_key_async(
+        self,
+        id: str,
+        *,
+        label: Optional[str] = None,
+        duration_hours: Optional[int] = None,
+        metadata: Any = None,
+    ) -> Awaitable[Response[KeyPayload]]: ...
+    def delete_key(self, id: str, *, reason: Optional[str] = None) -> Response[ErrorData]: ...
+    def delete_key_async(
+        self, id: str, *, reason: Optional[str] = None
+    ) -> Awaitable[Response[ErrorData]]: ...
+    def reset_key_hwid(self, id: str) -> Response[ResetHwidData]: ...
+    def reset_key_hwid_async(self, id: str) -> Awaitable[Response[ResetHwidData]]: ...
+    def analytics_summary(self, *, days: Optional[int] = None) -> Response[Any]: ...
+    def analytics_summary_async(
+        self, *, days: Optional[int] = None
+    ) -> Awaitable[Response[Any]]: ...
+    def request(
+        self,
+        method: str,
+        path: str,
+        body: Any = None,
+        *,
+        query: Optional[Mapping[str, str]] = None,
+    ) -> Response[Any]: ...
+    def request_async(
+        self,
+        method: str,
+        path: str,
+        body: Any = None,
+        *,
+        query: Optional[Mapping[str, str]] = None,
+    ) -> Awaitable[Response[Any]]: ...
@@ -0,0 +1,424 @@
+use serde::Serialize;
+use serde_json::{json, Value};
+use std::collections::HashMap;
+use std::fmt;
+use std::future::Future;
+use std::pin::Pin;
+use std::time::Duration;
+
+use crate::analytics::{top_countries, AnalyticsRange, AnalyticsSummary, GeoEntry};
+use crate::{
+    current_timestamp_ms, DashboardRequestOptions, KeySession, NebulAuthDashboardClient,
+    NebulAuthError,
+};
+
+const DEFAULT_COOLDOWN: Duration = Duration::from_secs(15 * 60);
+const WEBHOOK_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// Values the rules are evaluated against, taken at one point in time.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct AlertSnapshot {
+    pub summary: AnalyticsSummary,
+    pub countries: Vec<GeoEntry>,
+    pub active_sessions: usize,
+    pub taken_at_ms: u128,
+}
+
+impl AlertSnapshot {
+    pub async fn fetch(
+        client: &NebulAuthDashboardClient,
+        range: AnalyticsRange,
+        options: DashboardRequestOptions,
+    ) -> Result<Self, NebulAuthError> {
+        let summary = client.analytics_summary_for(range, options.clone()).await?;
+        let countries = client.analytics_geo_for(range, options.clone()).await?;
+        let sessions: Vec<KeySession> = client
+            .list_key_sessions(options)
+            .await?
+            .error_for_status()?
+            .items()?;
+        Ok(Self {
+            summary,
+            countries,
+            active_sessions: sessions.len(),
+            taken_at_ms: current_timestamp_ms(),
+        })
+    }
+
+    fn top_country(&self) -> Option<(String, f64)> {
+        let total: i64 = self.countries.iter().map(|entry| entry.count).sum();
+        let top = top_countries(&self.countries, 1).pop()?;
+        (total > 0).then(|| (top.country, top.count as f64 / total as f64))
+    }
+
+    fn value(&self, metric: &Metric) -> Option<f64> {
+        match metric {
+            Metric::Summary(name) => self.summary.metrics().get(name).copied(),
+            Metric::ActiveSessions => Some(self.active_sessions as f64),
+            Metric::TopCountryShare => self.top_country().map(|(_, share)| share),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum Metric {
+    /// A metric from the analytics summary, by the name `AnalyticsSummary::metrics` uses.
+    Summary(String),
+    ActiveSessions,
+    /// Fraction (0 to 1) of the geo total held by the largest country.
+    TopCountryShare,
+}
+
+impl fmt::Display for Metric {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Metric::Summary(name) => f.write_str(name),
+            Metric::ActiveSessions => f.write_str("active sessions"),
+            Metric::TopCountryShare => f.write_str("top country share"),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum Condition {
+    Above {
+        metric: Metric,
+        threshold: f64,
+    },
+    Below {
+        metric: Metric,
+        threshold: f64,
+    },
+    /// Rose by at least `percent` since the previous snapshot.
+    IncreaseAbove {
+        metric: Metric,
+        percent: f64,
+    },
+    /// Fell by at least `percent` since the previous snapshot.
+    DecreaseAbove {
+        metric: Metric,
+        percent: f64,
+    },
+    /// The largest country differs from the previous snapshot and holds at least `min_share`.
+    TopCountryChanged {
+        min_share: f64,
+    },
+}
+
+impl Condition {
+    fn check(&self, current: &AlertSnapshot, previous: Option<&AlertSnapshot>) -> Option<String> {
+        match self {
+            Condition::Above { metric, threshold } => {
+                let value = current.value(metric)?;
+                (value > *threshold).then(|| format!("{metric} is {value} (above {threshold})"))
+            }
+            Condition::Below { metric, threshold } => {
+                let value = current.value(metric)?;
+                (value < *threshold).then(|| format!("{metric} is {value} (below {threshold})"))
+            }
+            Condition::IncreaseAbove { metric, percent } => {
+                let (now, before) = (current.value(metric)?, previous?.value(metric)?);
+                let change = percent_change(now, before);
+                (change >= *percent)
+                    .then(|| format!("{metric} rose from {before} to {now} ({change:+.1}%)"))
+            }
+            Condition::DecreaseAbove { metric, percent } => {
+                let (now, before) = (current.value(metric)?, previous?.value(metric)?);
+                let change = percent_change(now, before);
+                (-change >= *percent)
+                    .then(|| format!("{metric} fell from {before} to {now} ({change:+.1}%)"))
+            }
+            Condition::TopCountryChanged { min_share } => {
+                let (country, share) = current.top_country()?;
+                let (before, _) = previous?.top_country()?;
+                (country != before && share >= *min_share).then(|| {
+                    format!(
+                        "{country} now leads with {:.0}% of traffic (was {before})",
+                        share * 100.0
+                    )
+                })
+            }
+        }
+    }
+}
+
+fn percent_change(now: f64, before: f64) -> f64 {
+    if before == 0.0 {
+        if now > 0.0 {
+            f64::INFINITY
+        } else {
+            0.0
+        }
+    } else {
+        (now - before) / before.abs() * 100.0
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Severity {
+    Info,
+    Warning,
+    Critical,
+}
+
+impl fmt::Display for Severity {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            Severity::Info => "info",
+            Severity::Warning => "warning",
+            Severity::Critical => "critical",
+        })
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct AlertRule {
+    pub name: String,
+    pub condition: Condition,
+    pub severity: Severity,
+    /// A rule that fired is not sent again within this time, even if it resolved and
+    /// fired again in between.
+    pub cooldown: Duration,
+    /// While a rule keeps firing it is only sent once, unless this is set.
+    pub repeat_interval: Option<Duration>,
+}
+
+impl AlertRule {
+    pub fn new(name: impl Into<String>, condition: Condition, severity: Severity) -> Self {
+        Self {
+            name: name.into(),
+            condition,
+            severity,
+            cooldown: DEFAULT_COOLDOWN,
+            repeat_interval: None,
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct Alert {
+    pub rule: String,
+    pub severity: Severity,
+    pub message: String,
+    pub fired_at_ms: u128,
+}
+
+pub type NotifyFuture<'a> = Pin<Box<dyn Future<Output = Result<(), NebulAuthError>> + Send + 'a>>;
+
+pub trait Notifier: Send + Sync {
+    fn notify<'a>(&'a self, alert: &'a Alert) -> NotifyFuture<'a>;
+}
+
+fn webhook_client() -> Result<reqwest::Client, NebulAuthError> {
+    Ok(reqwest::Client::builder()
+        .timeout(WEBHOOK_TIMEOUT)
+        .build()?)
+}
+
+async fn post_json(
+    client: &reqwest::Client,
+    url: &str,
+    body: &Value,
+) -> Result<(), NebulAuthError> {
+    let response = client.post(url).json(body).send().await?;
+    let status = response.status();
+    if status.is_success() {
+        return Ok(());
+    }
+    Err(NebulAuthError::Api {
+        status_code: status.as_u16(),
+        message: response.text().await.unwrap_or_default(),
+    })
+}
+
+/// Posts each alert as JSON: `{"rule", "severity", "message", "fired_at_ms"}`.
+pub struct WebhookNotifier {
+    url: String,
+    client: reqwest::Client,
+}
+
+impl WebhookNotifier {
+    pub fn new(url: impl Into<String>) -> Result<Self, NebulAuthError> {
+        Ok(Self {
+            url: url.into(),
+            client: webhook_client()?,
+        })
+    }
+}
+
+impl Notifier for WebhookNotifier {
+    fn notify<'a>(&'a self, alert: &'a Alert) -> NotifyFuture<'a> {
+        Box::pin(async move {
+            let body =
+                serde_json::to_value(alert).map_err(|e| NebulAuthError::Config(e.to_string()))?;
+            post_json(&self.client, &self.url, &body).await
+        })
+    }
+}
+
+/// Posts alerts as Discord webhook embeds, coloured by severity.
+pub struct DiscordWebhookNotifier {
+    url: String,
+    username: Option<String>,
+    client: reqwest::Client,
+}
+
+impl DiscordWebhookNotifier {
+    pub fn new(url: impl Into<String>) -> Result<Self, NebulAuthError> {
+        Ok(Self {
+            url: url.into(),
+            username: None,
+            client: webhook_client()?,
+        })
+    }
+
+    pub fn with_username(mut self, username: impl Into<String>) -> Self {
+        self.username = Some(username.into());
+        self
+    }
+
+    fn payload(&self, alert: &Alert) -> Value {
+        let color = match alert.severity {
+            Severity::Info => 0x3498db,
+            Severity::Warning => 0xf39c12,
+            Severity::Critical => 0xe74c3c,
+        };
+        let mut payload = json!({
+            "embeds": [{
+                "title": format!("[{}] {}", alert.severity, alert.rule),
+                "description": alert.message,
+                "color": color,
+            }]
+        });
+        if let Some(username) = &self.username {
+            payload["username"] = Value::String(username.clone());
+        }
+        payload
+    }
+}
+
+impl Notifier for DiscordWebhookNotifier {
+    fn notify<'a>(&'a self, alert: &'a Alert) -> NotifyFuture<'a> {
+        Box::pin(async move { post_json(&self.client, &self.url, &self.payload(alert)).await })
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct AlertDelivery {
+    pub alert: Alert,
+    /// One message per notifier that failed.
+    pub failures: Vec<String>,
+}
+
+#[derive(Debug, Default)]
+struct RuleState {
+    firing: bool,
+    last_sent_ms: Option<u128>,
+}
+
+pub struct AlertEngine {
+    rules: Vec<AlertRule>,
+    notifiers: Vec<Box<dyn Notifier>>,
+    range: AnalyticsRange,
+    previous: Option<AlertSnapshot>,
+    state: HashMap<String, RuleState>,
+}
+
+impl AlertEngine {
+    pub fn new(rules: Vec<AlertRule>, range: AnalyticsRange) -> Self {
+        Self {
+            rules,
+            notifiers: Vec::new(),
+            range,
+            previous: None,
+            state: HashMap::new(),
+        }
+    }
+
+    pub fn with_notifier(mut self, notifier: impl Notifier + 'static) -> Self {
+        self.notifiers.push(Box::new(notifier));
+        self
+    }
+
+    /// Checks every rule against `snapshot` (and the previous one for rate-of-change
+    /// conditions) and returns the alerts that pass dedup and cooldown.
+    pub fn evaluate(&mut self, snapshot: AlertSnapshot) -> Vec<Alert> {
+        let now = snapshot.taken_at_ms;
+        let mut alerts = Vec::new();
+        for rule in &self.rules {
+            let state = self.state.entry(rule.name.clone()).or_default();
+            let Some(message) = rule.condition.check(&snapshot, self.previous.as_ref()) else {
+                state.firing = false;
+                continue;
+            };
+
+            let since_sent = state
+                .last_sent_ms
+                .map(|sent| Duration::from_millis(now.saturating_sub(sent) as u64));
+            let send = match since_sent {
+                None => true,
+                Some(elapsed) if state.firing => rule
+                    .repeat_interval
+                    .is_some_and(|repeat| elapsed >= repeat.max(rule.cooldown)),
+                Some(elapsed) => elapsed >= rule.cooldown,
+            };
+            state.firing = true;
+            if send {
+                state.last_sent_ms = Some(now);
+                alerts.push(Alert {
+                    rule: rule.name.clone(),
+                    severity: rule.severity,
+                    message,
+                    fired_at_ms: now,
+                });
+            }
+        }
+        self.previous = Some(snapshot);
+        alerts
+    }
+
+    pub async fn notify(&self, alerts: Vec<Alert>) -> Vec<AlertDelivery> {
+        let mut deliveries = Vec::with_capacity(alerts.len());
+        for alert in alerts {
+            let mut failures = Vec::new();
+            for notifier in &self.notifiers {
+                if let Err(e) = notifier.notify(&alert).await {
+                    failures.push(e.to_string());
+                }
+            }
+            deliveries.push(AlertDelivery { alert, failures });
+        }
+        deliveries
+    }
+
+    /// Fetches a snapshot, evaluates the rules and sends the resulting alerts.
+    pub async fn run_once(
+        &mut self,
+        client: &NebulAuthDashboardClient,
+        options: DashboardRequestOptions,
+    ) -> Result<Vec<AlertDelivery>, NebulAuthError> {
+        let snapshot = AlertSnapshot::fetch(client, self.range, options).await?;
+        let alerts = self.evaluate(snapshot);
+        Ok(self.notify(alerts).await)
+    }
+
+    /// Calls `run_once` every `interval` forever. Fetch errors go to `on_error` and the
+    /// next tick is tried as usual.
+    pub async fn run(
+        &mut self,
+        client: &NebulAuthDashboardClient,
+        interval: Duration,
+        options: DashboardRequestOptions,
+        mut on_error: impl FnMut(NebulAuthError),
+    ) {
+        let mut ticker = tokio::time::interval(interval);
+        ticker.set_missed_tick_behavior(tokio::time::MissedTickBehavior::Delay);
+        loop {
+            ticker.tick().await;
+            if let Err(e) = self.run_once(client, options.clone()).await {
+                on_error(e);
+            }
+        }
+    }
+}
@@ -0,0 +1,328 @@
+use chrono::{DateTime, Days, NaiveDate, Utc};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::collections::BTreeMap;
+use std::num::NonZeroU32;
+
+use crate::{
+    current_timestamp_ms, DashboardRequestOptions, DashboardResponse, NebulAuthDashboardClient,
+    NebulAuthError,
+};
+
+/// The last `n` days (at least one) for analytics requests, sent as the documented `days`
+/// parameter. The API has no parameters for explicit date ranges yet.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct AnalyticsRange(NonZeroU32);
+
+impl AnalyticsRange {
+    pub fn days(days: u32) -> Result<Self, NebulAuthError> {
+        NonZeroU32::new(days).map(Self).ok_or_else(|| {
+            NebulAuthError::Config("analytics range must be at least one day".to_string())
+        })
+    }
+
+    pub fn get(&self) -> u32 {
+        self.0.get()
+    }
+
+    fn apply(&self, options: &mut DashboardRequestOptions) {
+        options.query.insert("days".to_string(), self.0.to_string());
+    }
+
+    /// Explicit dates for this range, counting back from `today`.
+    pub fn dates(&self, today: NaiveDate) -> (NaiveDate, NaiveDate) {
+        (today - Days::new(u64::from(self.get()) - 1), today)
+    }
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct AnalyticsSummary {
+    #[serde(alias = "totalKeys")]
+    pub total_keys: Option<i64>,
+    #[serde(alias = "activeKeys")]
+    pub active_keys: Option<i64>,
+    #[serde(alias = "revokedKeys")]
+    pub revoked_keys: Option<i64>,
+    #[serde(alias = "expiredKeys")]
+    pub expired_keys: Option<i64>,
+    #[serde(alias = "activeSessions")]
+    pub active_sessions: Option<i64>,
+    pub verifications: Option<i64>,
+    pub redemptions: Option<i64>,
+    /// Metrics the SDK doesn't model yet.
+    #[serde(flatten)]
+    pub extra: BTreeMap<String, Value>,
+}
+
+impl AnalyticsSummary {
+    /// Every numeric metric by name, including unmodelled ones.
+    pub fn metrics(&self) -> BTreeMap<String, f64> {
+        let mut metrics: BTreeMap<String, f64> = self
+            .extra
+            .iter()
+            .filter_map(|(name, value)| Some((name.clone(), value.as_f64()?)))
+            .collect();
+        for (name, value) in [
+            ("total_keys", self.total_keys),
+            ("active_keys", self.active_keys),
+            ("revoked_keys", self.revoked_keys),
+            ("expired_keys", self.expired_keys),
+            ("active_sessions", self.active_sessions),
+            ("verifications", self.verifications),
+            ("redemptions", self.redemptions),
+        ] {
+            if let Some(value) = value {
+                metrics.insert(name.to_string(), value as f64);
+            }
+        }
+        metrics
+    }
+
+    /// Counters for the window `longer` covers beyond `self`, e.g. the 7 days before the
+    /// last 7 from a 14-day and a 7-day summary. Point-in-time counts like `active_keys`
+    /// can't be split this way and are left empty.
+    pub fn earlier_counters(&self, longer: &AnalyticsSummary) -> AnalyticsSummary {
+        let earlier = |current: Option<i64>, longer: Option<i64>| Some(longer? - current?);
+        AnalyticsSummary {
+            verifications: earlier(self.verifications, longer.verifications),
+            redemptions: earlier(self.redemptions, longer.redemptions),
+            ..Default::default()
+        }
+    }
+
+    /// Per-metric change from `previous`; metrics missing on either side are skipped.
+    pub fn compare(&self, previous: &AnalyticsSummary) -> BTreeMap<String, MetricDelta> {
+        let before = previous.metrics();
+        self.metrics()
+            .into_iter()
+            .filter_map(|(name, current)| {
+                let previous = *before.get(&name)?;
+                Some((name, MetricDelta::new(current, previous)))
+            })
+            .collect()
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct MetricDelta {
+    pub current: f64,
+    pub previous: f64,
+    pub change: f64,
+    /// `None` when the previous value is zero.
+    pub percent: Option<f64>,
+}
+
+impl MetricDelta {
+    pub fn new(current: f64, previous: f64) -> Self {
+        let change = current - previous;
+        Self {
+            current,
+            previous,
+            change,
+            percent: (previous != 0.0).then(|| change / previous * 100.0),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct GeoEntry {
+    #[serde(alias = "country_code", alias = "countryCode", alias = "code")]
+    pub country: String,
+    #[serde(
+        alias = "sessions",
+        alias = "requests",
+        alias = "total",
+        alias = "value"
+    )]
+    pub count: i64,
+}
+
+/// Reads either a list of entries or a `{ "US": 10, ... }` map.
+pub fn parse_geo(response: &DashboardResponse) -> Result<Vec<GeoEntry>, NebulAuthError> {
+    if let Ok(entries) = response.items::<GeoEntry>() {
+        return Ok(entries);
+    }
+    let map = match &response.data {
+        Value::Object(map) => match ["countries", "geo", "data"]
+            .iter()
+            .find_map(|field| map.get(*field)?.as_object())
+        {
+            Some(inner) => inner,
+            None => map,
+        },
+        _ => {
+            return Err(NebulAuthError::Decode(
+                "unexpected geo response".to_string(),
+            ))
+        }
+    };
+    map.iter()
+        .map(|(country, count)| {
+            Ok(GeoEntry {
+                country: country.clone(),
+                count: count.as_i64().ok_or_else(|| {
+                    NebulAuthError::Decode(format!("non-numeric geo count for {country}"))
+                })?,
+            })
+        })
+        .collect()
+}
+
+/// Largest countries first; ties are ordered by country code.
+pub fn top_countries(entries: &[GeoEntry], n: usize) -> Vec<GeoEntry> {
+    let mut merged: BTreeMap<String, i64> = BTreeMap::new();
+    for entry in entries {
+        *merged.entry(entry.country.to_uppercase()).or_default() += entry.count;
+    }
+    let mut sorted: Vec<GeoEntry> = merged
+        .into_iter()
+        .map(|(country, count)| GeoEntry { country, count })
+        .collect();
+    sorted.sort_by(|a, b| {
+        b.count
+            .cmp(&a.count)
+            .then_with(|| a.country.cmp(&b.country))
+    });
+    sorted.truncate(n);
+    sorted
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ActivityPoint {
+    #[serde(alias = "date", alias = "time", alias = "bucket")]
+    pub timestamp: String,
+    pub verifications: Option<i64>,
+    pub redemptions: Option<i64>,
+    pub sessions: Option<i64>,
+    #[serde(alias = "newKeys")]
+    pub new_keys: Option<i64>,
+}
+
+impl ActivityPoint {
+    pub fn date(&self) -> Option<NaiveDate> {
+        DateTime::parse_from_rfc3339(&self.timestamp)
+            .map(|timestamp| timestamp.with_timezone(&Utc).date_naive())
+            .or_else(|_| NaiveDate::parse_from_str(&self.timestamp, "%Y-%m-%d"))
+            .ok()
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct DailyActivity {
+    pub date: NaiveDate,
+    pub verifications: i64,
+    pub redemptions: i64,
+    pub sessions: i64,
+    pub new_keys: i64,
+}
+
+/// Sums activity points per UTC day, oldest first. Points without a readable date are dropped.
+pub fn daily_rollup(points: &[ActivityPoint]) -> Vec<DailyActivity> {
+    let mut days: BTreeMap<NaiveDate, DailyActivity> = BTreeMap::new();
+    for point in points {
+        let Some(date) = point.date() else {
+            continue;
+        };
+        let day = days.entry(date).or_insert(DailyActivity {
+            date,
+            verifications: 0,
+            redemptions: 0,
+            sessions: 0,
+            new_keys: 0,
+        });
+        day.verifications += point.verifications.unwrap_or(0);
+        day.redemptions += point.redemptions.unwrap_or(0);
+        day.sessions += point.sessions.unwrap_or(0);
+        day.new_keys += point.new_keys.unwrap_or(0);
+    }
+    days.into_values().collect()
+}
+
+/// `previous` only holds counters; see `compare_analytics_periods`.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct PeriodComparison {
+    pub current: AnalyticsSummary,
+    pub previous: AnalyticsSummary,
+    pub deltas: BTreeMap<String, MetricDelta>,
+}
+
+fn today() -> NaiveDate {
+    DateTime::from_timestamp_millis(current_timestamp_ms() as i64)
+        .unwrap_or_default()
+        .date_naive()
+}
+
+impl NebulAuthDashboardClient {
+    pub async fn analytics_summary_for(
+        &self,
+        range: AnalyticsRange,
+        mut options: DashboardRequestOptions,
+    ) -> Result<AnalyticsSummary, NebulAuthError> {
+        range.apply(&mut options);
+        let response = self
+            .request("GET", "/analytics/summary", None, options)
+            .await?
+            .error_for_status()?;
+        response.json_unwrapped("summary")
+    }
+
+    pub async fn analytics_geo_for(
+        &self,
+        range: AnalyticsRange,
+        mut options: DashboardRequestOptions,
+    ) -> Result<Vec<GeoEntry>, NebulAuthError> {
+        range.apply(&mut options);
+        let response = self
+            .request("GET", "/analytics/geo", None, options)
+            .await?
+            .error_for_status()?;
+        parse_geo(&response)
+    }
+
+    /// The activity endpoint takes no range parameters, so `range` is applied to the returned
+    /// points; points without a readable date are dropped when a range is given.
+    pub async fn analytics_activity_for(
+        &self,
+        range: Option<AnalyticsRange>,
+        options: DashboardRequestOptions,
+    ) -> Result<Vec<ActivityPoint>, NebulAuthError> {
+        let mut points: Vec<ActivityPoint> = self
+            .request("GET", "/analytics/activity", None, options)
+            .await?
+            .error_for_status()?
+            .items()?;
+        if let Some(range) = range {
+            let (from, to) = range.dates(today());
+            points.retain(|point| point.date().is_some_and(|date| from <= date && date <= to));
+        }
+        Ok(points)
+    }
+
+    /// Compares `range` with the equally long period before it. The previous period is a
+    /// `days` request for twice the length minus the current one, which is only correct for
+    /// counters, so only `verifications` and `redemptions` get deltas. Gauges (key counts,
+    /// `active_sessions`), unmodelled metrics in `extra` and top-N data like geo counts are
+    /// not compared.
+    pub async fn compare_analytics_periods(
+        &self,
+        range: AnalyticsRange,
+        options: DashboardRequestOptions,
+    ) -> Result<PeriodComparison, NebulAuthError> {
+        let current = self.analytics_summary_for(range, options.clone()).await?;
+        let doubled = range.get().checked_mul(2).ok_or_else(|| {
+            NebulAuthError::Config("analytics range is too long to compare".to_string())
+        })?;
+        let both = self
+            .analytics_summary_for(AnalyticsRange::days(doubled)?, options)
+            .await?;
+        let previous = current.earlier_counters(&both);
+        let deltas = current.compare(&previous);
+        Ok(PeriodComparison {
+            current,
+            previous,
+            deltas,
+        })
+    }
+}
@@ -0,0 +1,392 @@
+use serde::{Deserialize, Serialize};
+use serde_json::{Map, Value};
+use std::fmt;
+use std::fs::OpenOptions;
+use std::io::{BufRead, BufReader, Write};
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex};
+
+use hmac::{Hmac, Mac};
+use sha2::Sha256;
+
+use crate::{current_timestamp_ms, hex_lower, sha256_hex, NebulAuthError};
+
+/// `prev_hash` of the first record in a log.
+pub const GENESIS_HASH: &str = "0000000000000000000000000000000000000000000000000000000000000000";
+pub(crate) const REDACTED: &str = "[redacted]";
+
+/// Body fields replaced with `[redacted]`, compared case-insensitively without `_`/`-`.
+const SECRET_FIELDS: &[&str] = &[
+    "password",
+    "token",
+    "accesstoken",
+    "bearertoken",
+    "secret",
+    "signingsecret",
+    "popkey",
+    "sessioncookie",
+];
+/// License key fields, replaced with a short SHA-256 fingerprint so records can still be
+/// matched to a key without storing it.
+const KEY_FIELDS: &[&str] = &["key", "licensekey"];
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct AuditRecord {
+    pub seq: u64,
+    pub timestamp_ms: u128,
+    pub actor: String,
+    /// `dashboard` or `runtime`.
+    pub client: String,
+    pub method: String,
+    pub path: String,
+    pub body: Value,
+    pub status: Option<u16>,
+    pub error: Option<String>,
+    pub prev_hash: String,
+    pub hash: String,
+}
+
+impl AuditRecord {
+    /// SHA-256 over the record serialized with an empty `hash`.
+    pub fn compute_hash(&self) -> Result<String, NebulAuthError> {
+        self.chain_hash(None)
+    }
+
+    /// HMAC-SHA256 keyed with `key` over the same serialization as
+    /// [`compute_hash`](AuditRecord::compute_hash).
+    pub fn compute_keyed_hash(&self, key: &[u8]) -> Result<String, NebulAuthError> {
+        self.chain_hash(Some(key))
+    }
+
+    fn chain_hash(&self, key: Option<&[u8]>) -> Result<String, NebulAuthError> {
+        let unhashed = AuditRecord {
+            hash: String::new(),
+            ..self.clone()
+        };
+        let line = serde_json::to_string(&unhashed).map_err(|e| {
+            NebulAuthError::Config(format!("failed to serialize audit record: {e}"))
+        })?;
+        let Some(key) = key else {
+            return Ok(sha256_hex(&line));
+        };
+        let mut mac = Hmac::<Sha256>::new_from_slice(key)
+            .map_err(|e| NebulAuthError::Crypto(format!("invalid audit chain key: {e}")))?;
+        mac.update(line.as_bytes());
+        Ok(hex_lower(&mac.finalize().into_bytes()))
+    }
+}
+
+fn normalize(name: &str) -> String {
+    name.chars()
+        .filter(|c| *c != '_' && *c != '-')
+        .flat_map(char::to_lowercase)
+        .collect()
+}
+
+/// Copy of `body` with secrets removed and license keys fingerprinted.
+pub fn redact(body: &Value) -> Value {
+    match body {
+        Value::Object(map) => Value::Object(
+            map.iter()
+                .map(|(name, value)| {
+                    let field = normalize(name);
+                    let value = match value {
+                        Value::Null => Value::Null,
+                        _ if SECRET_FIELDS.contains(&field.as_str()) => {
+                            Value::String(REDACTED.to_string())
+                        }
+                        Value::String(key) if KEY_FIELDS.contains(&field.as_str()) => {
+                            Value::String(format!("sha256:{}", &sha256_hex(key)[..16]))
+                        }
+                        other => redact(other),
+                    };
+                    (name.clone(), value)
+                })
+                .collect::<Map<String, Value>>(),
+        ),
+        Value::Array(items) => Value::Array(items.iter().map(redact).collect()),
+        other => other.clone(),
+    }
+}
+
+struct ChainHead {
+    seq: u64,
+    hash: String,
+}
+
+type ErrorHandler = Arc<dyn Fn(&NebulAuthError) + Send + Sync>;
+
+/// Append-only JSONL audit log. Each record carries the hash of the previous one, so editing,
+/// reordering or removing a record breaks the chain from that point on (see [`verify`]).
+///
+/// By default the hashes are plain SHA-256, which anyone who can write the file can recompute
+/// after rewriting it. The chain then only proves integrity up to a `last_hash` kept somewhere
+/// the writer can't reach (pass it to [`verify_with`] as `expected_head`). With
+/// [`AuditLog::with_chain_key`] the hashes are HMACs, and rewriting needs the key as well.
+///
+/// The chain is continued from the last record when an existing file is opened. Only one
+/// `AuditLog` (one process) should write to a file at a time.
+///
+/// Clients record requests after they complete. A record that can't be written doesn't fail
+/// the request (it has already happened); the error goes to the handler set with
+/// [`AuditLog::with_error_handler`] and, with the `tracing` feature, to a warning event.
+pub struct AuditLog {
+    path: PathBuf,
+    actor: String,
+    head: Mutex<ChainHead>,
+    chain_key: Option<Vec<u8>>,
+    on_error: Option<ErrorHandler>,
+}
+
+impl fmt::Debug for AuditLog {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("AuditLog")
+            .field("path", &self.path)
+            .field("actor", &self.actor)
+            .field("keyed", &self.chain_key.is_some())
+            .finish()
+    }
+}
+
+impl AuditLog {
+    pub fn open(
+        path: impl Into<PathBuf>,
+        actor: impl Into<String>,
+    ) -> Result<Self, NebulAuthError> {
+        let path = path.into();
+        let head = match std::fs::File::open(&path) {
+            Ok(file) => {
+                let mut head = ChainHead {
+                    seq: 0,
+                    hash: GENESIS_HASH.to_string(),
+                };
+                for line in BufReader::new(file).lines() {
+                    let line = line?;
+                    if line.trim().is_empty() {
+                        continue;
+                    }
+                    let record: AuditRecord = serde_json::from_str(&line).map_err(|e| {
+                        NebulAuthError::Decode(format!("invalid audit record: {e}"))
+                    })?;
+                    head = ChainHead {
+                        seq: record.seq,
+                        hash: record.hash,
+                    };
+                }
+                head
+            }
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => ChainHead {
+                seq: 0,
+                hash: GENESIS_HASH.to_string(),
+            },
+            Err(e) => return Err(e.into()),
+        };
+
+        Ok(Self {
+            path,
+            actor: actor.into(),
+            head: Mutex::new(head),
+            chain_key: None,
+            on_error: None,
+        })
+    }
+
+    /// Chains records with HMAC-SHA256 under `key` instead of plain SHA-256. The same key has
+    /// to be given to [`verify_with`].
+    pub fn with_chain_key(mut self, key: impl Into<Vec<u8>>) -> Self {
+        self.chain_key = Some(key.into());
+        self
+    }
+
+    /// Called with every error a client hits while recording a completed request.
+    pub fn with_error_handler(
+        mut self,
+        handler: impl Fn(&NebulAuthError) + Send + Sync + 'static,
+    ) -> Self {
+        self.on_error = Some(Arc::new(handler));
+        self
+    }
+
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    pub fn actor(&self) -> &str {
+        &self.actor
+    }
+
+    /// Appends one record for a request that was sent (`Ok(status)`) or failed to complete.
+    pub fn record(
+        &self,
+        client: &str,
+        method: &str,
+        path: &str,
+        body: Option<&Value>,
+        result: Result<u16, &NebulAuthError>,
+    ) -> Result<AuditRecord, NebulAuthError> {
+        let mut head = self.head.lock().unwrap_or_else(|e| e.into_inner());
+        let mut record = AuditRecord {
+            seq: head.seq + 1,
+            timestamp_ms: current_timestamp_ms(),
+            actor: self.actor.clone(),
+            client: client.to_string(),
+            method: method.to_uppercase(),
+            path: path.to_string(),
+            body: body.map(redact).unwrap_or(Value::Null),
+            status: result.as_ref().ok().copied(),
+            error: result.err().map(|e| e.to_string()),
+            prev_hash: head.hash.clone(),
+            hash: String::new(),
+        };
+        record.hash = record.chain_hash(self.chain_key.as_deref())?;
+
+        let line = serde_json::to_string(&record).map_err(|e| {
+            NebulAuthError::Config(format!("failed to serialize audit record: {e}"))
+        })?;
+        let mut file = OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(&self.path)?;
+        writeln!(file, "{line}")?;
+
+        head.seq = record.seq;
+        head.hash = record.hash.clone();
+        Ok(record)
+    }
+
+    /// [`AuditLog::record`] for a request that already completed: failures are reported
+    /// instead of returned.
+    pub(crate) fn record_completed(
+        &self,
+        client: &str,
+        method: &str,
+        path: &str,
+        body: Option<&Value>,
+        result: Result<u16, &NebulAuthError>,
+    ) {
+        let Err(error) = self.record(client, method, path, body, result) else {
+            return;
+        };
+        #[cfg(feature = "tracing")]
+        tracing::warn!(%error, method, path, "failed to write nebulauth audit record");
+        if let Some(on_error) = &self.on_error {
+            on_error(&error);
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ChainBreak {
+    /// 1-based line number in the log file.
+    pub line: usize,
+    pub reason: String,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct AuditVerification {
+    pub records: usize,
+    /// Hash of the last valid record. Keeping it elsewhere also detects truncation.
+    pub last_hash: String,
+    pub problem: Option<ChainBreak>,
+}
+
+impl AuditVerification {
+    pub fn is_intact(&self) -> bool {
+        self.problem.is_none()
+    }
+}
+
+impl fmt::Display for AuditVerification {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match &self.problem {
+            None => write!(
+                f,
+                "ok: {} records, last hash {}",
+                self.records, self.last_hash
+            ),
+            Some(problem) => write!(
+                f,
+                "broken at line {}: {} ({} valid records before it)",
+                problem.line, problem.reason, self.records
+            ),
+        }
+    }
+}
+
+#[derive(Clone, Default)]
+pub struct VerifyOptions {
+    /// Key the log was written with ([`AuditLog::with_chain_key`]).
+    pub chain_key: Option<Vec<u8>>,
+    /// A `last_hash` from an earlier verification, stored outside the log. The chain has to
+    /// still contain it, which catches a log that was rewritten or cut short since.
+    pub expected_head: Option<String>,
+}
+
+/// [`verify_with`] for an unkeyed log and no expected head.
+pub fn verify(path: &Path) -> Result<AuditVerification, NebulAuthError> {
+    verify_with(path, &VerifyOptions::default())
+}
+
+/// Walks the chain and stops at the first record that doesn't link to or hash like the one
+/// before it.
+pub fn verify_with(
+    path: &Path,
+    options: &VerifyOptions,
+) -> Result<AuditVerification, NebulAuthError> {
+    let file = std::fs::File::open(path)?;
+    let mut verification = AuditVerification {
+        records: 0,
+        last_hash: GENESIS_HASH.to_string(),
+        problem: None,
+    };
+    let mut seq = 0;
+    let mut lines = 0;
+    let mut head_seen = options
+        .expected_head
+        .as_ref()
+        .is_none_or(|head| head == GENESIS_HASH);
+
+    for (index, line) in BufReader::new(file).lines().enumerate() {
+        let line = line?;
+        lines = index + 1;
+        if line.trim().is_empty() {
+            continue;
+        }
+        let reason = match serde_json::from_str::<AuditRecord>(&line) {
+            Err(e) => Some(format!("unreadable record: {e}")),
+            Ok(record) if record.seq != seq + 1 => Some(format!(
+                "expected sequence {} but found {}",
+                seq + 1,
+                record.seq
+            )),
+            Ok(record) if record.prev_hash != verification.last_hash => {
+                Some("previous hash does not match".to_string())
+            }
+            Ok(record) if record.chain_hash(options.chain_key.as_deref())? != record.hash => {
+                Some("record hash does not match its contents".to_string())
+            }
+            Ok(record) => {
+                seq = record.seq;
+                verification.records += 1;
+                head_seen |= options.expected_head.as_ref() == Some(&record.hash);
+                verification.last_hash = record.hash;
+                None
+            }
+        };
+        if let Some(reason) = reason {
+            verification.problem = Some(ChainBreak {
+                line: index + 1,
+                reason,
+            });
+            break;
+        }
+    }
+
+    if verification.problem.is_none() && !head_seen {
+        verification.problem = Some(ChainBreak {
+            line: lines + 1,
+            reason: "expected head hash is not in the chain".to_string(),
+        });
+    }
+    Ok(verification)
+}
@@ -0,0 +1,655 @@
+use chrono::DateTime;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::collections::HashMap;
+use std::fmt;
+use std::path::Path;
+
+use crate::enums::same_scopes;
+use crate::{
+    current_timestamp_ms, ApiAuthMode, ApiToken, ApiTokenCreateRequest, BlacklistCreateRequest,
+    BlacklistEntry, Checkpoint, CheckpointCreateRequest, CheckpointStepInput, Customer,
+    CustomerUpdateRequest, DashboardKey, DashboardRequestOptions, DashboardResponse,
+    KeyCreateRequest, NebulAuthDashboardClient, NebulAuthError, ReplayProtectionMode, TeamMember,
+    TeamMemberCreateRequest, TeamRole,
+};
+
+pub const SNAPSHOT_VERSION: u32 = 1;
+const RESTORED_FROM_FIELD: &str = "restored_from";
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Snapshot {
+    pub version: u32,
+    pub created_at_ms: u64,
+    pub customer: Value,
+    pub users: Vec<Value>,
+    pub keys: Vec<Value>,
+    pub checkpoints: Vec<Value>,
+    pub blacklist: Vec<Value>,
+    pub api_tokens: Vec<Value>,
+}
+
+impl Snapshot {
+    pub fn load(path: impl AsRef<Path>) -> Result<Self, NebulAuthError> {
+        let contents = std::fs::read_to_string(path)?;
+        let snapshot: Snapshot = serde_json::from_str(&contents)
+            .map_err(|e| NebulAuthError::Decode(format!("invalid snapshot: {e}")))?;
+        if snapshot.version > SNAPSHOT_VERSION {
+            return Err(NebulAuthError::Config(format!(
+                "snapshot version {} is newer than supported version {SNAPSHOT_VERSION}",
+                snapshot.version
+            )));
+        }
+        Ok(snapshot)
+    }
+
+    pub fn save(&self, path: impl AsRef<Path>) -> Result<(), NebulAuthError> {
+        let contents = serde_json::to_string_pretty(self)
+            .map_err(|e| NebulAuthError::Config(format!("failed to serialize snapshot: {e}")))?;
+        // Snapshots hold customer data and token metadata; keep them private like the session file.
+        crate::dashboard::write_private_file(path.as_ref(), contents.as_bytes())
+    }
+}
+
+pub async fn backup(
+    client: &NebulAuthDashboardClient,
+    options: DashboardRequestOptions,
+) -> Result<Snapshot, NebulAuthError> {
+    let customer = client
+        .get_customer(options.clone())
+        .await?
+        .error_for_status()?;
+
+    Ok(Snapshot {
+        version: SNAPSHOT_VERSION,
+        created_at_ms: current_timestamp_ms() as u64,
+        customer: customer.json_unwrapped("customer")?,
+        users: client
+            .list_users(options.clone())
+            .await?
+            .error_for_status()?
+            .items()?,
+        keys: client
+            .list_keys(options.clone())
+            .await?
+            .error_for_status()?
+            .items()?,
+        checkpoints: client
+            .list_checkpoints(options.clone())
+            .await?
+            .error_for_status()?
+            .items()?,
+        blacklist: client
+            .list_blacklist(options.clone())
+            .await?
+            .error_for_status()?
+            .items()?,
+        api_tokens: client
+            .list_api_tokens(options)
+            .await?
+            .error_for_status()?
+            .items()?,
+    })
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ResourceKind {
+    Customer,
+    User,
+    Key,
+    Checkpoint,
+    BlacklistEntry,
+    ApiToken,
+}
+
+impl fmt::Display for ResourceKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            ResourceKind::Customer => "customer",
+            ResourceKind::User => "user",
+            ResourceKind::Key => "key",
+            ResourceKind::Checkpoint => "checkpoint",
+            ResourceKind::BlacklistEntry => "blacklist entry",
+            ResourceKind::ApiToken => "api token",
+        })
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "action", content = "detail", rename_all = "snake_case")]
+pub enum RestoreAction {
+    Exists,
+    WouldCreate,
+    Created,
+    WouldUpdate,
+    Updated,
+    Skipped(String),
+    Failed(String),
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RestoreItem {
+    pub kind: ResourceKind,
+    pub source_id: String,
+    pub target_id: Option<String>,
+    pub action: RestoreAction,
+}
+
+impl fmt::Display for RestoreItem {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} {}: ", self.kind, self.source_id)?;
+        match &self.action {
+            RestoreAction::Exists => write!(f, "exists")?,
+            RestoreAction::WouldCreate => write!(f, "would create")?,
+            RestoreAction::Created => write!(f, "created")?,
+            RestoreAction::WouldUpdate => write!(f, "would update")?,
+            RestoreAction::Updated => write!(f, "updated")?,
+            RestoreAction::Skipped(reason) => write!(f, "skipped ({reason})")?,
+            RestoreAction::Failed(reason) => write!(f, "failed ({reason})")?,
+        }
+        match &self.target_id {
+            Some(target_id) if target_id != &self.source_id => write!(f, " -> {target_id}"),
+            _ => Ok(()),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct RestoreOptions {
+    pub dry_run: bool,
+    pub user_password: Option<String>,
+}
+
+/// An API token minted by a restore. `data` is the create response, which holds the new
+/// token's secret; it is not shown again.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RestoredApiToken {
+    pub source_id: String,
+    pub data: Value,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct RestoreReport {
+    pub dry_run: bool,
+    pub items: Vec<RestoreItem>,
+    /// Snapshot ID to target ID. Recreated keys come with new key strings (the old ones
+    /// aren't kept in a snapshot), so this is how end users get matched to their new keys.
+    pub id_map: HashMap<String, String>,
+    pub api_tokens: Vec<RestoredApiToken>,
+}
+
+impl RestoreReport {
+    pub fn failed(&self) -> impl Iterator<Item = &RestoreItem> {
+        self.items
+            .iter()
+            .filter(|item| matches!(item.action, RestoreAction::Failed(_)))
+    }
+
+    fn record(
+        &mut self,
+        kind: ResourceKind,
+        source_id: &str,
+        target_id: Option<String>,
+        action: RestoreAction,
+    ) {
+        if let Some(target_id) = &target_id {
+            self.id_map.insert(source_id.to_string(), target_id.clone());
+        }
+        self.items.push(RestoreItem {
+            kind,
+            source_id: source_id.to_string(),
+            target_id,
+            action,
+        });
+    }
+}
+
+pub async fn restore(
+    client: &NebulAuthDashboardClient,
+    snapshot: &Snapshot,
+    restore_options: RestoreOptions,
+    options: DashboardRequestOptions,
+) -> Result<RestoreReport, NebulAuthError> {
+    let mut restorer = Restorer {
+        client,
+        dry_run: restore_options.dry_run,
+        options,
+        report: RestoreReport {
+            dry_run: restore_options.dry_run,
+            ..Default::default()
+        },
+    };
+
+    restorer.restore_customer(&snapshot.customer).await?;
+    restorer.restore_checkpoints(&snapshot.checkpoints).await?;
+    restorer.restore_blacklist(&snapshot.blacklist).await?;
+    restorer.restore_keys(&snapshot.keys).await?;
+    restorer
+        .restore_users(&snapshot.users, restore_options.user_password)
+        .await?;
+    restorer.restore_api_tokens(&snapshot.api_tokens).await?;
+
+    Ok(restorer.report)
+}
+
+struct Restorer<'a> {
+    client: &'a NebulAuthDashboardClient,
+    dry_run: bool,
+    options: DashboardRequestOptions,
+    report: RestoreReport,
+}
+
+impl Restorer<'_> {
+    async fn restore_customer(&mut self, customer: &Value) -> Result<(), NebulAuthError> {
+        let saved: Customer = decode(customer)?;
+        let live: Customer = self
+            .client
+            .get_customer(self.options.clone())
+            .await?
+            .error_for_status()?
+            .json_unwrapped("customer")?;
+
+        let update = CustomerUpdateRequest {
+            require_discord_redeem: saved
+                .require_discord_redeem
+                .filter(|v| live.require_discord_redeem != Some(*v)),
+            require_hwid: saved.require_hwid.filter(|v| live.require_hwid != Some(*v)),
+            paused: saved.paused.filter(|v| live.paused != Some(*v)),
+        };
+        let source_id = saved.id.unwrap_or_else(|| "customer".to_string());
+
+        if update.require_discord_redeem.is_none()
+            && update.require_hwid.is_none()
+            && update.paused.is_none()
+        {
+            self.report.record(
+                ResourceKind::Customer,
+                &source_id,
+                None,
+                RestoreAction::Exists,
+            );
+        } else if self.dry_run {
+            self.report.record(
+                ResourceKind::Customer,
+                &source_id,
+                None,
+                RestoreAction::WouldUpdate,
+            );
+        } else {
+            let result = self
+                .client
+                .update_customer(update, self.options.clone())
+                .await;
+            let action = match check(result) {
+                Ok(_) => RestoreAction::Updated,
+                Err(e) => RestoreAction::Failed(e.to_string()),
+            };
+            self.report
+                .record(ResourceKind::Customer, &source_id, None, action);
+        }
+        Ok(())
+    }
+
+    async fn restore_checkpoints(&mut self, checkpoints: &[Value]) -> Result<(), NebulAuthError> {
+        let live: Vec<Checkpoint> = self
+            .client
+            .list_checkpoints(self.options.clone())
+            .await?
+            .error_for_status()?
+            .items()?;
+        let live_by_name: HashMap<&str, &str> = live
+            .iter()
+            .map(|c| (c.name.as_str(), c.id.as_str()))
+            .collect();
+
+        for raw in checkpoints {
+            let checkpoint: Checkpoint = decode(raw)?;
+            if let Some(target_id) = live_by_name.get(checkpoint.name.as_str()) {
+                self.report.record(
+                    ResourceKind::Checkpoint,
+                    &checkpoint.id,
+                    Some(target_id.to_string()),
+                    RestoreAction::Exists,
+                );
+                continue;
+            }
+
+            let request = CheckpointCreateRequest {
+                name: checkpoint.name.clone(),
+                duration_hours: checkpoint.duration_hours.unwrap_or_default(),
+                is_active: checkpoint.is_active.unwrap_or(true),
+                referrer_domain_only: checkpoint.referrer_domain_only,
+                steps: checkpoint
+                    .steps
+                    .iter()
+                    .map(|step| CheckpointStepInput {
+                        ad_url: step.ad_url.clone(),
+                    })
+                    .collect(),
+            };
+            let result = if self.dry_run {
+                None
+            } else {
+                Some(
+                    self.client
+                        .create_checkpoint(request, self.options.clone())
+                        .await,
+                )
+            };
+            self.record_create(ResourceKind::Checkpoint, &checkpoint.id, result);
+        }
+        Ok(())
+    }
+
+    async fn restore_blacklist(&mut self, entries: &[Value]) -> Result<(), NebulAuthError> {
+        let live: Vec<BlacklistEntry> = self
+            .client
+            .list_blacklist(self.options.clone())
+            .await?
+            .error_for_status()?
+            .items()?;
+        let live_by_value: HashMap<(&str, &str), &str> = live
+            .iter()
+            .map(|e| ((e.r#type.as_str(), e.value.as_str()), e.id.as_str()))
+            .collect();
+
+        for raw in entries {
+            let entry: BlacklistEntry = decode(raw)?;
+            if let Some(target_id) =
+                live_by_value.get(&(entry.r#type.as_str(), entry.value.as_str()))
+            {
+                self.report.record(
+                    ResourceKind::BlacklistEntry,
+                    &entry.id,
+                    Some(target_id.to_string()),
+                    RestoreAction::Exists,
+                );
+                continue;
+            }
+
+            let request = BlacklistCreateRequest {
+                r#type: entry.r#type.clone(),
+                value: entry.value.clone(),
+                reason: entry.reason.clone(),
+            };
+            let result = if self.dry_run {
+                None
+            } else {
+                Some(
+                    self.client
+                        .create_blacklist_entry(request, self.options.clone())
+                        .await,
+                )
+            };
+            self.record_create(ResourceKind::BlacklistEntry, &entry.id, result);
+        }
+        Ok(())
+    }
+
+    async fn restore_keys(&mut self, keys: &[Value]) -> Result<(), NebulAuthError> {
+        let live: Vec<DashboardKey> = self
+            .client
+            .list_keys(self.options.clone())
+            .await?
+            .error_for_status()?
+            .items()?;
+        let mut live_ids: HashMap<String, String> = HashMap::new();
+        for key in &live {
+            live_ids.insert(key.id.clone(), key.id.clone());
+            if let Some(source_id) = restored_from(key.metadata.as_ref()) {
+                live_ids.insert(source_id.to_string(), key.id.clone());
+            }
+        }
+
+        for raw in keys {
+            let key: DashboardKey = decode(raw)?;
+            if key.revoked == Some(true) {
+                self.report.record(
+                    ResourceKind::Key,
+                    &key.id,
+                    None,
+                    RestoreAction::Skipped("revoked".to_string()),
+                );
+                continue;
+            }
+            if let Some(target_id) = live_ids.get(&key.id) {
+                self.report.record(
+                    ResourceKind::Key,
+                    &key.id,
+                    Some(target_id.clone()),
+                    RestoreAction::Exists,
+                );
+                continue;
+            }
+
+            let mut metadata = match key.metadata.clone() {
+                Some(Value::Object(map)) => map,
+                _ => serde_json::Map::new(),
+            };
+            metadata.insert(
+                RESTORED_FROM_FIELD.to_string(),
+                Value::String(key.id.clone()),
+            );
+
+            let duration_hours = match remaining_hours(&key) {
+                Ok(Some(hours)) if hours <= 0 => {
+                    self.report.record(
+                        ResourceKind::Key,
+                        &key.id,
+                        None,
+                        RestoreAction::Skipped("expired".to_string()),
+                    );
+                    continue;
+                }
+                Ok(Some(hours)) => Some(hours),
+                Ok(None) => key.duration_hours,
+                Err(reason) => {
+                    self.report.record(
+                        ResourceKind::Key,
+                        &key.id,
+                        None,
+                        RestoreAction::Failed(reason),
+                    );
+                    continue;
+                }
+            };
+
+            let request = KeyCreateRequest {
+                label: key.label.clone(),
+                duration_hours,
+                metadata: Some(Value::Object(metadata)),
+            };
+            let result = if self.dry_run {
+                None
+            } else {
+                Some(self.client.create_key(request, self.options.clone()).await)
+            };
+            self.record_create(ResourceKind::Key, &key.id, result);
+        }
+        Ok(())
+    }
+
+    async fn restore_users(
+        &mut self,
+        users: &[Value],
+        password: Option<String>,
+    ) -> Result<(), NebulAuthError> {
+        let live: Vec<TeamMember> = self
+            .client
+            .list_users(self.options.clone())
+            .await?
+            .error_for_status()?
+            .items()?;
+        let live_by_email: HashMap<String, &str> = live
+            .iter()
+            .map(|m| (m.email.to_lowercase(), m.id.as_str()))
+            .collect();
+
+        for raw in users {
+            let user: TeamMember = decode(raw)?;
+            if let Some(target_id) = live_by_email.get(&user.email.to_lowercase()) {
+                self.report.record(
+                    ResourceKind::User,
+                    &user.id,
+                    Some(target_id.to_string()),
+                    RestoreAction::Exists,
+                );
+                continue;
+            }
+            if user.role == Some(TeamRole::Owner) {
+                self.report.record(
+                    ResourceKind::User,
+                    &user.id,
+                    None,
+                    RestoreAction::Skipped("owner accounts are not recreated".to_string()),
+                );
+                continue;
+            }
+            let Some(password) = password.clone() else {
+                self.report.record(
+                    ResourceKind::User,
+                    &user.id,
+                    None,
+                    RestoreAction::Skipped("no user password provided".to_string()),
+                );
+                continue;
+            };
+
+            let request = TeamMemberCreateRequest {
+                email: user.email.clone(),
+                password,
+                role: user.role.clone().unwrap_or(TeamRole::Member),
+            };
+            let result = if self.dry_run {
+                None
+            } else {
+                Some(self.client.create_user(request, self.options.clone()).await)
+            };
+            self.record_create(ResourceKind::User, &user.id, result);
+        }
+        Ok(())
+    }
+
+    async fn restore_api_tokens(&mut self, tokens: &[Value]) -> Result<(), NebulAuthError> {
+        let live: Vec<ApiToken> = self
+            .client
+            .list_api_tokens(self.options.clone())
+            .await?
+            .error_for_status()?
+            .items()?;
+        // Tokens have no metadata to carry the source ID, so a saved token matches a live one
+        // with the same ID, or else the first unclaimed one with the same configuration.
+        let saved: Vec<ApiToken> = tokens.iter().map(decode).collect::<Result<_, _>>()?;
+        let mut unclaimed: Vec<&ApiToken> = live
+            .iter()
+            .filter(|live| !saved.iter().any(|token| token.id == live.id))
+            .collect();
+
+        for token in saved.iter() {
+            let target_id = match live.iter().find(|live| live.id == token.id) {
+                Some(live) => Some(live.id.clone()),
+                None => unclaimed
+                    .iter()
+                    .position(|live| same_token_config(live, token))
+                    .map(|index| unclaimed.remove(index).id.clone()),
+            };
+            if let Some(target_id) = target_id {
+                self.report.record(
+                    ResourceKind::ApiToken,
+                    &token.id,
+                    Some(target_id),
+                    RestoreAction::Exists,
+                );
+                continue;
+            }
+
+            let request = ApiTokenCreateRequest {
+                scopes: token.scopes.clone(),
+                replay_protection: token
+                    .replay_protection
+                    .clone()
+                    .unwrap_or(ReplayProtectionMode::Strict),
+                auth_mode: token.auth_mode.clone().unwrap_or(ApiAuthMode::Bearer),
+                expires_at: token.expires_at.clone(),
+            };
+            let result = if self.dry_run {
+                None
+            } else {
+                Some(check(
+                    self.client
+                        .create_api_token(request, self.options.clone())
+                        .await,
+                ))
+            };
+            if let Some(Ok(response)) = &result {
+                self.report.api_tokens.push(RestoredApiToken {
+                    source_id: token.id.clone(),
+                    data: response.data.clone(),
+                });
+            }
+            self.record_create(ResourceKind::ApiToken, &token.id, result);
+        }
+        Ok(())
+    }
+
+    fn record_create(
+        &mut self,
+        kind: ResourceKind,
+        source_id: &str,
+        result: Option<Result<DashboardResponse, NebulAuthError>>,
+    ) {
+        let (target_id, action) = match result.map(check) {
+            None => (None, RestoreAction::WouldCreate),
+            Some(Ok(response)) => (response_id(&response.data), RestoreAction::Created),
+            Some(Err(e)) => (None, RestoreAction::Failed(e.to_string())),
+        };
+        self.report.record(kind, source_id, target_id, action);
+    }
+}
+
+/// Hours left on a key that has an expiry, rounded up so a restored key never ends early.
+/// `None` for keys without `expires_at`, which keep their full `duration_hours`.
+fn remaining_hours(key: &DashboardKey) -> Result<Option<i64>, String> {
+    let Some(expires_at) = &key.expires_at else {
+        return Ok(None);
+    };
+    let expires_at = DateTime::parse_from_rfc3339(expires_at)
+        .map_err(|e| format!("invalid expires_at '{expires_at}': {e}"))?;
+    let remaining_ms = expires_at.timestamp_millis() - current_timestamp_ms() as i64;
+    Ok(Some((remaining_ms + 3_599_999).div_euclid(3_600_000)))
+}
+
+fn restored_from(metadata: Option<&Value>) -> Option<&str> {
+    metadata?.get(RESTORED_FROM_FIELD)?.as_str()
+}
+
+fn same_token_config(live: &ApiToken, saved: &ApiToken) -> bool {
+    same_scopes(&live.scopes, &saved.scopes)
+        && live.replay_protection == saved.replay_protection
+        && live.auth_mode == saved.auth_mode
+        && live.expires_at == saved.expires_at
+}
+
+fn decode<T: serde::de::DeserializeOwned>(value: &Value) -> Result<T, NebulAuthError> {
+    serde_json::from_value(value.clone()).map_err(|e| NebulAuthError::Decode(e.to_string()))
+}
+
+fn check(
+    result: Result<DashboardResponse, NebulAuthError>,
+) -> Result<DashboardResponse, NebulAuthError> {
+    result.and_then(DashboardResponse::error_for_status)
+}
+
+pub(crate) fn response_id(data: &Value) -> Option<String> {
+    let id = data.get("id").or_else(|| {
+        data.as_object()?
+            .values()
+            .find_map(|value| value.as_object()?.get("id"))
+    })?;
+    match id {
+        Value::String(id) => Some(id.clone()),
+        Value::Number(id) => Some(id.to_string()),
+        _ => None,
+    }
+}
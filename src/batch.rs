@@ -0,0 +1,586 @@
+use futures_util::stream::{self, StreamExt};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::fs::File;
+use std::io::{BufWriter, Write};
+use std::path::Path;
+use std::sync::atomic::{AtomicBool, Ordering};
+
+use crate::{
+    BatchFormat, DashboardRequestOptions, DashboardResponse, KeyBatchCreateRequest,
+    KeyUpdateRequest, NebulAuthDashboardClient, NebulAuthError,
+};
+
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct GeneratedKey {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub id: Option<String>,
+    #[serde(alias = "license", alias = "license_key", alias = "licenseKey")]
+    pub key: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub label: Option<String>,
+    #[serde(
+        default,
+        alias = "durationHours",
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub duration_hours: Option<i64>,
+    #[serde(default, alias = "expiresAt", skip_serializing_if = "Option::is_none")]
+    pub expires_at: Option<String>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ExportFormat {
+    Csv,
+    Text,
+    Ndjson,
+}
+
+impl ExportFormat {
+    pub fn from_path(path: &Path) -> Option<Self> {
+        match path.extension().and_then(|ext| ext.to_str()) {
+            Some("csv") => Some(ExportFormat::Csv),
+            Some("txt") => Some(ExportFormat::Text),
+            Some("ndjson") | Some("jsonl") => Some(ExportFormat::Ndjson),
+            _ => None,
+        }
+    }
+}
+
+pub fn parse_generated_keys(
+    response: &DashboardResponse,
+    format: BatchFormat,
+) -> Result<Vec<GeneratedKey>, NebulAuthError> {
+    match (&response.data, format) {
+        (Value::String(text), BatchFormat::Csv | BatchFormat::Txt) => {
+            let mut parser = KeyParser::new(format);
+            let mut keys = parser.push(text.as_bytes())?;
+            keys.extend(parser.finish()?);
+            Ok(keys)
+        }
+        _ => response
+            .items::<Value>()?
+            .into_iter()
+            .map(json_key)
+            .collect(),
+    }
+}
+
+fn json_key(value: Value) -> Result<GeneratedKey, NebulAuthError> {
+    match value {
+        Value::String(key) => Ok(GeneratedKey {
+            key,
+            ..Default::default()
+        }),
+        other => serde_json::from_value(other).map_err(|e| NebulAuthError::Decode(e.to_string())),
+    }
+}
+
+/// Turns CSV or text batch output into keys as it arrives. A CSV record ends at a newline
+/// outside quotes, so quoted fields may span lines and chunk boundaries.
+struct KeyParser {
+    format: BatchFormat,
+    columns: Option<Vec<String>>,
+    pending: Vec<u8>,
+    scanned: usize,
+    quoted: bool,
+    rows: usize,
+}
+
+impl KeyParser {
+    fn new(format: BatchFormat) -> Self {
+        Self {
+            format,
+            columns: None,
+            pending: Vec::new(),
+            scanned: 0,
+            quoted: false,
+            rows: 0,
+        }
+    }
+
+    fn push(&mut self, chunk: &[u8]) -> Result<Vec<GeneratedKey>, NebulAuthError> {
+        self.pending.extend_from_slice(chunk);
+        let mut keys = Vec::new();
+        let mut start = 0;
+        for index in self.scanned..self.pending.len() {
+            match self.pending[index] {
+                b'"' if self.format == BatchFormat::Csv => self.quoted = !self.quoted,
+                b'\n' if !self.quoted => {
+                    let record = self.pending[start..index].to_vec();
+                    keys.extend(self.parse_record(&record)?);
+                    start = index + 1;
+                }
+                _ => {}
+            }
+        }
+        self.pending.drain(..start);
+        self.scanned = self.pending.len();
+        Ok(keys)
+    }
+
+    fn finish(mut self) -> Result<Option<GeneratedKey>, NebulAuthError> {
+        let record = std::mem::take(&mut self.pending);
+        self.parse_record(&record)
+    }
+
+    fn parse_record(&mut self, record: &[u8]) -> Result<Option<GeneratedKey>, NebulAuthError> {
+        let record = std::str::from_utf8(record)
+            .map_err(|e| NebulAuthError::Decode(format!("batch output is not utf-8: {e}")))?;
+        let record = record.strip_suffix('\r').unwrap_or(record);
+        if record.trim().is_empty() {
+            return Ok(None);
+        }
+        if self.format == BatchFormat::Txt {
+            return Ok(Some(GeneratedKey {
+                key: record.trim().to_string(),
+                ..Default::default()
+            }));
+        }
+
+        let fields = csv::ReaderBuilder::new()
+            .has_headers(false)
+            .flexible(true)
+            .from_reader(record.as_bytes())
+            .records()
+            .next()
+            .transpose()
+            .map_err(|e| NebulAuthError::Decode(format!("invalid csv row: {e}")))?
+            .unwrap_or_default();
+        let Some(columns) = &self.columns else {
+            self.columns = Some(
+                fields
+                    .iter()
+                    .map(|column| column.trim().to_lowercase())
+                    .collect(),
+            );
+            return Ok(None);
+        };
+        self.rows += 1;
+
+        let mut key = GeneratedKey::default();
+        for (column, value) in columns.iter().zip(fields.iter()) {
+            let value = (!value.is_empty()).then(|| value.to_string());
+            match column.as_str() {
+                "key" | "license" | "license_key" | "licensekey" => {
+                    key.key = value.unwrap_or_default()
+                }
+                "id" => key.id = value,
+                "label" => key.label = value,
+                "duration_hours" | "durationhours" => {
+                    key.duration_hours = value.and_then(|v| v.parse().ok())
+                }
+                "expires_at" | "expiresat" => key.expires_at = value,
+                _ => {}
+            }
+        }
+        if key.key.is_empty() {
+            return Err(NebulAuthError::Decode(format!(
+                "csv row {} has no key column",
+                self.rows
+            )));
+        }
+        Ok(Some(key))
+    }
+}
+
+enum Sink<W: Write> {
+    Text(W),
+    Ndjson(W),
+    Csv(Box<csv::Writer<W>>),
+}
+
+pub struct KeyWriter<W: Write> {
+    sink: Sink<W>,
+    wrote_header: bool,
+}
+
+impl<W: Write> KeyWriter<W> {
+    pub fn new(inner: W, format: ExportFormat) -> Self {
+        let sink = match format {
+            ExportFormat::Csv => Sink::Csv(Box::new(csv::Writer::from_writer(inner))),
+            ExportFormat::Text => Sink::Text(inner),
+            ExportFormat::Ndjson => Sink::Ndjson(inner),
+        };
+        Self {
+            sink,
+            wrote_header: false,
+        }
+    }
+
+    pub fn write_key(&mut self, key: &GeneratedKey) -> Result<(), NebulAuthError> {
+        match &mut self.sink {
+            Sink::Text(inner) => writeln!(inner, "{}", key.key)?,
+            Sink::Ndjson(inner) => {
+                let line = serde_json::to_string(key)
+                    .map_err(|e| NebulAuthError::Config(e.to_string()))?;
+                writeln!(inner, "{line}")?;
+            }
+            Sink::Csv(writer) => {
+                if !self.wrote_header {
+                    writer
+                        .write_record(["key", "id", "label", "duration_hours", "expires_at"])
+                        .map_err(csv_error)?;
+                    self.wrote_header = true;
+                }
+                let duration_hours = key.duration_hours.map(|h| h.to_string());
+                writer
+                    .write_record(
+                        [
+                            Some(key.key.as_str()),
+                            key.id.as_deref(),
+                            key.label.as_deref(),
+                            duration_hours.as_deref(),
+                            key.expires_at.as_deref(),
+                        ]
+                        .map(Option::unwrap_or_default),
+                    )
+                    .map_err(csv_error)?;
+            }
+        }
+        Ok(())
+    }
+
+    pub fn write_all(&mut self, keys: &[GeneratedKey]) -> Result<(), NebulAuthError> {
+        for key in keys {
+            self.write_key(key)?;
+        }
+        Ok(())
+    }
+
+    pub fn finish(self) -> Result<W, NebulAuthError> {
+        match self.sink {
+            Sink::Text(mut inner) | Sink::Ndjson(mut inner) => {
+                inner.flush()?;
+                Ok(inner)
+            }
+            Sink::Csv(writer) => writer
+                .into_inner()
+                .map_err(|e| NebulAuthError::Io(e.into_error())),
+        }
+    }
+}
+
+fn csv_error(error: csv::Error) -> NebulAuthError {
+    match error.into_kind() {
+        csv::ErrorKind::Io(e) => NebulAuthError::Io(e),
+        other => NebulAuthError::Config(format!("failed to write csv: {other:?}")),
+    }
+}
+
+pub fn export_keys(
+    keys: &[GeneratedKey],
+    path: impl AsRef<Path>,
+    format: ExportFormat,
+) -> Result<(), NebulAuthError> {
+    let file = File::create(path)?;
+    let mut writer = KeyWriter::new(BufWriter::new(file), format);
+    writer.write_all(keys)?;
+    writer.finish()?;
+    Ok(())
+}
+
+const DEFAULT_CHUNK_SIZE: i64 = 1_000;
+const DEFAULT_CHUNK_CONCURRENCY: usize = 2;
+
+#[derive(Debug, Clone)]
+pub struct ChunkedBatchOptions {
+    pub chunk_size: i64,
+    pub concurrency: usize,
+    pub format: BatchFormat,
+    pub resume: Option<ChunkedBatchReport>,
+}
+
+impl Default for ChunkedBatchOptions {
+    fn default() -> Self {
+        Self {
+            chunk_size: DEFAULT_CHUNK_SIZE,
+            concurrency: DEFAULT_CHUNK_CONCURRENCY,
+            format: BatchFormat::Json,
+            resume: None,
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "status", content = "error", rename_all = "snake_case")]
+pub enum ChunkOutcome {
+    Pending,
+    Succeeded,
+    /// The server created fewer keys than asked for; resuming requests only the rest.
+    Partial(String),
+    Failed(String),
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ChunkResult {
+    pub index: usize,
+    /// Position of the chunk's first key in the whole batch.
+    pub offset: i64,
+    pub count: i64,
+    pub outcome: ChunkOutcome,
+    pub keys: Vec<GeneratedKey>,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct ChunkedBatchReport {
+    pub requested: i64,
+    pub chunks: Vec<ChunkResult>,
+}
+
+impl ChunkedBatchReport {
+    pub fn is_complete(&self) -> bool {
+        self.chunks
+            .iter()
+            .all(|chunk| chunk.outcome == ChunkOutcome::Succeeded)
+    }
+
+    pub fn keys(&self) -> impl Iterator<Item = &GeneratedKey> {
+        self.chunks.iter().flat_map(|chunk| chunk.keys.iter())
+    }
+
+    /// Chunks that failed or came back short.
+    pub fn failed_chunks(&self) -> impl Iterator<Item = &ChunkResult> {
+        self.chunks.iter().filter(|chunk| {
+            matches!(
+                chunk.outcome,
+                ChunkOutcome::Failed(_) | ChunkOutcome::Partial(_)
+            )
+        })
+    }
+
+    pub fn load(path: impl AsRef<Path>) -> Result<Self, NebulAuthError> {
+        let contents = std::fs::read_to_string(path)?;
+        serde_json::from_str(&contents)
+            .map_err(|e| NebulAuthError::Decode(format!("invalid batch report: {e}")))
+    }
+
+    pub fn save(&self, path: impl AsRef<Path>) -> Result<(), NebulAuthError> {
+        let contents = serde_json::to_string_pretty(self).map_err(|e| {
+            NebulAuthError::Config(format!("failed to serialize batch report: {e}"))
+        })?;
+        std::fs::write(path, contents)?;
+        Ok(())
+    }
+}
+
+impl NebulAuthDashboardClient {
+    /// Splits `payload` into batches of at most `chunk_size` keys. With a `label_prefix`, keys
+    /// are renamed to `{prefix}{n}` by their position in the whole batch, so labels stay
+    /// unique and in order across chunks; this needs key IDs, so `Txt` is rejected.
+    pub async fn bulk_create_keys_chunked(
+        &self,
+        payload: KeyBatchCreateRequest,
+        chunk_options: ChunkedBatchOptions,
+        options: DashboardRequestOptions,
+    ) -> Result<ChunkedBatchReport, NebulAuthError> {
+        if payload.count <= 0 {
+            return Err(NebulAuthError::Config(
+                "batch count must be positive".to_string(),
+            ));
+        }
+        if chunk_options.chunk_size <= 0 {
+            return Err(NebulAuthError::Config(
+                "chunk_size must be positive".to_string(),
+            ));
+        }
+        if payload.label_prefix.is_some()
+            && payload.count > chunk_options.chunk_size
+            && chunk_options.format == BatchFormat::Txt
+        {
+            return Err(NebulAuthError::Config(
+                "label_prefix across several chunks needs key IDs; use the json or csv format"
+                    .to_string(),
+            ));
+        }
+
+        let mut chunks: Vec<ChunkResult> = (0..payload.count)
+            .step_by(chunk_options.chunk_size as usize)
+            .enumerate()
+            .map(|(index, offset)| ChunkResult {
+                index,
+                offset,
+                count: chunk_options.chunk_size.min(payload.count - offset),
+                outcome: ChunkOutcome::Pending,
+                keys: Vec::new(),
+            })
+            .collect();
+
+        if let Some(previous) = &chunk_options.resume {
+            if previous.requested != payload.count {
+                return Err(NebulAuthError::Config(format!(
+                    "resume report was for {} keys, not {}",
+                    previous.requested, payload.count
+                )));
+            }
+            // Keys of a chunk that failed while relabeling exist on the server too.
+            for done in previous
+                .chunks
+                .iter()
+                .filter(|chunk| chunk.outcome == ChunkOutcome::Succeeded || !chunk.keys.is_empty())
+            {
+                if let Some(chunk) = chunks.get_mut(done.index) {
+                    if chunk.offset == done.offset && chunk.count == done.count {
+                        *chunk = done.clone();
+                    }
+                }
+            }
+        }
+
+        let stop = AtomicBool::new(false);
+        let pending: Vec<ChunkResult> = chunks
+            .iter()
+            .filter(|chunk| chunk.outcome != ChunkOutcome::Succeeded)
+            .cloned()
+            .collect();
+
+        let finished: Vec<ChunkResult> = stream::iter(pending)
+            .map(|mut chunk| {
+                let stop = &stop;
+                let options = options.clone();
+                let prefix = payload.label_prefix.clone();
+                let request = KeyBatchCreateRequest {
+                    count: chunk.count - chunk.keys.len() as i64,
+                    ..payload.clone()
+                };
+                async move {
+                    if stop.load(Ordering::SeqCst) {
+                        return chunk;
+                    }
+                    if request.count > 0 {
+                        match self
+                            .generate_keys(request, chunk_options.format, options.clone())
+                            .await
+                        {
+                            Ok(keys) => chunk.keys.extend(keys),
+                            Err(e) => {
+                                stop.store(true, Ordering::SeqCst);
+                                chunk.outcome = ChunkOutcome::Failed(e.to_string());
+                                return chunk;
+                            }
+                        }
+                    }
+                    if let Some(prefix) = &prefix {
+                        if let Err(e) = self.relabel_chunk(&mut chunk, prefix, &options).await {
+                            stop.store(true, Ordering::SeqCst);
+                            chunk.outcome = ChunkOutcome::Failed(e.to_string());
+                            return chunk;
+                        }
+                    }
+
+                    let received = chunk.keys.len() as i64;
+                    let message = format!("expected {} keys, got {received}", chunk.count);
+                    chunk.outcome = match received.cmp(&chunk.count) {
+                        std::cmp::Ordering::Equal => ChunkOutcome::Succeeded,
+                        std::cmp::Ordering::Less => ChunkOutcome::Partial(message),
+                        std::cmp::Ordering::Greater => ChunkOutcome::Failed(message),
+                    };
+                    chunk
+                }
+            })
+            .buffer_unordered(chunk_options.concurrency.max(1))
+            .collect()
+            .await;
+
+        for chunk in finished {
+            let index = chunk.index;
+            chunks[index] = chunk;
+        }
+
+        Ok(ChunkedBatchReport {
+            requested: payload.count,
+            chunks,
+        })
+    }
+
+    /// Renames the chunk's keys to `{prefix}{n}`, numbered from the chunk's offset. Keys that
+    /// already carry their label are left alone, so a resumed chunk only renames the rest.
+    async fn relabel_chunk(
+        &self,
+        chunk: &mut ChunkResult,
+        prefix: &str,
+        options: &DashboardRequestOptions,
+    ) -> Result<(), NebulAuthError> {
+        for (position, key) in (chunk.offset + 1..).zip(chunk.keys.iter_mut()) {
+            let label = format!("{prefix}{position}");
+            if key.label.as_deref() == Some(label.as_str()) {
+                continue;
+            }
+            let id = key.id.clone().ok_or_else(|| {
+                NebulAuthError::Decode("created key has no id to relabel".to_string())
+            })?;
+            self.update_key(
+                &id,
+                KeyUpdateRequest {
+                    label: Some(label.clone()),
+                    ..Default::default()
+                },
+                options.clone(),
+            )
+            .await?
+            .error_for_status()?;
+            key.label = Some(label);
+        }
+        Ok(())
+    }
+
+    pub async fn generate_keys(
+        &self,
+        payload: KeyBatchCreateRequest,
+        format: BatchFormat,
+        options: DashboardRequestOptions,
+    ) -> Result<Vec<GeneratedKey>, NebulAuthError> {
+        let response = self
+            .bulk_create_keys(payload, format, options)
+            .await?
+            .error_for_status()?;
+        parse_generated_keys(&response, format)
+    }
+
+    /// Creates a batch and writes each key to `writer` as its record arrives, returning how
+    /// many were written. JSON output has to be read whole before it can be parsed.
+    pub async fn stream_generated_keys<W: Write>(
+        &self,
+        payload: KeyBatchCreateRequest,
+        format: BatchFormat,
+        writer: &mut KeyWriter<W>,
+        mut options: DashboardRequestOptions,
+    ) -> Result<usize, NebulAuthError> {
+        if format == BatchFormat::Json {
+            let keys = self.generate_keys(payload, format, options).await?;
+            writer.write_all(&keys)?;
+            return Ok(keys.len());
+        }
+
+        options
+            .query
+            .insert("format".to_string(), format.to_string());
+        let body =
+            serde_json::to_value(payload).map_err(|e| NebulAuthError::Config(e.to_string()))?;
+        let mut response = self
+            .request_streaming("POST", "/keys/batch", Some(body), options)
+            .await?;
+        if !(200..300).contains(&response.status) {
+            let status = response.status;
+            let headers = std::mem::take(&mut response.headers);
+            let text = response.text().await?;
+            return DashboardResponse::from_body(status, headers, text)
+                .error_for_status()
+                .map(|_| 0);
+        }
+
+        let mut parser = KeyParser::new(format);
+        let mut written = 0;
+        while let Some(chunk) = response.body.next().await {
+            for key in parser.push(&chunk?)? {
+                writer.write_key(&key)?;
+                written += 1;
+            }
+        }
+        if let Some(key) = parser.finish()? {
+            writer.write_key(&key)?;
+            written += 1;
+        }
+        Ok(written)
+    }
+}
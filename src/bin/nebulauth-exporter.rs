@@ -0,0 +1,229 @@
+use clap::Parser;
+use nebulauth_sdk::analytics::AnalyticsRange;
+use nebulauth_sdk::exporter::{self, ServiceMetrics};
+use nebulauth_sdk::{
+    DashboardAuth, DashboardRequestOptions, NebulAuthDashboardClient,
+    NebulAuthDashboardClientOptions, NebulAuthError,
+};
+use std::net::SocketAddr;
+use std::process::ExitCode;
+use std::sync::{Arc, RwLock};
+use std::time::Duration;
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
+use tokio::net::{TcpListener, TcpStream};
+
+#[derive(Parser)]
+#[command(
+    name = "nebulauth-exporter",
+    version,
+    about = "Serve NebulAuth dashboard metrics in Prometheus format"
+)]
+struct Cli {
+    /// Dashboard API base URL
+    #[arg(long, env = "NEBULAUTH_DASHBOARD_BASE_URL")]
+    base_url: Option<String>,
+    /// Service label for the token in NEBULAUTH_DASHBOARD_BEARER_TOKEN
+    #[arg(long, default_value = "default")]
+    service_name: String,
+    /// Additional service as NAME=env:VAR, reading its token from the environment variable
+    /// VAR; may be repeated
+    #[arg(long = "service", value_parser = parse_service)]
+    services: Vec<(String, String)>,
+    /// Address to serve /metrics on
+    #[arg(long, default_value = "127.0.0.1:9464")]
+    listen: SocketAddr,
+    /// Seconds between refreshes
+    #[arg(long, default_value_t = 60)]
+    interval_secs: u64,
+    /// Analytics window in days
+    #[arg(long, default_value = "1", value_parser = parse_days)]
+    days: AnalyticsRange,
+}
+
+fn parse_days(value: &str) -> Result<AnalyticsRange, String> {
+    let days = value.parse::<u32>().map_err(|e| e.to_string())?;
+    AnalyticsRange::days(days).map_err(|e| e.to_string())
+}
+
+/// Parses `NAME=env:VAR` into the service name and variable. Tokens themselves are refused:
+/// anything on the command line is visible in `ps` and kept in shell history.
+fn parse_service(value: &str) -> Result<(String, String), String> {
+    match value.split_once('=') {
+        Some((name, source)) if !name.is_empty() => match source.strip_prefix("env:") {
+            Some(var) if !var.is_empty() => Ok((name.to_string(), var.to_string())),
+            _ => Err(
+                "expected NAME=env:VAR; tokens are not accepted on the command line".to_string(),
+            ),
+        },
+        _ => Err("expected NAME=env:VAR".to_string()),
+    }
+}
+
+fn env_token(var: &str, service: &str) -> Result<String, NebulAuthError> {
+    std::env::var(var)
+        .ok()
+        .filter(|token| !token.is_empty())
+        .ok_or_else(|| {
+            NebulAuthError::Config(format!(
+                "environment variable {var} for service '{service}' is not set"
+            ))
+        })
+}
+
+type State = Arc<RwLock<Vec<ServiceMetrics>>>;
+
+const DEFAULT_TOKEN_VAR: &str = "NEBULAUTH_DASHBOARD_BEARER_TOKEN";
+
+/// How long a scrape connection may take to send its request, and separately to take the
+/// response, before it is dropped.
+const CONNECTION_TIMEOUT: Duration = Duration::from_secs(10);
+
+#[tokio::main]
+async fn main() -> ExitCode {
+    match run(Cli::parse()).await {
+        Ok(()) => ExitCode::SUCCESS,
+        Err(e) => {
+            eprintln!("error: {e}");
+            ExitCode::FAILURE
+        }
+    }
+}
+
+async fn run(cli: Cli) -> Result<(), NebulAuthError> {
+    let mut targets = Vec::with_capacity(cli.services.len() + 1);
+    if let Some(token) = std::env::var(DEFAULT_TOKEN_VAR)
+        .ok()
+        .filter(|token| !token.is_empty())
+    {
+        targets.push((cli.service_name.clone(), token));
+    }
+    for (name, var) in &cli.services {
+        targets.push((name.clone(), env_token(var, name)?));
+    }
+    if targets.is_empty() {
+        return Err(NebulAuthError::Config(format!(
+            "set {DEFAULT_TOKEN_VAR} or pass at least one --service NAME=env:VAR"
+        )));
+    }
+
+    let mut clients = Vec::with_capacity(targets.len());
+    for (_, bearer_token) in &targets {
+        let mut options = NebulAuthDashboardClientOptions {
+            auth: Some(DashboardAuth::Bearer {
+                bearer_token: bearer_token.clone(),
+            }),
+            ..Default::default()
+        };
+        if let Some(base_url) = &cli.base_url {
+            options.base_url = base_url.clone();
+        }
+        clients.push(NebulAuthDashboardClient::new(options)?);
+    }
+
+    let state: State = Arc::new(RwLock::new(
+        targets
+            .iter()
+            .map(|(name, _)| ServiceMetrics::new(name))
+            .collect(),
+    ));
+
+    let listener = TcpListener::bind(cli.listen).await?;
+    println!("serving metrics on http://{}/metrics", cli.listen);
+
+    let poller = tokio::spawn(poll(
+        clients,
+        state.clone(),
+        cli.days,
+        Duration::from_secs(cli.interval_secs.max(1)),
+    ));
+
+    loop {
+        tokio::select! {
+            accepted = listener.accept() => {
+                let (stream, _) = accepted?;
+                tokio::spawn(serve(stream, state.clone()));
+            }
+            _ = tokio::signal::ctrl_c() => break,
+        }
+    }
+    poller.abort();
+    Ok(())
+}
+
+async fn poll(
+    clients: Vec<NebulAuthDashboardClient>,
+    state: State,
+    range: AnalyticsRange,
+    interval: Duration,
+) {
+    loop {
+        for (index, client) in clients.iter().enumerate() {
+            let mut metrics = state.read().unwrap_or_else(|e| e.into_inner())[index].clone();
+            if let Err(e) = metrics
+                .refresh(client, range, DashboardRequestOptions::default())
+                .await
+            {
+                eprintln!("refresh failed for {}: {e}", metrics.service);
+            }
+            state.write().unwrap_or_else(|e| e.into_inner())[index] = metrics;
+        }
+        tokio::time::sleep(interval).await;
+    }
+}
+
+async fn serve(mut stream: TcpStream, state: State) {
+    let mut buffer = vec![0u8; 8192];
+    let Ok(read) =
+        tokio::time::timeout(CONNECTION_TIMEOUT, read_head(&mut stream, &mut buffer)).await
+    else {
+        return;
+    };
+
+    let request = String::from_utf8_lossy(&buffer[..read]);
+    let mut parts = request.split_whitespace();
+    let (status, content_type, body) = match (parts.next(), parts.next()) {
+        (Some("GET"), Some("/metrics")) => {
+            let services = state.read().unwrap_or_else(|e| e.into_inner());
+            (
+                "200 OK",
+                "text/plain; version=0.0.4; charset=utf-8",
+                exporter::render(&services),
+            )
+        }
+        (Some("GET"), Some("/")) => (
+            "200 OK",
+            "text/plain; charset=utf-8",
+            "NebulAuth exporter: see /metrics\n".to_string(),
+        ),
+        _ => (
+            "404 Not Found",
+            "text/plain; charset=utf-8",
+            "not found\n".to_string(),
+        ),
+    };
+
+    let response = format!(
+        "HTTP/1.1 {status}\r\nContent-Type: {content_type}\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{body}",
+        body.len()
+    );
+    let _ = tokio::time::timeout(CONNECTION_TIMEOUT, async {
+        let _ = stream.write_all(response.as_bytes()).await;
+        let _ = stream.shutdown().await;
+    })
+    .await;
+}
+
+/// Reads until the end of the request head, EOF, an error or a full buffer.
+async fn read_head(stream: &mut TcpStream, buffer: &mut [u8]) -> usize {
+    let mut read = 0;
+    while read < buffer.len() {
+        match stream.read(&mut buffer[read..]).await {
+            Ok(0) | Err(_) => break,
+            Ok(n) => read += n,
+        }
+        if buffer[..read].windows(4).any(|w| w == b"\r\n\r\n") {
+            break;
+        }
+    }
+    read
+}
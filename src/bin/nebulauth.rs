@@ -0,0 +1,306 @@
+use clap::{Args, Parser, Subcommand};
+use nebulauth_sdk::audit::{self, AuditLog};
+use nebulauth_sdk::backup::{self, ResourceKind, RestoreAction, RestoreOptions, Snapshot};
+use nebulauth_sdk::config::{self, Change, DesiredState, PlanOptions};
+use nebulauth_sdk::import::{self, ImportMapping, ImportOptions};
+use nebulauth_sdk::{
+    DashboardAuth, DashboardRequestOptions, NebulAuthDashboardClient,
+    NebulAuthDashboardClientOptions, NebulAuthError,
+};
+use std::path::PathBuf;
+use std::process::ExitCode;
+use std::sync::Arc;
+
+/// HMAC key for the audit chain, used by `--audit-log` and `verify-audit`.
+const AUDIT_CHAIN_KEY_ENV: &str = "NEBULAUTH_AUDIT_CHAIN_KEY";
+
+#[derive(Parser)]
+#[command(
+    name = "nebulauth",
+    version,
+    about = "NebulAuth dashboard command line tool"
+)]
+struct Cli {
+    #[command(flatten)]
+    connection: ConnectionArgs,
+    #[command(subcommand)]
+    command: Command,
+}
+
+#[derive(Args)]
+struct ConnectionArgs {
+    /// Dashboard API base URL
+    #[arg(long, global = true, env = "NEBULAUTH_DASHBOARD_BASE_URL")]
+    base_url: Option<String>,
+    /// Dashboard bearer token
+    #[arg(
+        long,
+        global = true,
+        env = "NEBULAUTH_DASHBOARD_BEARER_TOKEN",
+        hide_env_values = true
+    )]
+    token: Option<String>,
+    /// File holding a persisted dashboard session cookie
+    #[arg(long, global = true, env = "NEBULAUTH_SESSION_FILE")]
+    session_file: Option<PathBuf>,
+    /// Append a hash-chained record of every change to this JSONL file
+    #[arg(long, global = true, env = "NEBULAUTH_AUDIT_LOG")]
+    audit_log: Option<PathBuf>,
+    /// Name recorded as the actor in the audit log
+    #[arg(
+        long,
+        global = true,
+        env = "NEBULAUTH_AUDIT_ACTOR",
+        default_value = "nebulauth-cli"
+    )]
+    actor: String,
+}
+
+#[derive(Subcommand)]
+enum Command {
+    /// Show the changes needed to reach the desired state
+    Plan(ConfigArgs),
+    /// Apply the changes needed to reach the desired state
+    Apply(ConfigArgs),
+    /// Write a JSON snapshot of the service
+    Backup {
+        /// Output file
+        output: PathBuf,
+    },
+    /// Recreate missing resources from a JSON snapshot
+    Restore(RestoreArgs),
+    /// Create keys from another provider's CSV or JSON export
+    Import(ImportArgs),
+    /// Check that an audit log's hash chain is intact
+    VerifyAudit {
+        /// Audit log written with --audit-log
+        file: PathBuf,
+        /// Last hash from an earlier check, kept outside the log; fails if the chain no
+        /// longer contains it
+        #[arg(long)]
+        expected_head: Option<String>,
+    },
+}
+
+#[derive(Args)]
+struct ConfigArgs {
+    /// Desired-state file (.yaml, .yml or .toml)
+    file: PathBuf,
+    /// Delete live resources that are not listed in the file
+    #[arg(long)]
+    prune: bool,
+}
+
+#[derive(Args)]
+struct RestoreArgs {
+    /// Snapshot file written by `backup`
+    file: PathBuf,
+    /// Report what would be restored without changing anything
+    #[arg(long)]
+    dry_run: bool,
+    /// Password given to recreated team members
+    #[arg(long, env = "NEBULAUTH_RESTORE_USER_PASSWORD", hide_env_values = true)]
+    user_password: Option<String>,
+}
+
+#[derive(Args)]
+struct ImportArgs {
+    /// JSON mapping from export columns to key fields
+    mapping: PathBuf,
+    /// Export file (.csv, .json, .ndjson or .jsonl)
+    input: PathBuf,
+    /// Report what would be imported without creating keys
+    #[arg(long)]
+    dry_run: bool,
+    /// Maximum keys created per second (0 for no limit)
+    #[arg(long, default_value_t = ImportOptions::default().requests_per_second)]
+    rate: u32,
+    /// Write the reconciliation report to this file
+    #[arg(long)]
+    report: Option<PathBuf>,
+}
+
+#[tokio::main]
+async fn main() -> ExitCode {
+    let cli = Cli::parse();
+    match run(cli).await {
+        Ok(()) => ExitCode::SUCCESS,
+        Err(e) => {
+            eprintln!("error: {e}");
+            ExitCode::FAILURE
+        }
+    }
+}
+
+async fn run(cli: Cli) -> Result<(), NebulAuthError> {
+    let client = connect(cli.connection)?;
+
+    match cli.command {
+        Command::Plan(args) => {
+            let plan = load_plan(&client, &args).await?;
+            print!("{plan}");
+        }
+        Command::Apply(args) => {
+            let plan = load_plan(&client, &args).await?;
+            print!("{plan}");
+            if plan.is_empty() {
+                return Ok(());
+            }
+
+            let applied = config::apply(&client, &plan, DashboardRequestOptions::default()).await?;
+            for (change, response) in applied {
+                println!("applied: {change}");
+                if let Change::CreateApiToken(_) = change {
+                    println!("  {}", response.data);
+                }
+            }
+        }
+        Command::Backup { output } => {
+            let snapshot = backup::backup(&client, DashboardRequestOptions::default()).await?;
+            snapshot.save(&output)?;
+            println!(
+                "wrote {} keys, {} users, {} checkpoints, {} blacklist entries, {} api tokens to {}",
+                snapshot.keys.len(),
+                snapshot.users.len(),
+                snapshot.checkpoints.len(),
+                snapshot.blacklist.len(),
+                snapshot.api_tokens.len(),
+                output.display()
+            );
+        }
+        Command::Restore(args) => {
+            let snapshot = Snapshot::load(&args.file)?;
+            let report = backup::restore(
+                &client,
+                &snapshot,
+                RestoreOptions {
+                    dry_run: args.dry_run,
+                    user_password: args.user_password,
+                },
+                DashboardRequestOptions::default(),
+            )
+            .await?;
+            for item in &report.items {
+                println!("{item}");
+            }
+            let recreated_keys = report
+                .items
+                .iter()
+                .filter(|item| {
+                    item.kind == ResourceKind::Key && item.action == RestoreAction::Created
+                })
+                .count();
+            if recreated_keys > 0 {
+                println!(
+                    "{recreated_keys} key(s) were recreated with new key strings; \
+                     end users need the new keys (old -> new IDs are listed above)"
+                );
+            }
+            for token in &report.api_tokens {
+                println!("new api token for {}:", token.source_id);
+                println!("  {}", token.data);
+            }
+            if report.failed().next().is_some() {
+                return Err(NebulAuthError::Config(
+                    "some resources failed to restore".to_string(),
+                ));
+            }
+        }
+        Command::Import(args) => {
+            let mapping = ImportMapping::load(&args.mapping)?;
+            let records = import::read_records(&args.input)?;
+            let report = import::import_keys(
+                &client,
+                &records,
+                &mapping,
+                ImportOptions {
+                    dry_run: args.dry_run,
+                    requests_per_second: args.rate,
+                    ..Default::default()
+                },
+                DashboardRequestOptions::default(),
+            )
+            .await?;
+            for item in &report.items {
+                println!("{item}");
+            }
+            println!("{report}");
+            if let Some(path) = &args.report {
+                report.save(path)?;
+            }
+            if report.unresolved().next().is_some() {
+                return Err(NebulAuthError::Config(
+                    "some rows were not imported".to_string(),
+                ));
+            }
+        }
+        Command::VerifyAudit {
+            file,
+            expected_head,
+        } => {
+            let verification = audit::verify_with(
+                &file,
+                &audit::VerifyOptions {
+                    chain_key: audit_chain_key(),
+                    expected_head,
+                },
+            )?;
+            println!("{verification}");
+            if !verification.is_intact() {
+                return Err(NebulAuthError::Config(
+                    "audit log has been modified".to_string(),
+                ));
+            }
+        }
+    }
+
+    Ok(())
+}
+
+fn connect(args: ConnectionArgs) -> Result<NebulAuthDashboardClient, NebulAuthError> {
+    let mut options = NebulAuthDashboardClientOptions {
+        auth: args
+            .token
+            .map(|bearer_token| DashboardAuth::Bearer { bearer_token }),
+        session_file: args.session_file,
+        ..Default::default()
+    };
+    if let Some(base_url) = args.base_url {
+        options.base_url = base_url;
+    }
+
+    let client = NebulAuthDashboardClient::new(options)?;
+    match args.audit_log {
+        Some(path) => {
+            let mut audit_log = AuditLog::open(path, args.actor)?
+                .with_error_handler(|e| eprintln!("warning: failed to write audit record: {e}"));
+            if let Some(key) = audit_chain_key() {
+                audit_log = audit_log.with_chain_key(key);
+            }
+            Ok(client.with_audit_log(Arc::new(audit_log)))
+        }
+        None => Ok(client),
+    }
+}
+
+/// Read from the environment only, so the key never shows up in `ps` or shell history.
+fn audit_chain_key() -> Option<Vec<u8>> {
+    std::env::var(AUDIT_CHAIN_KEY_ENV)
+        .ok()
+        .filter(|key| !key.is_empty())
+        .map(String::into_bytes)
+}
+
+async fn load_plan(
+    client: &NebulAuthDashboardClient,
+    args: &ConfigArgs,
+) -> Result<config::Plan, NebulAuthError> {
+    let desired = DesiredState::load(&args.file)?;
+    config::plan(
+        client,
+        &desired,
+        PlanOptions { prune: args.prune },
+        DashboardRequestOptions::default(),
+    )
+    .await
+}
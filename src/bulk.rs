@@ -0,0 +1,322 @@
+use chrono::{DateTime, Utc};
+use futures_util::stream::{self, StreamExt};
+use serde::{Deserialize, Serialize};
+use serde_json::{Map, Value};
+use std::collections::HashSet;
+use std::path::Path;
+use std::sync::Arc;
+
+use crate::{
+    DashboardKey, DashboardRequestOptions, DashboardResponse, KeyRevokeRequest, KeyUpdateRequest,
+    NebulAuthDashboardClient, NebulAuthError,
+};
+
+const DEFAULT_CONCURRENCY: usize = 4;
+
+#[derive(Debug, Clone, Default)]
+pub struct KeySelector {
+    pub ids: Option<Vec<String>>,
+    pub label_prefix: Option<String>,
+    pub metadata: Map<String, Value>,
+    pub expires_after: Option<DateTime<Utc>>,
+    pub expires_before: Option<DateTime<Utc>>,
+    pub include_revoked: bool,
+}
+
+impl KeySelector {
+    pub fn ids<I, S>(ids: I) -> Self
+    where
+        I: IntoIterator<Item = S>,
+        S: Into<String>,
+    {
+        Self {
+            ids: Some(ids.into_iter().map(Into::into).collect()),
+            ..Default::default()
+        }
+    }
+
+    pub fn label_prefix(prefix: impl Into<String>) -> Self {
+        Self {
+            label_prefix: Some(prefix.into()),
+            ..Default::default()
+        }
+    }
+
+    pub fn matches(&self, key: &DashboardKey) -> bool {
+        if !self.include_revoked && is_revoked(key) {
+            return false;
+        }
+        if let Some(ids) = &self.ids {
+            if !ids.iter().any(|id| id == &key.id) {
+                return false;
+            }
+        }
+        if let Some(prefix) = &self.label_prefix {
+            if !key
+                .label
+                .as_deref()
+                .is_some_and(|label| label.starts_with(prefix.as_str()))
+            {
+                return false;
+            }
+        }
+        if !self.metadata.is_empty() {
+            let Some(Value::Object(metadata)) = &key.metadata else {
+                return false;
+            };
+            if !self
+                .metadata
+                .iter()
+                .all(|(field, expected)| metadata.get(field) == Some(expected))
+            {
+                return false;
+            }
+        }
+        if self.expires_after.is_some() || self.expires_before.is_some() {
+            let Some(expires_at) = key.expires_at.as_deref().and_then(parse_timestamp) else {
+                return false;
+            };
+            if self.expires_after.is_some_and(|after| expires_at < after) {
+                return false;
+            }
+            if self
+                .expires_before
+                .is_some_and(|before| expires_at > before)
+            {
+                return false;
+            }
+        }
+        true
+    }
+}
+
+pub(crate) fn is_revoked(key: &DashboardKey) -> bool {
+    key.revoked == Some(true) || key.status.as_deref() == Some("revoked")
+}
+
+pub(crate) fn parse_timestamp(value: &str) -> Option<DateTime<Utc>> {
+    DateTime::parse_from_rfc3339(value)
+        .ok()
+        .map(|timestamp| timestamp.with_timezone(&Utc))
+}
+
+#[derive(Debug, Clone)]
+pub enum BulkOperation {
+    Revoke(KeyRevokeRequest),
+    Update(KeyUpdateRequest),
+    Extend { hours: i64 },
+    ResetHwid,
+}
+
+#[derive(Debug, Clone)]
+pub struct BulkProgress {
+    pub key_id: String,
+    pub succeeded: bool,
+    pub completed: usize,
+    pub failed: usize,
+    pub total: usize,
+}
+
+pub type ProgressCallback = Arc<dyn Fn(&BulkProgress) + Send + Sync>;
+
+#[derive(Clone)]
+pub struct BulkOptions {
+    pub concurrency: usize,
+    pub dry_run: bool,
+    pub resume: Option<BulkReport>,
+    pub on_progress: Option<ProgressCallback>,
+}
+
+impl Default for BulkOptions {
+    fn default() -> Self {
+        Self {
+            concurrency: DEFAULT_CONCURRENCY,
+            dry_run: false,
+            resume: None,
+            on_progress: None,
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "status", content = "error", rename_all = "snake_case")]
+pub enum BulkOutcome {
+    Planned,
+    Succeeded,
+    Failed(String),
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BulkKeyResult {
+    pub key_id: String,
+    pub label: Option<String>,
+    pub outcome: BulkOutcome,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct BulkReport {
+    pub dry_run: bool,
+    pub results: Vec<BulkKeyResult>,
+}
+
+impl BulkReport {
+    pub fn succeeded(&self) -> impl Iterator<Item = &BulkKeyResult> {
+        self.results
+            .iter()
+            .filter(|result| result.outcome == BulkOutcome::Succeeded)
+    }
+
+    pub fn failed(&self) -> impl Iterator<Item = &BulkKeyResult> {
+        self.results
+            .iter()
+            .filter(|result| matches!(result.outcome, BulkOutcome::Failed(_)))
+    }
+
+    pub fn load(path: impl AsRef<Path>) -> Result<Self, NebulAuthError> {
+        let contents = std::fs::read_to_string(path)?;
+        serde_json::from_str(&contents)
+            .map_err(|e| NebulAuthError::Decode(format!("invalid bulk report: {e}")))
+    }
+
+    pub fn save(&self, path: impl AsRef<Path>) -> Result<(), NebulAuthError> {
+        let contents = serde_json::to_string_pretty(self)
+            .map_err(|e| NebulAuthError::Config(format!("failed to serialize bulk report: {e}")))?;
+        std::fs::write(path, contents)?;
+        Ok(())
+    }
+}
+
+pub async fn select_keys(
+    client: &NebulAuthDashboardClient,
+    selector: &KeySelector,
+    options: DashboardRequestOptions,
+) -> Result<Vec<DashboardKey>, NebulAuthError> {
+    let keys: Vec<DashboardKey> = client
+        .list_keys(options)
+        .await?
+        .error_for_status()?
+        .items()?;
+    Ok(keys
+        .into_iter()
+        .filter(|key| selector.matches(key))
+        .collect())
+}
+
+pub async fn run(
+    client: &NebulAuthDashboardClient,
+    selector: &KeySelector,
+    operation: &BulkOperation,
+    bulk_options: BulkOptions,
+    options: DashboardRequestOptions,
+) -> Result<BulkReport, NebulAuthError> {
+    if let BulkOperation::Extend { hours } = operation {
+        if *hours <= 0 {
+            return Err(NebulAuthError::Config(
+                "extend hours must be positive".to_string(),
+            ));
+        }
+    }
+
+    let keys = select_keys(client, selector, options.clone()).await?;
+
+    let previous: Vec<BulkKeyResult> = bulk_options
+        .resume
+        .iter()
+        .flat_map(|report| report.succeeded().cloned())
+        .collect();
+    let already_done: HashSet<&str> = previous.iter().map(|r| r.key_id.as_str()).collect();
+    let pending: Vec<DashboardKey> = keys
+        .into_iter()
+        .filter(|key| !already_done.contains(key.id.as_str()))
+        .collect();
+
+    let mut report = BulkReport {
+        dry_run: bulk_options.dry_run,
+        results: Vec::with_capacity(previous.len() + pending.len()),
+    };
+    report.results.extend(previous.iter().cloned());
+
+    if bulk_options.dry_run {
+        report
+            .results
+            .extend(pending.into_iter().map(|key| BulkKeyResult {
+                key_id: key.id,
+                label: key.label,
+                outcome: BulkOutcome::Planned,
+            }));
+        return Ok(report);
+    }
+
+    let total = pending.len();
+    let mut completed = 0;
+    let mut failed = 0;
+    let mut results = stream::iter(pending)
+        .map(|key| {
+            let options = options.clone();
+            async move {
+                let outcome = match apply(client, &key, operation, options).await {
+                    Ok(_) => BulkOutcome::Succeeded,
+                    Err(e) => BulkOutcome::Failed(e.to_string()),
+                };
+                BulkKeyResult {
+                    key_id: key.id,
+                    label: key.label,
+                    outcome,
+                }
+            }
+        })
+        .buffer_unordered(bulk_options.concurrency.max(1));
+
+    while let Some(result) = results.next().await {
+        completed += 1;
+        let succeeded = result.outcome == BulkOutcome::Succeeded;
+        if !succeeded {
+            failed += 1;
+        }
+        if let Some(on_progress) = &bulk_options.on_progress {
+            on_progress(&BulkProgress {
+                key_id: result.key_id.clone(),
+                succeeded,
+                completed,
+                failed,
+                total,
+            });
+        }
+        report.results.push(result);
+    }
+
+    Ok(report)
+}
+
+async fn apply(
+    client: &NebulAuthDashboardClient,
+    key: &DashboardKey,
+    operation: &BulkOperation,
+    options: DashboardRequestOptions,
+) -> Result<DashboardResponse, NebulAuthError> {
+    let response = match operation {
+        BulkOperation::Revoke(payload) => {
+            client.delete_key(&key.id, payload.clone(), options).await
+        }
+        BulkOperation::Update(payload) => {
+            client.update_key(&key.id, payload.clone(), options).await
+        }
+        BulkOperation::Extend { hours } => {
+            let duration_hours = key.duration_hours.ok_or_else(|| {
+                NebulAuthError::Config(format!("key {} has no duration to extend", key.id))
+            })?;
+            client
+                .update_key(
+                    &key.id,
+                    KeyUpdateRequest {
+                        duration_hours: Some(duration_hours + hours),
+                        ..Default::default()
+                    },
+                    options,
+                )
+                .await
+        }
+        BulkOperation::ResetHwid => client.reset_key_hwid(&key.id, options).await,
+    }?;
+    response.error_for_status()
+}
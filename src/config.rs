@@ -0,0 +1,677 @@
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, HashSet};
+use std::fmt;
+use std::path::Path;
+
+use crate::enums::same_scopes;
+use crate::{
+    ApiAuthMode, ApiToken, ApiTokenCreateRequest, ApiTokenScope, ApiTokenUpdateRequest,
+    BlacklistCreateRequest, BlacklistEntry, BlacklistType, Checkpoint, CheckpointCreateRequest,
+    CheckpointStepInput, CheckpointUpdateRequest, Customer, CustomerUpdateRequest,
+    DashboardRequestOptions, DashboardResponse, NebulAuthDashboardClient, NebulAuthError,
+    ReplayProtectionMode, TeamMember, TeamMemberCreateRequest, TeamMemberUpdateRequest, TeamRole,
+};
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct DesiredState {
+    #[serde(default)]
+    pub customer: Option<CustomerConfig>,
+    #[serde(default)]
+    pub checkpoints: Option<Vec<CheckpointConfig>>,
+    #[serde(default)]
+    pub blacklist: Option<Vec<BlacklistConfig>>,
+    #[serde(default)]
+    pub api_tokens: Option<Vec<ApiTokenConfig>>,
+    #[serde(default)]
+    pub team: Option<Vec<TeamMemberConfig>>,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct CustomerConfig {
+    #[serde(default)]
+    pub require_discord_redeem: Option<bool>,
+    #[serde(default)]
+    pub require_hwid: Option<bool>,
+    #[serde(default)]
+    pub paused: Option<bool>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct CheckpointConfig {
+    pub name: String,
+    pub duration_hours: i64,
+    #[serde(default = "default_true")]
+    pub is_active: bool,
+    #[serde(default)]
+    pub referrer_domain_only: Option<bool>,
+    #[serde(default)]
+    pub steps: Vec<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct BlacklistConfig {
+    pub r#type: BlacklistType,
+    pub value: String,
+    #[serde(default)]
+    pub reason: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct ApiTokenConfig {
+    #[serde(default)]
+    pub id: Option<String>,
+    pub scopes: Vec<ApiTokenScope>,
+    pub replay_protection: ReplayProtectionMode,
+    pub auth_mode: ApiAuthMode,
+    #[serde(default)]
+    pub expires_at: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct TeamMemberConfig {
+    pub email: String,
+    pub role: TeamRole,
+    #[serde(default)]
+    pub password: Option<String>,
+}
+
+fn default_true() -> bool {
+    true
+}
+
+impl DesiredState {
+    pub fn from_yaml_str(input: &str) -> Result<Self, NebulAuthError> {
+        serde_yaml::from_str(input)
+            .map_err(|e| NebulAuthError::Config(format!("invalid YAML config: {e}")))
+    }
+
+    pub fn from_toml_str(input: &str) -> Result<Self, NebulAuthError> {
+        toml::from_str(input)
+            .map_err(|e| NebulAuthError::Config(format!("invalid TOML config: {e}")))
+    }
+
+    pub fn load(path: impl AsRef<Path>) -> Result<Self, NebulAuthError> {
+        let path = path.as_ref();
+        let contents = std::fs::read_to_string(path)?;
+        match path.extension().and_then(|ext| ext.to_str()) {
+            Some("yaml") | Some("yml") => Self::from_yaml_str(&contents),
+            Some("toml") => Self::from_toml_str(&contents),
+            _ => Err(NebulAuthError::Config(format!(
+                "unsupported config file extension: {}",
+                path.display()
+            ))),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct LiveState {
+    pub customer: Customer,
+    pub checkpoints: Vec<Checkpoint>,
+    pub blacklist: Vec<BlacklistEntry>,
+    pub api_tokens: Vec<ApiToken>,
+    pub team: Vec<TeamMember>,
+}
+
+impl LiveState {
+    pub async fn fetch(
+        client: &NebulAuthDashboardClient,
+        desired: &DesiredState,
+        options: &DashboardRequestOptions,
+    ) -> Result<Self, NebulAuthError> {
+        let mut live = LiveState::default();
+
+        if desired.customer.is_some() {
+            let response = client
+                .get_customer(options.clone())
+                .await?
+                .error_for_status()?;
+            live.customer = response.json_unwrapped("customer")?;
+        }
+        if desired.checkpoints.is_some() {
+            live.checkpoints = client
+                .list_checkpoints(options.clone())
+                .await?
+                .error_for_status()?
+                .items()?;
+        }
+        if desired.blacklist.is_some() {
+            live.blacklist = client
+                .list_blacklist(options.clone())
+                .await?
+                .error_for_status()?
+                .items()?;
+        }
+        if desired.api_tokens.is_some() {
+            live.api_tokens = client
+                .list_api_tokens(options.clone())
+                .await?
+                .error_for_status()?
+                .items()?;
+        }
+        if desired.team.is_some() {
+            live.team = client
+                .list_users(options.clone())
+                .await?
+                .error_for_status()?
+                .items()?;
+        }
+
+        Ok(live)
+    }
+}
+
+#[derive(Debug, Clone)]
+pub enum Change {
+    UpdateCustomer(CustomerUpdateRequest),
+    CreateCheckpoint(CheckpointCreateRequest),
+    UpdateCheckpoint {
+        id: String,
+        name: String,
+        update: CheckpointUpdateRequest,
+    },
+    DeleteCheckpoint {
+        id: String,
+        name: String,
+    },
+    CreateBlacklistEntry(BlacklistCreateRequest),
+    DeleteBlacklistEntry {
+        id: String,
+        r#type: BlacklistType,
+        value: String,
+    },
+    CreateApiToken(ApiTokenCreateRequest),
+    UpdateApiToken {
+        id: String,
+        update: ApiTokenUpdateRequest,
+    },
+    DeleteApiToken {
+        id: String,
+    },
+    CreateTeamMember(TeamMemberCreateRequest),
+    UpdateTeamMember {
+        id: String,
+        email: String,
+        update: TeamMemberUpdateRequest,
+    },
+    DeleteTeamMember {
+        id: String,
+        email: String,
+    },
+}
+
+impl fmt::Display for Change {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Change::UpdateCustomer(update) => {
+                write!(f, "~ customer")?;
+                write_flag(f, "require_discord_redeem", update.require_discord_redeem)?;
+                write_flag(f, "require_hwid", update.require_hwid)?;
+                write_flag(f, "paused", update.paused)
+            }
+            Change::CreateCheckpoint(create) => write!(f, "+ checkpoint '{}'", create.name),
+            Change::UpdateCheckpoint { name, .. } => write!(f, "~ checkpoint '{name}'"),
+            Change::DeleteCheckpoint { name, .. } => write!(f, "- checkpoint '{name}'"),
+            Change::CreateBlacklistEntry(create) => {
+                write!(f, "+ blacklist {} '{}'", create.r#type, create.value)
+            }
+            Change::DeleteBlacklistEntry { r#type, value, .. } => {
+                write!(f, "- blacklist {} '{}'", r#type, value)
+            }
+            Change::CreateApiToken(create) => {
+                write!(f, "+ api token [{}]", join_scopes(&create.scopes))
+            }
+            Change::UpdateApiToken { id, .. } => write!(f, "~ api token {id}"),
+            Change::DeleteApiToken { id } => write!(f, "- api token {id}"),
+            Change::CreateTeamMember(create) => {
+                write!(f, "+ team member {} ({})", create.email, create.role)
+            }
+            Change::UpdateTeamMember { email, update, .. } => match &update.role {
+                Some(role) => write!(f, "~ team member {email} -> {role}"),
+                None => write!(f, "~ team member {email}"),
+            },
+            Change::DeleteTeamMember { email, .. } => write!(f, "- team member {email}"),
+        }
+    }
+}
+
+fn write_flag(f: &mut fmt::Formatter<'_>, name: &str, value: Option<bool>) -> fmt::Result {
+    match value {
+        Some(value) => write!(f, " {name}={value}"),
+        None => Ok(()),
+    }
+}
+
+fn join_scopes(scopes: &[ApiTokenScope]) -> String {
+    scopes
+        .iter()
+        .map(ApiTokenScope::as_str)
+        .collect::<Vec<_>>()
+        .join(", ")
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct Plan {
+    pub changes: Vec<Change>,
+}
+
+impl Plan {
+    pub fn is_empty(&self) -> bool {
+        self.changes.is_empty()
+    }
+}
+
+impl fmt::Display for Plan {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.changes.is_empty() {
+            return writeln!(f, "No changes.");
+        }
+        for change in &self.changes {
+            writeln!(f, "{change}")?;
+        }
+        writeln!(f, "{} change(s).", self.changes.len())
+    }
+}
+
+#[derive(Debug, Clone, Copy, Default)]
+pub struct PlanOptions {
+    pub prune: bool,
+}
+
+pub fn diff(
+    desired: &DesiredState,
+    live: &LiveState,
+    options: PlanOptions,
+) -> Result<Plan, NebulAuthError> {
+    let mut changes = Vec::new();
+
+    if let Some(customer) = &desired.customer {
+        let update = CustomerUpdateRequest {
+            require_discord_redeem: changed(
+                customer.require_discord_redeem,
+                live.customer.require_discord_redeem,
+            ),
+            require_hwid: changed(customer.require_hwid, live.customer.require_hwid),
+            paused: changed(customer.paused, live.customer.paused),
+        };
+        if update.require_discord_redeem.is_some()
+            || update.require_hwid.is_some()
+            || update.paused.is_some()
+        {
+            changes.push(Change::UpdateCustomer(update));
+        }
+    }
+
+    if let Some(checkpoints) = &desired.checkpoints {
+        diff_checkpoints(checkpoints, &live.checkpoints, options, &mut changes);
+    }
+    if let Some(blacklist) = &desired.blacklist {
+        diff_blacklist(blacklist, &live.blacklist, options, &mut changes);
+    }
+    if let Some(api_tokens) = &desired.api_tokens {
+        diff_api_tokens(api_tokens, &live.api_tokens, options, &mut changes)?;
+    }
+    if let Some(team) = &desired.team {
+        diff_team(team, &live.team, options, &mut changes)?;
+    }
+
+    Ok(Plan { changes })
+}
+
+fn changed<T: PartialEq + Copy>(desired: Option<T>, live: Option<T>) -> Option<T> {
+    desired.filter(|value| live != Some(*value))
+}
+
+fn diff_checkpoints(
+    desired: &[CheckpointConfig],
+    live: &[Checkpoint],
+    options: PlanOptions,
+    changes: &mut Vec<Change>,
+) {
+    let live_by_name: HashMap<&str, &Checkpoint> =
+        live.iter().map(|c| (c.name.as_str(), c)).collect();
+
+    for checkpoint in desired {
+        let steps: Vec<CheckpointStepInput> = checkpoint
+            .steps
+            .iter()
+            .map(|ad_url| CheckpointStepInput {
+                ad_url: ad_url.clone(),
+            })
+            .collect();
+
+        let Some(existing) = live_by_name.get(checkpoint.name.as_str()) else {
+            changes.push(Change::CreateCheckpoint(CheckpointCreateRequest {
+                name: checkpoint.name.clone(),
+                duration_hours: checkpoint.duration_hours,
+                is_active: checkpoint.is_active,
+                referrer_domain_only: checkpoint.referrer_domain_only,
+                steps,
+            }));
+            continue;
+        };
+
+        let live_steps: Vec<&str> = existing.steps.iter().map(|s| s.ad_url.as_str()).collect();
+        let update = CheckpointUpdateRequest {
+            name: None,
+            duration_hours: changed(Some(checkpoint.duration_hours), existing.duration_hours),
+            is_active: changed(Some(checkpoint.is_active), existing.is_active),
+            referrer_domain_only: changed(
+                checkpoint.referrer_domain_only,
+                existing.referrer_domain_only,
+            ),
+            steps: (live_steps != checkpoint.steps).then_some(steps),
+        };
+        if update.duration_hours.is_some()
+            || update.is_active.is_some()
+            || update.referrer_domain_only.is_some()
+            || update.steps.is_some()
+        {
+            changes.push(Change::UpdateCheckpoint {
+                id: existing.id.clone(),
+                name: existing.name.clone(),
+                update,
+            });
+        }
+    }
+
+    if options.prune {
+        let desired_names: HashSet<&str> = desired.iter().map(|c| c.name.as_str()).collect();
+        for checkpoint in live {
+            if !desired_names.contains(checkpoint.name.as_str()) {
+                changes.push(Change::DeleteCheckpoint {
+                    id: checkpoint.id.clone(),
+                    name: checkpoint.name.clone(),
+                });
+            }
+        }
+    }
+}
+
+fn diff_blacklist(
+    desired: &[BlacklistConfig],
+    live: &[BlacklistEntry],
+    options: PlanOptions,
+    changes: &mut Vec<Change>,
+) {
+    let live_keys: HashSet<(&str, &str)> = live
+        .iter()
+        .map(|e| (e.r#type.as_str(), e.value.as_str()))
+        .collect();
+
+    for entry in desired {
+        if !live_keys.contains(&(entry.r#type.as_str(), entry.value.as_str())) {
+            changes.push(Change::CreateBlacklistEntry(BlacklistCreateRequest {
+                r#type: entry.r#type.clone(),
+                value: entry.value.clone(),
+                reason: entry.reason.clone(),
+            }));
+        }
+    }
+
+    if options.prune {
+        let desired_keys: HashSet<(&str, &str)> = desired
+            .iter()
+            .map(|e| (e.r#type.as_str(), e.value.as_str()))
+            .collect();
+        for entry in live {
+            if !desired_keys.contains(&(entry.r#type.as_str(), entry.value.as_str())) {
+                changes.push(Change::DeleteBlacklistEntry {
+                    id: entry.id.clone(),
+                    r#type: entry.r#type.clone(),
+                    value: entry.value.clone(),
+                });
+            }
+        }
+    }
+}
+
+fn diff_api_tokens(
+    desired: &[ApiTokenConfig],
+    live: &[ApiToken],
+    options: PlanOptions,
+    changes: &mut Vec<Change>,
+) -> Result<(), NebulAuthError> {
+    let mut matched: HashSet<&str> = HashSet::new();
+    let mut resolved: Vec<Option<&ApiToken>> = Vec::with_capacity(desired.len());
+
+    // Tokens pinned by `id` are claimed first so a config-only entry can never take them.
+    for token in desired {
+        let existing = match &token.id {
+            Some(id) => {
+                let existing = live.iter().find(|t| &t.id == id).ok_or_else(|| {
+                    NebulAuthError::Config(format!("api token {id} does not exist"))
+                })?;
+                if !matched.insert(existing.id.as_str()) {
+                    return Err(NebulAuthError::Config(format!(
+                        "api token {id} is listed more than once"
+                    )));
+                }
+                Some(existing)
+            }
+            None => None,
+        };
+        resolved.push(existing);
+    }
+
+    for (token, existing) in desired.iter().zip(resolved.iter_mut()) {
+        if token.id.is_none() {
+            *existing = match_unpinned_token(token, live, &matched)?;
+            if let Some(existing) = existing {
+                matched.insert(existing.id.as_str());
+            }
+        }
+    }
+
+    for (token, existing) in desired.iter().zip(resolved) {
+        let Some(existing) = existing else {
+            changes.push(Change::CreateApiToken(ApiTokenCreateRequest {
+                scopes: token.scopes.clone(),
+                replay_protection: token.replay_protection.clone(),
+                auth_mode: token.auth_mode.clone(),
+                expires_at: token.expires_at.clone(),
+            }));
+            continue;
+        };
+        let update = ApiTokenUpdateRequest {
+            scopes: (!same_scopes(&token.scopes, &existing.scopes)).then(|| token.scopes.clone()),
+            replay_protection: (existing.replay_protection.as_ref()
+                != Some(&token.replay_protection))
+            .then(|| token.replay_protection.clone()),
+            auth_mode: (existing.auth_mode.as_ref() != Some(&token.auth_mode))
+                .then(|| token.auth_mode.clone()),
+            expires_at: token
+                .expires_at
+                .clone()
+                .filter(|expires_at| existing.expires_at.as_ref() != Some(expires_at)),
+        };
+        if update.scopes.is_some()
+            || update.replay_protection.is_some()
+            || update.auth_mode.is_some()
+            || update.expires_at.is_some()
+        {
+            changes.push(Change::UpdateApiToken {
+                id: existing.id.clone(),
+                update,
+            });
+        }
+    }
+
+    if options.prune {
+        for token in live {
+            if !matched.contains(token.id.as_str()) {
+                changes.push(Change::DeleteApiToken {
+                    id: token.id.clone(),
+                });
+            }
+        }
+    }
+
+    Ok(())
+}
+
+/// Picks the live token for an entry without `id`. Candidates with the desired expiry win;
+/// if the rest still differ the match is refused rather than guessed, since a prune would
+/// delete whichever token lost.
+fn match_unpinned_token<'a>(
+    desired: &ApiTokenConfig,
+    live: &'a [ApiToken],
+    matched: &HashSet<&str>,
+) -> Result<Option<&'a ApiToken>, NebulAuthError> {
+    let mut candidates: Vec<&ApiToken> = live
+        .iter()
+        .filter(|t| !matched.contains(t.id.as_str()) && token_matches(desired, t))
+        .collect();
+    if desired.expires_at.is_some()
+        && candidates
+            .iter()
+            .any(|t| t.expires_at == desired.expires_at)
+    {
+        candidates.retain(|t| t.expires_at == desired.expires_at);
+    }
+    candidates.sort_by(|a, b| a.id.cmp(&b.id));
+
+    match candidates.as_slice() {
+        [] => Ok(None),
+        [first, rest @ ..] if rest.iter().all(|t| t.expires_at == first.expires_at) => {
+            Ok(Some(first))
+        }
+        _ => Err(NebulAuthError::Config(format!(
+            "api token config matches several live tokens ({}); set `id` to pick one",
+            candidates
+                .iter()
+                .map(|t| t.id.as_str())
+                .collect::<Vec<_>>()
+                .join(", ")
+        ))),
+    }
+}
+
+fn token_matches(desired: &ApiTokenConfig, live: &ApiToken) -> bool {
+    same_scopes(&desired.scopes, &live.scopes)
+        && live.replay_protection.as_ref() == Some(&desired.replay_protection)
+        && live.auth_mode.as_ref() == Some(&desired.auth_mode)
+}
+
+fn diff_team(
+    desired: &[TeamMemberConfig],
+    live: &[TeamMember],
+    options: PlanOptions,
+    changes: &mut Vec<Change>,
+) -> Result<(), NebulAuthError> {
+    let live_by_email: HashMap<String, &TeamMember> =
+        live.iter().map(|m| (m.email.to_lowercase(), m)).collect();
+
+    for member in desired {
+        let Some(existing) = live_by_email.get(&member.email.to_lowercase()) else {
+            let password = member.password.clone().ok_or_else(|| {
+                NebulAuthError::Config(format!(
+                    "team member {} does not exist and has no password to create it with",
+                    member.email
+                ))
+            })?;
+            changes.push(Change::CreateTeamMember(TeamMemberCreateRequest {
+                email: member.email.clone(),
+                password,
+                role: member.role.clone(),
+            }));
+            continue;
+        };
+
+        if existing.role.as_ref() != Some(&member.role) {
+            changes.push(Change::UpdateTeamMember {
+                id: existing.id.clone(),
+                email: existing.email.clone(),
+                update: TeamMemberUpdateRequest {
+                    role: Some(member.role.clone()),
+                    password: None,
+                },
+            });
+        }
+    }
+
+    if options.prune {
+        let desired_emails: HashSet<String> =
+            desired.iter().map(|m| m.email.to_lowercase()).collect();
+        for member in live {
+            if member.role == Some(TeamRole::Owner) {
+                continue;
+            }
+            if !desired_emails.contains(&member.email.to_lowercase()) {
+                changes.push(Change::DeleteTeamMember {
+                    id: member.id.clone(),
+                    email: member.email.clone(),
+                });
+            }
+        }
+    }
+
+    Ok(())
+}
+
+pub async fn plan(
+    client: &NebulAuthDashboardClient,
+    desired: &DesiredState,
+    plan_options: PlanOptions,
+    options: DashboardRequestOptions,
+) -> Result<Plan, NebulAuthError> {
+    let live = LiveState::fetch(client, desired, &options).await?;
+    diff(desired, &live, plan_options)
+}
+
+pub async fn apply(
+    client: &NebulAuthDashboardClient,
+    plan: &Plan,
+    options: DashboardRequestOptions,
+) -> Result<Vec<(Change, DashboardResponse)>, NebulAuthError> {
+    let mut applied = Vec::with_capacity(plan.changes.len());
+
+    for change in &plan.changes {
+        let options = options.clone();
+        let response = match change.clone() {
+            Change::UpdateCustomer(update) => client.update_customer(update, options).await,
+            Change::CreateCheckpoint(create) => client.create_checkpoint(create, options).await,
+            Change::UpdateCheckpoint { id, update, .. } => {
+                client.update_checkpoint(&id, update, options).await
+            }
+            Change::DeleteCheckpoint { id, .. } => client.delete_checkpoint(&id, options).await,
+            Change::CreateBlacklistEntry(create) => {
+                client.create_blacklist_entry(create, options).await
+            }
+            Change::DeleteBlacklistEntry { id, .. } => {
+                client.delete_blacklist_entry(&id, options).await
+            }
+            Change::CreateApiToken(create) => client.create_api_token(create, options).await,
+            Change::UpdateApiToken { id, update } => {
+                client.update_api_token(&id, update, options).await
+            }
+            Change::DeleteApiToken { id } => client.delete_api_token(&id, options).await,
+            Change::CreateTeamMember(create) => client.create_user(create, options).await,
+            Change::UpdateTeamMember { id, update, .. } => {
+                client.update_user(&id, update, options).await
+            }
+            Change::DeleteTeamMember { id, .. } => client.delete_user(&id, options).await,
+        }?
+        .error_for_status()
+        .map_err(|e| match e {
+            NebulAuthError::Api {
+                status_code,
+                message,
+            } => NebulAuthError::Api {
+                status_code,
+                message: format!("failed to apply '{change}': {message}"),
+            },
+            other => other,
+        })?;
+
+        applied.push((change.clone(), response));
+    }
+
+    Ok(applied)
+}
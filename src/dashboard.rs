@@ -1,12 +1,22 @@
 use reqwest::header::{HeaderMap, HeaderName, HeaderValue, CONTENT_TYPE};
+use serde::de::DeserializeOwned;
 use serde::Serialize;
 use serde_json::{json, Value};
 use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, RwLock, RwLockReadGuard, RwLockWriteGuard};
 use std::time::Duration;
 
-use crate::NebulAuthError;
+use crate::audit::AuditLog;
+use crate::telemetry::{self, RequestTelemetry};
+use crate::transport::{HttpRequest, ReqwestTransport, StreamingResponse, Transport};
+use crate::{
+    ApiAuthMode, ApiTokenScope, BatchFormat, BlacklistType, NebulAuthError, ReplayProtectionMode,
+    TeamRole,
+};
 
 const DEFAULT_DASHBOARD_BASE_URL: &str = "https://api.nebulauth.com/dashboard";
+const SESSION_COOKIE_NAME: &str = "mc_session";
 
 #[derive(Debug, Clone)]
 pub enum DashboardAuth {
@@ -19,6 +29,9 @@ pub struct NebulAuthDashboardClientOptions {
     pub base_url: String,
     pub auth: Option<DashboardAuth>,
     pub timeout_ms: u64,
+    pub credentials: Option<LoginRequest>,
+    pub session_file: Option<PathBuf>,
+    pub auto_relogin: bool,
 }
 
 impl Default for NebulAuthDashboardClientOptions {
@@ -27,6 +40,9 @@ impl Default for NebulAuthDashboardClientOptions {
             base_url: DEFAULT_DASHBOARD_BASE_URL.to_string(),
             auth: None,
             timeout_ms: 15_000,
+            credentials: None,
+            session_file: None,
+            auto_relogin: true,
         }
     }
 }
@@ -46,6 +62,90 @@ pub struct DashboardResponse {
     pub headers: HashMap<String, String>,
 }
 
+impl DashboardResponse {
+    /// Builds a response from a raw body, decoding it as JSON where possible.
+    pub(crate) fn from_body(status: u16, headers: Vec<(String, String)>, text: String) -> Self {
+        let data = if text.trim().is_empty() {
+            json!({})
+        } else {
+            serde_json::from_str::<Value>(&text).unwrap_or(Value::String(text))
+        };
+
+        Self {
+            status_code: status,
+            ok: (200..300).contains(&status),
+            data,
+            headers: headers.into_iter().collect(),
+        }
+    }
+
+    pub fn error_for_status(self) -> Result<Self, NebulAuthError> {
+        if self.ok {
+            return Ok(self);
+        }
+
+        let message = ["error", "message"]
+            .iter()
+            .find_map(|field| self.data.get(*field).and_then(Value::as_str))
+            .map(str::to_string)
+            .unwrap_or_else(|| match &self.data {
+                Value::String(text) => text.clone(),
+                other => other.to_string(),
+            });
+
+        Err(NebulAuthError::Api {
+            status_code: self.status_code,
+            message,
+        })
+    }
+
+    pub fn json<T: DeserializeOwned>(&self) -> Result<T, NebulAuthError> {
+        serde_json::from_value(self.data.clone()).map_err(|e| NebulAuthError::Decode(e.to_string()))
+    }
+
+    pub fn json_unwrapped<T: DeserializeOwned>(&self, field: &str) -> Result<T, NebulAuthError> {
+        let value = match self.data.get(field) {
+            Some(inner @ Value::Object(_)) => inner,
+            _ => &self.data,
+        };
+        serde_json::from_value(value.clone()).map_err(|e| NebulAuthError::Decode(e.to_string()))
+    }
+
+    /// Decodes a list endpoint: either a bare array or an array under one of the known
+    /// envelope fields. Any other shape is a decode error rather than a guess.
+    pub fn items<T: DeserializeOwned>(&self) -> Result<Vec<T>, NebulAuthError> {
+        let list = match &self.data {
+            Value::Array(_) => &self.data,
+            Value::Object(map) => LIST_ENVELOPE_FIELDS
+                .iter()
+                .find_map(|field| map.get(*field).filter(|value| value.is_array()))
+                .ok_or_else(|| {
+                    NebulAuthError::Decode("response does not contain a list".to_string())
+                })?,
+            _ => {
+                return Err(NebulAuthError::Decode(
+                    "response does not contain a list".to_string(),
+                ))
+            }
+        };
+
+        serde_json::from_value(list.clone()).map_err(|e| NebulAuthError::Decode(e.to_string()))
+    }
+}
+
+/// Fields list endpoints wrap their array in, checked in this order.
+const LIST_ENVELOPE_FIELDS: [&str; 9] = [
+    "data",
+    "items",
+    "results",
+    "keys",
+    "users",
+    "sessions",
+    "checkpoints",
+    "entries",
+    "tokens",
+];
+
 #[derive(Debug, Clone, Serialize)]
 pub struct LoginRequest {
     pub email: String,
@@ -66,13 +166,13 @@ pub struct CustomerUpdateRequest {
 pub struct TeamMemberCreateRequest {
     pub email: String,
     pub password: String,
-    pub role: String,
+    pub role: TeamRole,
 }
 
 #[derive(Debug, Clone, Serialize, Default)]
 pub struct TeamMemberUpdateRequest {
     #[serde(skip_serializing_if = "Option::is_none")]
-    pub role: Option<String>,
+    pub role: Option<TeamRole>,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub password: Option<String>,
 }
@@ -87,7 +187,7 @@ pub struct KeyCreateRequest {
     pub metadata: Option<Value>,
 }
 
-#[derive(Debug, Clone, Serialize)]
+#[derive(Debug, Clone, Serialize, Default)]
 pub struct KeyBatchCreateRequest {
     pub count: i64,
     #[serde(skip_serializing_if = "Option::is_none")]
@@ -173,7 +273,7 @@ pub struct CheckpointUpdateRequest {
 
 #[derive(Debug, Clone, Serialize)]
 pub struct BlacklistCreateRequest {
-    pub r#type: String,
+    pub r#type: BlacklistType,
     pub value: String,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub reason: Option<String>,
@@ -181,9 +281,9 @@ pub struct BlacklistCreateRequest {
 
 #[derive(Debug, Clone, Serialize)]
 pub struct ApiTokenCreateRequest {
-    pub scopes: Vec<String>,
-    pub replay_protection: String,
-    pub auth_mode: String,
+    pub scopes: Vec<ApiTokenScope>,
+    pub replay_protection: ReplayProtectionMode,
+    pub auth_mode: ApiAuthMode,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub expires_at: Option<String>,
 }
@@ -191,19 +291,23 @@ pub struct ApiTokenCreateRequest {
 #[derive(Debug, Clone, Serialize, Default)]
 pub struct ApiTokenUpdateRequest {
     #[serde(skip_serializing_if = "Option::is_none")]
-    pub scopes: Option<Vec<String>>,
+    pub scopes: Option<Vec<ApiTokenScope>>,
     #[serde(skip_serializing_if = "Option::is_none")]
-    pub replay_protection: Option<String>,
+    pub replay_protection: Option<ReplayProtectionMode>,
     #[serde(skip_serializing_if = "Option::is_none")]
-    pub auth_mode: Option<String>,
+    pub auth_mode: Option<ApiAuthMode>,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub expires_at: Option<String>,
 }
 
 pub struct NebulAuthDashboardClient {
     base_url: String,
-    default_auth: Option<DashboardAuth>,
-    client: reqwest::Client,
+    default_auth: RwLock<Option<DashboardAuth>>,
+    credentials: RwLock<Option<LoginRequest>>,
+    session_file: Option<PathBuf>,
+    auto_relogin: bool,
+    audit_log: Option<Arc<AuditLog>>,
+    transport: Arc<dyn Transport>,
 }
 
 impl NebulAuthDashboardClient {
@@ -218,33 +322,104 @@ impl NebulAuthDashboardClient {
             .timeout(Duration::from_millis(options.timeout_ms))
             .build()?;
 
+        let default_auth = match (options.auth, &options.session_file) {
+            (Some(auth), _) => Some(auth),
+            (None, Some(path)) => load_session_file(path)?
+                .map(|session_cookie| DashboardAuth::Session { session_cookie }),
+            (None, None) => None,
+        };
+
         Ok(Self {
             base_url,
-            default_auth: options.auth,
-            client,
+            default_auth: RwLock::new(default_auth),
+            credentials: RwLock::new(options.credentials),
+            session_file: options.session_file,
+            auto_relogin: options.auto_relogin,
+            audit_log: None,
+            transport: Arc::new(ReqwestTransport::new(client)),
         })
     }
 
+    /// Records every mutating (non-GET) request in `audit_log`.
+    pub fn with_audit_log(mut self, audit_log: Arc<AuditLog>) -> Self {
+        self.audit_log = Some(audit_log);
+        self
+    }
+
+    /// Sends requests through `transport` instead of the built-in HTTP client.
+    pub fn with_transport(mut self, transport: Arc<dyn Transport>) -> Self {
+        self.transport = transport;
+        self
+    }
+
+    pub fn session_cookie(&self) -> Option<String> {
+        match &*read_lock(&self.default_auth) {
+            Some(DashboardAuth::Session { session_cookie }) => Some(session_cookie.clone()),
+            _ => None,
+        }
+    }
+
+    pub fn set_auth(&self, auth: Option<DashboardAuth>) {
+        *write_lock(&self.default_auth) = auth;
+    }
+
+    pub fn clear_session(&self) -> Result<(), NebulAuthError> {
+        {
+            let mut auth = write_lock(&self.default_auth);
+            if matches!(*auth, Some(DashboardAuth::Session { .. })) {
+                *auth = None;
+            }
+        }
+        *write_lock(&self.credentials) = None;
+
+        if let Some(path) = &self.session_file {
+            match std::fs::remove_file(path) {
+                Ok(()) => {}
+                Err(e) if e.kind() == std::io::ErrorKind::NotFound => {}
+                Err(e) => return Err(e.into()),
+            }
+        }
+        Ok(())
+    }
+
     pub async fn login(
         &self,
         payload: LoginRequest,
         options: DashboardRequestOptions,
     ) -> Result<DashboardResponse, NebulAuthError> {
-        self.request(
-            "POST",
-            "/auth/login",
-            Some(serde_json::to_value(payload).map_err(|e| NebulAuthError::Config(e.to_string()))?),
-            options,
-        )
-        .await
+        let body =
+            serde_json::to_value(&payload).map_err(|e| NebulAuthError::Config(e.to_string()))?;
+        let (response, session_cookie) = self
+            .send("POST", "/auth/login", Some(body), options)
+            .await?;
+
+        if response.ok {
+            if let Some(session_cookie) = session_cookie {
+                self.store_session(session_cookie)?;
+                if self.auto_relogin {
+                    *write_lock(&self.credentials) = Some(payload);
+                }
+            }
+        }
+
+        Ok(response)
     }
 
+    /// Ends the session on the server, then forgets it locally. Local state is only cleared
+    /// once the server confirms (2xx, or 401 for a session that is already gone); any other
+    /// failure is returned with the session kept, so the logout can be retried.
     pub async fn logout(
         &self,
         options: DashboardRequestOptions,
     ) -> Result<DashboardResponse, NebulAuthError> {
-        self.request("POST", "/auth/logout", Some(json!({})), options)
-            .await
+        let (response, _) = self
+            .send("POST", "/auth/logout", Some(json!({})), options)
+            .await?;
+        if !response.ok && response.status_code != 401 {
+            return response.error_for_status();
+        }
+        self.clear_session()?;
+        Ok(response)
     }
 
     pub async fn me(
@@ -337,7 +512,7 @@ impl NebulAuthDashboardClient {
     pub async fn bulk_create_keys(
         &self,
         payload: KeyBatchCreateRequest,
-        format: &str,
+        format: BatchFormat,
         mut options: DashboardRequestOptions,
     ) -> Result<DashboardResponse, NebulAuthError> {
         options
@@ -629,6 +804,223 @@ impl NebulAuthDashboardClient {
         body: Option<Value>,
         options: DashboardRequestOptions,
     ) -> Result<DashboardResponse, NebulAuthError> {
+        let audited_body = self.audit_log.is_some().then(|| body.clone());
+
+        let telemetry = RequestTelemetry::start("dashboard", method, path, None);
+        let result = telemetry
+            .instrument(self.request_with_relogin(method, path, body, options, &telemetry))
+            .await;
+        let status = result.as_ref().map(|response| response.status_code);
+        telemetry.finish(status);
+
+        if let Some(body) = audited_body {
+            self.audit(method, path, body.as_ref(), status);
+        }
+        result
+    }
+
+    /// Like [`request`](Self::request), but the body is read as it arrives. Transports that
+    /// cannot stream hand it over in one chunk.
+    pub(crate) async fn request_streaming(
+        &self,
+        method: &str,
+        path: &str,
+        body: Option<Value>,
+        options: DashboardRequestOptions,
+    ) -> Result<StreamingResponse<'_>, NebulAuthError> {
+        let audited_body = self.audit_log.is_some().then(|| body.clone());
+
+        let telemetry = RequestTelemetry::start("dashboard", method, path, None);
+        let result = telemetry
+            .instrument(self.stream_with_relogin(method, path, body, options, &telemetry))
+            .await;
+        let status = result.as_ref().map(|response| response.status);
+        telemetry.finish(status);
+
+        if let Some(body) = audited_body {
+            self.audit(method, path, body.as_ref(), status);
+        }
+        result
+    }
+
+    /// Records a completed mutating request in the audit log, if one is set.
+    pub(crate) fn audit(
+        &self,
+        method: &str,
+        path: &str,
+        body: Option<&Value>,
+        result: Result<u16, &NebulAuthError>,
+    ) {
+        if let Some(audit_log) = &self.audit_log {
+            if !method.eq_ignore_ascii_case("GET") {
+                audit_log.record_completed("dashboard", method, path, body, result);
+            }
+        }
+    }
+
+    async fn request_with_relogin(
+        &self,
+        method: &str,
+        path: &str,
+        body: Option<Value>,
+        options: DashboardRequestOptions,
+        telemetry: &RequestTelemetry,
+    ) -> Result<DashboardResponse, NebulAuthError> {
+        let retry = if self.can_relogin(path, &options) {
+            Some((body.clone(), options.clone()))
+        } else {
+            None
+        };
+        let stored_session = options.auth.is_none()
+            && matches!(
+                *read_lock(&self.default_auth),
+                Some(DashboardAuth::Session { .. })
+            );
+
+        let (response, _) = self.send(method, path, body, options).await?;
+        if stored_session {
+            telemetry.session_cache(response.status_code != 401);
+        }
+        if response.status_code != 401 {
+            return Ok(response);
+        }
+
+        match retry {
+            Some((body, options)) if self.relogin().await? => {
+                telemetry.retried();
+                let (response, _) = self.send(method, path, body, options).await?;
+                Ok(response)
+            }
+            _ => Ok(response),
+        }
+    }
+
+    async fn stream_with_relogin(
+        &self,
+        method: &str,
+        path: &str,
+        body: Option<Value>,
+        options: DashboardRequestOptions,
+        telemetry: &RequestTelemetry,
+    ) -> Result<StreamingResponse<'_>, NebulAuthError> {
+        let retry = if self.can_relogin(path, &options) {
+            Some((body.clone(), options.clone()))
+        } else {
+            None
+        };
+        let stored_session = options.auth.is_none()
+            && matches!(
+                *read_lock(&self.default_auth),
+                Some(DashboardAuth::Session { .. })
+            );
+
+        let response = self.send_streaming(method, path, body, options).await?;
+        if stored_session {
+            telemetry.session_cache(response.status != 401);
+        }
+        if response.status != 401 {
+            return Ok(response);
+        }
+
+        match retry {
+            Some((body, options)) if self.relogin().await? => {
+                telemetry.retried();
+                self.send_streaming(method, path, body, options).await
+            }
+            _ => Ok(response),
+        }
+    }
+
+    fn can_relogin(&self, path: &str, options: &DashboardRequestOptions) -> bool {
+        let endpoint = path.trim_start_matches('/');
+        self.auto_relogin
+            && options.auth.is_none()
+            && endpoint != "auth/login"
+            && endpoint != "auth/logout"
+            && read_lock(&self.credentials).is_some()
+    }
+
+    async fn relogin(&self) -> Result<bool, NebulAuthError> {
+        let credentials = match read_lock(&self.credentials).clone() {
+            Some(credentials) => credentials,
+            None => return Ok(false),
+        };
+
+        let body =
+            serde_json::to_value(credentials).map_err(|e| NebulAuthError::Config(e.to_string()))?;
+        let (response, session_cookie) = self
+            .send(
+                "POST",
+                "/auth/login",
+                Some(body),
+                DashboardRequestOptions::default(),
+            )
+            .await?;
+
+        match session_cookie {
+            Some(session_cookie) if response.ok => {
+                self.store_session(session_cookie)?;
+                Ok(true)
+            }
+            _ => Ok(false),
+        }
+    }
+
+    fn store_session(&self, session_cookie: String) -> Result<(), NebulAuthError> {
+        if let Some(path) = &self.session_file {
+            save_session_file(path, &session_cookie)?;
+        }
+        *write_lock(&self.default_auth) = Some(DashboardAuth::Session { session_cookie });
+        Ok(())
+    }
+
+    async fn send(
+        &self,
+        method: &str,
+        path: &str,
+        body: Option<Value>,
+        options: DashboardRequestOptions,
+    ) -> Result<(DashboardResponse, Option<String>), NebulAuthError> {
+        let response = self
+            .transport
+            .send(self.build_http_request(method, path, body, options)?)
+            .await?;
+        telemetry::record_response(&response.headers);
+
+        let session_cookie = response
+            .headers
+            .iter()
+            .filter(|(key, _)| key.eq_ignore_ascii_case("set-cookie"))
+            .find_map(|(_, value)| parse_session_cookie(value));
+
+        Ok((
+            DashboardResponse::from_body(response.status, response.headers, response.body),
+            session_cookie,
+        ))
+    }
+
+    async fn send_streaming(
+        &self,
+        method: &str,
+        path: &str,
+        body: Option<Value>,
+        options: DashboardRequestOptions,
+    ) -> Result<StreamingResponse<'_>, NebulAuthError> {
+        let response = self
+            .transport
+            .send_streaming(self.build_http_request(method, path, body, options)?)
+            .await?;
+        telemetry::record_response(&response.headers);
+        Ok(response)
+    }
+
+    fn build_http_request(
+        &self,
+        method: &str,
+        path: &str,
+        body: Option<Value>,
+        options: DashboardRequestOptions,
+    ) -> Result<HttpRequest, NebulAuthError> {
         let endpoint = if path.starts_with('/') {
             path.to_string()
         } else {
@@ -650,7 +1042,9 @@ impl NebulAuthDashboardClient {
             headers.insert(header_name, header_value);
         }
 
-        let auth = options.auth.or_else(|| self.default_auth.clone());
+        let auth = options
+            .auth
+            .or_else(|| read_lock(&self.default_auth).clone());
         if let Some(auth_mode) = auth {
             match auth_mode {
                 DashboardAuth::Session { session_cookie } => {
@@ -675,49 +1069,86 @@ impl NebulAuthDashboardClient {
         }
 
         let method_upper = method.to_uppercase();
-        let request_method = match method_upper.as_str() {
-            "GET" => reqwest::Method::GET,
-            "POST" => reqwest::Method::POST,
-            "PATCH" => reqwest::Method::PATCH,
-            "DELETE" => reqwest::Method::DELETE,
-            _ => {
-                return Err(NebulAuthError::Config(format!(
-                    "unsupported dashboard method: {method}"
-                )))
+        if !matches!(method_upper.as_str(), "GET" | "POST" | "PATCH" | "DELETE") {
+            return Err(NebulAuthError::Config(format!(
+                "unsupported dashboard method: {method}"
+            )));
+        }
+
+        let body = match body {
+            Some(payload) => {
+                headers.insert(CONTENT_TYPE, HeaderValue::from_static("application/json"));
+                Some(
+                    serde_json::to_string(&payload)
+                        .map_err(|e| NebulAuthError::Config(e.to_string()))?,
+                )
             }
+            None => None,
         };
+        telemetry::propagate(&mut headers);
+
+        Ok(HttpRequest::new(
+            &method_upper,
+            url.to_string(),
+            &headers,
+            body,
+        ))
+    }
+}
 
-        let mut request = self.client.request(request_method, url).headers(headers);
-        if let Some(payload) = body {
-            request = request.header(CONTENT_TYPE, "application/json").body(
-                serde_json::to_string(&payload)
-                    .map_err(|e| NebulAuthError::Config(e.to_string()))?,
-            );
+fn parse_session_cookie(header: &str) -> Option<String> {
+    let pair = header.split(';').next()?;
+    let (name, value) = pair.split_once('=')?;
+    let value = value.trim();
+    if name.trim() == SESSION_COOKIE_NAME && !value.is_empty() {
+        Some(value.to_string())
+    } else {
+        None
+    }
+}
+
+fn load_session_file(path: &Path) -> Result<Option<String>, NebulAuthError> {
+    match std::fs::read_to_string(path) {
+        Ok(contents) => {
+            let session_cookie = contents.trim();
+            Ok((!session_cookie.is_empty()).then(|| session_cookie.to_string()))
         }
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(None),
+        Err(e) => Err(e.into()),
+    }
+}
 
-        let response = request.send().await?;
-        let status = response.status();
+fn save_session_file(path: &Path, session_cookie: &str) -> Result<(), NebulAuthError> {
+    write_private_file(path, session_cookie.as_bytes())
+}
 
-        let mut response_headers = HashMap::new();
-        for (key, value) in response.headers() {
-            response_headers.insert(
-                key.to_string(),
-                value.to_str().unwrap_or_default().to_string(),
-            );
-        }
+/// Writes `contents` to `path`, readable only by the owner on Unix.
+pub(crate) fn write_private_file(path: &Path, contents: &[u8]) -> Result<(), NebulAuthError> {
+    use std::io::Write;
 
-        let text = response.text().await?;
-        let data = if text.trim().is_empty() {
-            json!({})
-        } else {
-            serde_json::from_str::<Value>(&text).unwrap_or(Value::String(text))
-        };
+    let mut open_options = std::fs::OpenOptions::new();
+    open_options.write(true).create(true).truncate(true);
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::OpenOptionsExt;
+        open_options.mode(0o600);
+    }
 
-        Ok(DashboardResponse {
-            status_code: status.as_u16(),
-            ok: status.is_success(),
-            data,
-            headers: response_headers,
-        })
+    let mut file = open_options.open(path)?;
+    // `mode` only applies when the file is created; tighten an existing one as well.
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::PermissionsExt;
+        file.set_permissions(std::fs::Permissions::from_mode(0o600))?;
     }
+    file.write_all(contents)?;
+    Ok(())
+}
+
+fn read_lock<T>(lock: &RwLock<T>) -> RwLockReadGuard<'_, T> {
+    lock.read().unwrap_or_else(|e| e.into_inner())
+}
+
+fn write_lock<T>(lock: &RwLock<T>) -> RwLockWriteGuard<'_, T> {
+    lock.write().unwrap_or_else(|e| e.into_inner())
 }
@@ -0,0 +1,137 @@
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+#[cfg(feature = "dashboard")]
+use std::collections::HashSet;
+use std::convert::Infallible;
+use std::fmt;
+use std::str::FromStr;
+
+macro_rules! string_enum {
+    ($name:ident { $($variant:ident => $value:literal),+ $(,)? }) => {
+        #[derive(Debug, Clone, PartialEq, Eq, Hash)]
+        pub enum $name {
+            $($variant,)+
+            Other(String),
+        }
+
+        impl $name {
+            pub fn as_str(&self) -> &str {
+                match self {
+                    $(Self::$variant => $value,)+
+                    Self::Other(value) => value,
+                }
+            }
+        }
+
+        impl From<&str> for $name {
+            fn from(value: &str) -> Self {
+                match value {
+                    $($value => Self::$variant,)+
+                    other => Self::Other(other.to_string()),
+                }
+            }
+        }
+
+        impl From<String> for $name {
+            fn from(value: String) -> Self {
+                Self::from(value.as_str())
+            }
+        }
+
+        impl FromStr for $name {
+            type Err = Infallible;
+
+            fn from_str(value: &str) -> Result<Self, Self::Err> {
+                Ok(Self::from(value))
+            }
+        }
+
+        impl fmt::Display for $name {
+            fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+                f.write_str(self.as_str())
+            }
+        }
+
+        impl Serialize for $name {
+            fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+                serializer.serialize_str(self.as_str())
+            }
+        }
+
+        impl<'de> Deserialize<'de> for $name {
+            fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+                Ok(Self::from(String::deserialize(deserializer)?))
+            }
+        }
+    };
+}
+
+string_enum!(ReplayProtectionMode {
+    None => "none",
+    Nonce => "nonce",
+    Strict => "strict",
+});
+
+#[cfg(feature = "dashboard")]
+string_enum!(BlacklistType {
+    Hwid => "hwid",
+    Ip => "ip",
+    Discord => "discord",
+    Key => "key",
+});
+
+#[cfg(feature = "dashboard")]
+string_enum!(TeamRole {
+    Owner => "owner",
+    Admin => "admin",
+    Member => "member",
+    Viewer => "viewer",
+});
+
+#[cfg(feature = "dashboard")]
+string_enum!(ApiTokenScope {
+    KeysVerify => "keys:verify",
+    KeysRedeem => "keys:redeem",
+    KeysResetHwid => "keys:reset-hwid",
+    AuthVerify => "auth:verify",
+});
+
+/// Whether two scope lists grant the same scopes, ignoring order and duplicates.
+#[cfg(feature = "dashboard")]
+pub(crate) fn same_scopes(a: &[ApiTokenScope], b: &[ApiTokenScope]) -> bool {
+    let a: HashSet<&ApiTokenScope> = a.iter().collect();
+    let b: HashSet<&ApiTokenScope> = b.iter().collect();
+    a == b
+}
+
+#[cfg(feature = "dashboard")]
+string_enum!(ApiAuthMode {
+    Bearer => "bearer",
+    Pop => "pop",
+});
+
+#[cfg(feature = "dashboard")]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum BatchFormat {
+    Json,
+    Csv,
+    Txt,
+}
+
+#[cfg(feature = "dashboard")]
+impl BatchFormat {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            BatchFormat::Json => "json",
+            BatchFormat::Csv => "csv",
+            BatchFormat::Txt => "txt",
+        }
+    }
+}
+
+#[cfg(feature = "dashboard")]
+impl fmt::Display for BatchFormat {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
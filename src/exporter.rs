@@ -0,0 +1,227 @@
+use std::collections::BTreeMap;
+use std::fmt::Write;
+
+use crate::analytics::{AnalyticsRange, AnalyticsSummary, GeoEntry};
+use crate::bulk::is_revoked;
+use crate::{
+    current_timestamp_ms, BlacklistEntry, DashboardKey, DashboardRequestOptions, KeySession,
+    NebulAuthDashboardClient, NebulAuthError,
+};
+
+/// Last collected values for one service. Values from the last successful refresh are kept
+/// when a refresh fails, with `up` cleared.
+#[derive(Debug, Clone, Default)]
+pub struct ServiceMetrics {
+    pub service: String,
+    pub up: bool,
+    pub summary: AnalyticsSummary,
+    pub countries: Vec<GeoEntry>,
+    pub active_keys: usize,
+    pub revoked_keys: usize,
+    pub sessions: usize,
+    pub blacklist: BTreeMap<String, usize>,
+    pub refresh_errors: u64,
+    pub refreshed_at_ms: u128,
+}
+
+impl ServiceMetrics {
+    pub fn new(service: impl Into<String>) -> Self {
+        Self {
+            service: service.into(),
+            ..Default::default()
+        }
+    }
+
+    pub async fn refresh(
+        &mut self,
+        client: &NebulAuthDashboardClient,
+        range: AnalyticsRange,
+        options: DashboardRequestOptions,
+    ) -> Result<(), NebulAuthError> {
+        let result = self.collect(client, range, options).await;
+        self.up = result.is_ok();
+        if result.is_err() {
+            self.refresh_errors += 1;
+        }
+        result
+    }
+
+    async fn collect(
+        &mut self,
+        client: &NebulAuthDashboardClient,
+        range: AnalyticsRange,
+        options: DashboardRequestOptions,
+    ) -> Result<(), NebulAuthError> {
+        let summary = client.analytics_summary_for(range, options.clone()).await?;
+        let countries = client.analytics_geo_for(range, options.clone()).await?;
+        let keys: Vec<DashboardKey> = client
+            .list_keys(options.clone())
+            .await?
+            .error_for_status()?
+            .items()?;
+        let sessions: Vec<KeySession> = client
+            .list_key_sessions(options.clone())
+            .await?
+            .error_for_status()?
+            .items()?;
+        let entries: Vec<BlacklistEntry> = client
+            .list_blacklist(options)
+            .await?
+            .error_for_status()?
+            .items()?;
+
+        let revoked_keys = keys.iter().filter(|key| is_revoked(key)).count();
+        let mut blacklist = BTreeMap::new();
+        for entry in &entries {
+            *blacklist.entry(entry.r#type.to_string()).or_default() += 1;
+        }
+
+        self.summary = summary;
+        self.countries = countries;
+        self.active_keys = keys.len() - revoked_keys;
+        self.revoked_keys = revoked_keys;
+        self.sessions = sessions.len();
+        self.blacklist = blacklist;
+        self.refreshed_at_ms = current_timestamp_ms();
+        Ok(())
+    }
+}
+
+struct Family {
+    name: String,
+    help: &'static str,
+    kind: &'static str,
+    samples: Vec<String>,
+}
+
+impl Family {
+    fn new(name: &str, help: &'static str, kind: &'static str) -> Self {
+        Self {
+            name: name.to_string(),
+            help,
+            kind,
+            samples: Vec::new(),
+        }
+    }
+
+    fn sample(&mut self, labels: &[(&str, &str)], value: f64) {
+        let labels = labels
+            .iter()
+            .map(|(name, value)| format!("{name}=\"{}\"", escape_label(value)))
+            .collect::<Vec<_>>()
+            .join(",");
+        self.samples
+            .push(format!("{}{{{labels}}} {value}", self.name));
+    }
+}
+
+fn escape_label(value: &str) -> String {
+    value
+        .replace('\\', "\\\\")
+        .replace('"', "\\\"")
+        .replace('\n', "\\n")
+}
+
+/// Turns `failedVerifications` or `key-count` into `failed_verifications` / `key_count`.
+fn metric_name(name: &str) -> String {
+    let mut out = String::with_capacity(name.len() + 4);
+    for (i, c) in name.chars().enumerate() {
+        if c.is_ascii_uppercase() {
+            if i > 0 && !out.ends_with('_') {
+                out.push('_');
+            }
+            out.push(c.to_ascii_lowercase());
+        } else if c.is_ascii_alphanumeric() {
+            out.push(c);
+        } else if !out.ends_with('_') {
+            out.push('_');
+        }
+    }
+    out.trim_matches('_').to_string()
+}
+
+/// Renders all services in the Prometheus text exposition format.
+pub fn render(services: &[ServiceMetrics]) -> String {
+    let mut up = Family::new(
+        "nebulauth_up",
+        "Whether the last refresh succeeded.",
+        "gauge",
+    );
+    let mut errors = Family::new(
+        "nebulauth_refresh_errors_total",
+        "Failed refreshes since start.",
+        "counter",
+    );
+    let mut refreshed = Family::new(
+        "nebulauth_last_refresh_timestamp_seconds",
+        "Time of the last successful refresh.",
+        "gauge",
+    );
+    let mut keys = Family::new("nebulauth_keys", "License keys by status.", "gauge");
+    let mut sessions = Family::new("nebulauth_key_sessions", "Open key sessions.", "gauge");
+    let mut blacklist = Family::new(
+        "nebulauth_blacklist_entries",
+        "Blacklist entries by type.",
+        "gauge",
+    );
+    let mut geo = Family::new(
+        "nebulauth_analytics_country_count",
+        "Analytics count per country.",
+        "gauge",
+    );
+    let mut summary: BTreeMap<String, Family> = BTreeMap::new();
+
+    for metrics in services {
+        let service = metrics.service.as_str();
+        up.sample(&[("service", service)], if metrics.up { 1.0 } else { 0.0 });
+        errors.sample(&[("service", service)], metrics.refresh_errors as f64);
+        if metrics.refreshed_at_ms == 0 {
+            continue;
+        }
+        refreshed.sample(
+            &[("service", service)],
+            metrics.refreshed_at_ms as f64 / 1000.0,
+        );
+        keys.sample(
+            &[("service", service), ("status", "active")],
+            metrics.active_keys as f64,
+        );
+        keys.sample(
+            &[("service", service), ("status", "revoked")],
+            metrics.revoked_keys as f64,
+        );
+        sessions.sample(&[("service", service)], metrics.sessions as f64);
+        for (r#type, count) in &metrics.blacklist {
+            blacklist.sample(&[("service", service), ("type", r#type)], *count as f64);
+        }
+        for entry in &metrics.countries {
+            geo.sample(
+                &[("service", service), ("country", &entry.country)],
+                entry.count as f64,
+            );
+        }
+        for (name, value) in metrics.summary.metrics() {
+            let name = format!("nebulauth_analytics_{}", metric_name(&name));
+            summary
+                .entry(name.clone())
+                .or_insert_with(|| Family::new(&name, "Analytics summary metric.", "gauge"))
+                .sample(&[("service", service)], value);
+        }
+    }
+
+    let mut out = String::new();
+    for family in [up, errors, refreshed, keys, sessions, blacklist, geo]
+        .into_iter()
+        .chain(summary.into_values())
+    {
+        if family.samples.is_empty() {
+            continue;
+        }
+        let _ = writeln!(out, "# HELP {} {}", family.name, family.help);
+        let _ = writeln!(out, "# TYPE {} {}", family.name, family.kind);
+        for sample in family.samples {
+            let _ = writeln!(out, "{sample}");
+        }
+    }
+    out
+}
@@ -0,0 +1,359 @@
+//! C API over [`crate::NebulAuthClient`]. `include/nebulauth.h` is generated from this file
+//! by `build.rs` (cbindgen), which adds the `NebulAuth` prefix to every type name.
+//!
+//! Calls block the calling thread. Every call returns an [`ErrorCode`]; when it isn't `OK`
+//! and `out_error` is not null, the error is written there and must be released with
+//! `nebulauth_free_error`. Strings passed in are borrowed and must be NUL-terminated UTF-8.
+
+use std::ffi::{c_char, CStr, CString};
+use std::panic::{catch_unwind, AssertUnwindSafe};
+use std::ptr;
+
+use crate::{
+    NebulAuthClientOptions, NebulAuthError, NebulAuthResponse, ReplayProtectionMode,
+    ResetHwidInput, VerifyKeyInput,
+};
+
+#[repr(C)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ErrorCode {
+    Ok = 0,
+    /// A required pointer was null or a string was not valid UTF-8.
+    InvalidArgument = 1,
+    Config = 2,
+    Request = 3,
+    Timeout = 4,
+    Url = 5,
+    Crypto = 6,
+    Io = 7,
+    Api = 8,
+    Decode = 9,
+    /// The call panicked, e.g. because it was made from inside a Tokio runtime.
+    Panic = 10,
+}
+
+/// Values for `NebulAuthClientConfig::replay_protection`.
+#[repr(u32)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ReplayProtection {
+    Strict = 0,
+    Nonce = 1,
+    None = 2,
+}
+
+/// Null strings and a zero `timeout_ms` use the `NebulAuthClientOptions` defaults.
+#[repr(C)]
+#[derive(Debug)]
+pub struct ClientConfig {
+    pub base_url: *const c_char,
+    pub bearer_token: *const c_char,
+    pub signing_secret: *const c_char,
+    pub service_slug: *const c_char,
+    /// One of `NebulAuthReplayProtection`.
+    pub replay_protection: u32,
+    pub timeout_ms: u64,
+}
+
+/// `body` is the JSON response body. Release it with `nebulauth_free_response`.
+#[repr(C)]
+#[derive(Debug)]
+pub struct Response {
+    pub status_code: u16,
+    pub ok: bool,
+    pub body: *mut c_char,
+}
+
+/// `status_code` is set for `API` errors, otherwise 0. Release `message` with
+/// `nebulauth_free_error`.
+#[repr(C)]
+#[derive(Debug)]
+pub struct Error {
+    pub code: ErrorCode,
+    pub status_code: u16,
+    pub message: *mut c_char,
+}
+
+/// Opaque client handle. Safe to share between threads.
+pub struct Client {
+    inner: crate::NebulAuthClient,
+    runtime: tokio::runtime::Runtime,
+}
+
+struct Failure {
+    code: ErrorCode,
+    status_code: u16,
+    message: String,
+}
+
+impl Failure {
+    fn invalid(message: impl Into<String>) -> Self {
+        Self {
+            code: ErrorCode::InvalidArgument,
+            status_code: 0,
+            message: message.into(),
+        }
+    }
+}
+
+impl From<NebulAuthError> for Failure {
+    fn from(error: NebulAuthError) -> Self {
+        let (code, status_code) = match &error {
+            NebulAuthError::Config(_) => (ErrorCode::Config, 0),
+            NebulAuthError::Request(e) if e.is_timeout() => (ErrorCode::Timeout, 0),
+            NebulAuthError::Request(_) => (ErrorCode::Request, 0),
+            NebulAuthError::Url(_) => (ErrorCode::Url, 0),
+            NebulAuthError::Crypto(_) => (ErrorCode::Crypto, 0),
+            NebulAuthError::Io(_) => (ErrorCode::Io, 0),
+            NebulAuthError::Api { status_code, .. } => (ErrorCode::Api, *status_code),
+            NebulAuthError::Decode(_) => (ErrorCode::Decode, 0),
+        };
+        Self {
+            code,
+            status_code,
+            message: error.to_string(),
+        }
+    }
+}
+
+fn into_c_string(value: String) -> *mut c_char {
+    CString::new(value.replace('\0', ""))
+        .unwrap_or_default()
+        .into_raw()
+}
+
+/// # Safety
+/// `value` must be null or a NUL-terminated string valid for the duration of the call.
+unsafe fn read_str(value: *const c_char, name: &str) -> Result<Option<String>, Failure> {
+    if value.is_null() {
+        return Ok(None);
+    }
+    CStr::from_ptr(value)
+        .to_str()
+        .map(|value| Some(value.to_string()))
+        .map_err(|_| Failure::invalid(format!("{name} is not valid UTF-8")))
+}
+
+/// Runs `call`, turning a failure or panic into an error code and filling `out_error`.
+///
+/// # Safety
+/// `out_error` must be null or valid for writes.
+unsafe fn guard<F>(out_error: *mut Error, call: F) -> ErrorCode
+where
+    F: FnOnce() -> Result<(), Failure>,
+{
+    if !out_error.is_null() {
+        out_error.write(Error {
+            code: ErrorCode::Ok,
+            status_code: 0,
+            message: ptr::null_mut(),
+        });
+    }
+
+    let failure = match catch_unwind(AssertUnwindSafe(call)) {
+        Ok(Ok(())) => return ErrorCode::Ok,
+        Ok(Err(failure)) => failure,
+        Err(panic) => Failure {
+            code: ErrorCode::Panic,
+            status_code: 0,
+            message: panic
+                .downcast_ref::<&str>()
+                .map(|message| message.to_string())
+                .or_else(|| panic.downcast_ref::<String>().cloned())
+                .unwrap_or_else(|| "panic in nebulauth".to_string()),
+        },
+    };
+    if !out_error.is_null() {
+        out_error.write(Error {
+            code: failure.code,
+            status_code: failure.status_code,
+            message: into_c_string(failure.message),
+        });
+    }
+    failure.code
+}
+
+/// # Safety
+/// `out_response` must be null or valid for writes.
+unsafe fn write_response(out_response: *mut Response, response: NebulAuthResponse) {
+    if out_response.is_null() {
+        return;
+    }
+    let body = serde_json::to_string(&response.data).unwrap_or_else(|_| "{}".to_string());
+    out_response.write(Response {
+        status_code: response.status_code,
+        ok: response.ok,
+        body: into_c_string(body),
+    });
+}
+
+/// # Safety
+/// `out_response` must be null or valid for writes.
+unsafe fn clear_response(out_response: *mut Response) {
+    if !out_response.is_null() {
+        out_response.write(Response {
+            status_code: 0,
+            ok: false,
+            body: ptr::null_mut(),
+        });
+    }
+}
+
+/// Creates a client and stores it in `*out_client` (null on failure). Release it with
+/// `nebulauth_free_client`.
+///
+/// # Safety
+/// `config` must point to a valid config whose strings are null or NUL-terminated.
+/// `out_client` must be valid for writes and `out_error` null or valid for writes.
+#[no_mangle]
+pub unsafe extern "C" fn nebulauth_client_new(
+    config: *const ClientConfig,
+    out_client: *mut *mut Client,
+    out_error: *mut Error,
+) -> ErrorCode {
+    guard(out_error, || {
+        if out_client.is_null() {
+            return Err(Failure::invalid("out_client is null"));
+        }
+        out_client.write(ptr::null_mut());
+        let config = config
+            .as_ref()
+            .ok_or_else(|| Failure::invalid("config is null"))?;
+
+        let defaults = NebulAuthClientOptions::default();
+        let options = NebulAuthClientOptions {
+            base_url: read_str(config.base_url, "base_url")?.unwrap_or(defaults.base_url),
+            bearer_token: read_str(config.bearer_token, "bearer_token")?,
+            signing_secret: read_str(config.signing_secret, "signing_secret")?,
+            service_slug: read_str(config.service_slug, "service_slug")?,
+            replay_protection: match config.replay_protection {
+                r if r == ReplayProtection::Strict as u32 => ReplayProtectionMode::Strict,
+                r if r == ReplayProtection::Nonce as u32 => ReplayProtectionMode::Nonce,
+                r if r == ReplayProtection::None as u32 => ReplayProtectionMode::None,
+                other => {
+                    return Err(Failure::invalid(format!(
+                        "unknown replay_protection value {other}"
+                    )))
+                }
+            },
+            timeout_ms: match config.timeout_ms {
+                0 => defaults.timeout_ms,
+                timeout_ms => timeout_ms,
+            },
+            ..defaults
+        };
+
+        let runtime = tokio::runtime::Builder::new_current_thread()
+            .enable_all()
+            .build()
+            .map_err(NebulAuthError::from)?;
+        let client = Client {
+            inner: crate::NebulAuthClient::new(options)?,
+            runtime,
+        };
+        out_client.write(Box::into_raw(Box::new(client)));
+        Ok(())
+    })
+}
+
+/// Calls `/keys/verify`. `hwid` and `request_id` may be null. A response is written for any
+/// HTTP status; check `ok` or `status_code` for rejected keys.
+///
+/// # Safety
+/// `client` must come from `nebulauth_client_new` and not be freed yet. String arguments
+/// must be null or NUL-terminated; `out_response` and `out_error` null or valid for writes.
+#[no_mangle]
+pub unsafe extern "C" fn nebulauth_verify_key(
+    client: *const Client,
+    key: *const c_char,
+    hwid: *const c_char,
+    request_id: *const c_char,
+    out_response: *mut Response,
+    out_error: *mut Error,
+) -> ErrorCode {
+    clear_response(out_response);
+    guard(out_error, || {
+        let client = client
+            .as_ref()
+            .ok_or_else(|| Failure::invalid("client is null"))?;
+        let input = VerifyKeyInput {
+            key: read_str(key, "key")?.ok_or_else(|| Failure::invalid("key is null"))?,
+            hwid: read_str(hwid, "hwid")?,
+            request_id: read_str(request_id, "request_id")?,
+            ..Default::default()
+        };
+        let response = client.runtime.block_on(client.inner.verify_key(input))?;
+        write_response(out_response, response);
+        Ok(())
+    })
+}
+
+/// Calls `/keys/reset-hwid`. At least one of `discord_id` and `key` is required;
+/// `request_id` may be null.
+///
+/// # Safety
+/// Same as `nebulauth_verify_key`.
+#[no_mangle]
+pub unsafe extern "C" fn nebulauth_reset_hwid(
+    client: *const Client,
+    discord_id: *const c_char,
+    key: *const c_char,
+    request_id: *const c_char,
+    out_response: *mut Response,
+    out_error: *mut Error,
+) -> ErrorCode {
+    clear_response(out_response);
+    guard(out_error, || {
+        let client = client
+            .as_ref()
+            .ok_or_else(|| Failure::invalid("client is null"))?;
+        let input = ResetHwidInput {
+            discord_id: read_str(discord_id, "discord_id")?,
+            key: read_str(key, "key")?,
+            request_id: read_str(request_id, "request_id")?,
+            ..Default::default()
+        };
+        let response = client.runtime.block_on(client.inner.reset_hwid(input))?;
+        write_response(out_response, response);
+        Ok(())
+    })
+}
+
+/// Frees a client. Null is ignored.
+///
+/// # Safety
+/// `client` must be null or come from `nebulauth_client_new`, and must not be used or freed
+/// again afterwards.
+#[no_mangle]
+pub unsafe extern "C" fn nebulauth_free_client(client: *mut Client) {
+    if !client.is_null() {
+        drop(Box::from_raw(client));
+    }
+}
+
+/// Frees the response body and sets it to null, so freeing twice is harmless.
+///
+/// # Safety
+/// `response` must be null or point to a response written by this library.
+#[no_mangle]
+pub unsafe extern "C" fn nebulauth_free_response(response: *mut Response) {
+    if let Some(response) = response.as_mut() {
+        if !response.body.is_null() {
+            drop(CString::from_raw(response.body));
+            response.body = ptr::null_mut();
+        }
+    }
+}
+
+/// Frees the error message and sets it to null, so freeing twice is harmless.
+///
+/// # Safety
+/// `error` must be null or point to an error written by this library.
+#[no_mangle]
+pub unsafe extern "C" fn nebulauth_free_error(error: *mut Error) {
+    if let Some(error) = error.as_mut() {
+        if !error.message.is_null() {
+            drop(CString::from_raw(error.message));
+            error.message = ptr::null_mut();
+        }
+    }
+}
@@ -0,0 +1,409 @@
+use serde::{Deserialize, Serialize};
+use serde_json::{Map, Value};
+use std::collections::{BTreeMap, HashMap, HashSet};
+use std::fmt;
+use std::io::Read;
+use std::path::Path;
+use std::time::Duration;
+
+use crate::backup::response_id;
+use crate::bulk::parse_timestamp;
+use crate::{
+    current_timestamp_ms, DashboardKey, DashboardRequestOptions, DashboardResponse,
+    KeyCreateRequest, NebulAuthDashboardClient, NebulAuthError,
+};
+
+pub const IMPORTED_FROM_FIELD: &str = "imported_from";
+pub const IMPORT_SOURCE_FIELD: &str = "import_source";
+
+const DEFAULT_REQUESTS_PER_SECOND: u32 = 5;
+const DEFAULT_RATE_LIMIT_RETRIES: u32 = 3;
+const HOUR_MS: i64 = 3_600_000;
+
+pub type ImportRecord = Map<String, Value>;
+
+/// Maps columns (CSV) or fields (JSON) of the source export onto key fields.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct ImportMapping {
+    pub source: Option<String>,
+    pub id: String,
+    pub label: Option<String>,
+    pub duration_hours: Option<String>,
+    pub expires_at: Option<String>,
+    pub metadata: BTreeMap<String, String>,
+}
+
+impl ImportMapping {
+    pub fn from_json_str(input: &str) -> Result<Self, NebulAuthError> {
+        serde_json::from_str(input)
+            .map_err(|e| NebulAuthError::Config(format!("invalid import mapping: {e}")))
+    }
+
+    pub fn load(path: impl AsRef<Path>) -> Result<Self, NebulAuthError> {
+        Self::from_json_str(&std::fs::read_to_string(path)?)
+    }
+
+    fn prepare(
+        &self,
+        record: &ImportRecord,
+        now_ms: i64,
+    ) -> Result<(String, KeyCreateRequest), String> {
+        let source_id = field(record, &self.id)
+            .ok_or_else(|| format!("missing value for id column '{}'", self.id))?;
+
+        let duration_hours =
+            if let Some(value) = self.duration_hours.as_ref().and_then(|c| field(record, c)) {
+                Some(
+                    value
+                        .parse::<i64>()
+                        .map_err(|_| format!("invalid duration_hours '{value}'"))?,
+                )
+            } else if let Some(value) = self.expires_at.as_ref().and_then(|c| field(record, c)) {
+                let expires_at = parse_timestamp(&value)
+                    .ok_or_else(|| format!("invalid expires_at '{value}'"))?;
+                let remaining_ms = expires_at.timestamp_millis() - now_ms;
+                if remaining_ms <= 0 {
+                    return Err(format!("expired at {value}"));
+                }
+                Some((remaining_ms + HOUR_MS - 1) / HOUR_MS)
+            } else {
+                None
+            };
+
+        let mut metadata = Map::new();
+        for (target, column) in &self.metadata {
+            match record.get(column) {
+                Some(Value::Null) | None => {}
+                Some(value) => {
+                    metadata.insert(target.clone(), value.clone());
+                }
+            }
+        }
+        metadata.insert(
+            IMPORTED_FROM_FIELD.to_string(),
+            Value::String(source_id.clone()),
+        );
+        if let Some(source) = &self.source {
+            metadata.insert(
+                IMPORT_SOURCE_FIELD.to_string(),
+                Value::String(source.clone()),
+            );
+        }
+
+        Ok((
+            source_id,
+            KeyCreateRequest {
+                label: self.label.as_ref().and_then(|c| field(record, c)),
+                duration_hours,
+                metadata: Some(Value::Object(metadata)),
+            },
+        ))
+    }
+
+    fn imported_id(&self, key: &DashboardKey) -> Option<String> {
+        let metadata = key.metadata.as_ref()?.as_object()?;
+        let source = metadata.get(IMPORT_SOURCE_FIELD).and_then(Value::as_str);
+        if source != self.source.as_deref() {
+            return None;
+        }
+        metadata
+            .get(IMPORTED_FROM_FIELD)
+            .and_then(Value::as_str)
+            .map(str::to_string)
+    }
+}
+
+fn field(record: &ImportRecord, column: &str) -> Option<String> {
+    match record.get(column)? {
+        Value::String(value) => {
+            let value = value.trim();
+            (!value.is_empty()).then(|| value.to_string())
+        }
+        Value::Number(value) => Some(value.to_string()),
+        Value::Bool(value) => Some(value.to_string()),
+        _ => None,
+    }
+}
+
+pub fn parse_csv_records(reader: impl Read) -> Result<Vec<ImportRecord>, NebulAuthError> {
+    let mut reader = csv::Reader::from_reader(reader);
+    let headers = reader
+        .headers()
+        .map_err(|e| NebulAuthError::Decode(format!("invalid csv header: {e}")))?
+        .clone();
+    reader
+        .records()
+        .map(|record| {
+            let record =
+                record.map_err(|e| NebulAuthError::Decode(format!("invalid csv row: {e}")))?;
+            Ok(headers
+                .iter()
+                .zip(record.iter())
+                .map(|(column, value)| {
+                    (column.trim().to_string(), Value::String(value.to_string()))
+                })
+                .collect())
+        })
+        .collect()
+}
+
+pub fn parse_json_records(input: &str) -> Result<Vec<ImportRecord>, NebulAuthError> {
+    serde_json::from_str(input)
+        .map_err(|e| NebulAuthError::Decode(format!("expected a JSON array of objects: {e}")))
+}
+
+/// Reads `.csv`, `.json` (array of objects) or `.ndjson`/`.jsonl` exports.
+pub fn read_records(path: impl AsRef<Path>) -> Result<Vec<ImportRecord>, NebulAuthError> {
+    let path = path.as_ref();
+    match path.extension().and_then(|ext| ext.to_str()) {
+        Some("csv") => parse_csv_records(std::fs::File::open(path)?),
+        Some("json") => parse_json_records(&std::fs::read_to_string(path)?),
+        Some("ndjson") | Some("jsonl") => std::fs::read_to_string(path)?
+            .lines()
+            .filter(|line| !line.trim().is_empty())
+            .map(|line| {
+                serde_json::from_str(line)
+                    .map_err(|e| NebulAuthError::Decode(format!("invalid json line: {e}")))
+            })
+            .collect(),
+        _ => Err(NebulAuthError::Config(format!(
+            "unsupported import file '{}' (expected .csv, .json, .ndjson or .jsonl)",
+            path.display()
+        ))),
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct ImportOptions {
+    pub dry_run: bool,
+    /// Upper bound on `create_key` calls per second; `0` disables the limit.
+    pub requests_per_second: u32,
+    /// How often a `429` is retried after waiting for `Retry-After`.
+    pub rate_limit_retries: u32,
+}
+
+impl Default for ImportOptions {
+    fn default() -> Self {
+        Self {
+            dry_run: false,
+            requests_per_second: DEFAULT_REQUESTS_PER_SECOND,
+            rate_limit_retries: DEFAULT_RATE_LIMIT_RETRIES,
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "status", content = "detail", rename_all = "snake_case")]
+pub enum ImportOutcome {
+    WouldCreate,
+    Created,
+    AlreadyImported,
+    Invalid(String),
+    Failed(String),
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ImportItem {
+    pub row: usize,
+    pub source_id: Option<String>,
+    pub label: Option<String>,
+    pub key_id: Option<String>,
+    pub outcome: ImportOutcome,
+}
+
+impl fmt::Display for ImportItem {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "row {}", self.row)?;
+        if let Some(source_id) = &self.source_id {
+            write!(f, " ({source_id})")?;
+        }
+        match &self.outcome {
+            ImportOutcome::WouldCreate => write!(f, ": would create")?,
+            ImportOutcome::Created => write!(f, ": created")?,
+            ImportOutcome::AlreadyImported => write!(f, ": already imported")?,
+            ImportOutcome::Invalid(reason) => write!(f, ": invalid ({reason})")?,
+            ImportOutcome::Failed(reason) => write!(f, ": failed ({reason})")?,
+        }
+        match &self.key_id {
+            Some(key_id) => write!(f, " -> {key_id}"),
+            None => Ok(()),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct ImportReport {
+    pub dry_run: bool,
+    pub items: Vec<ImportItem>,
+}
+
+impl ImportReport {
+    fn count(&self, outcome: fn(&ImportOutcome) -> bool) -> usize {
+        self.items
+            .iter()
+            .filter(|item| outcome(&item.outcome))
+            .count()
+    }
+
+    pub fn created(&self) -> impl Iterator<Item = &ImportItem> {
+        self.items
+            .iter()
+            .filter(|item| item.outcome == ImportOutcome::Created)
+    }
+
+    pub fn already_imported(&self) -> impl Iterator<Item = &ImportItem> {
+        self.items
+            .iter()
+            .filter(|item| item.outcome == ImportOutcome::AlreadyImported)
+    }
+
+    /// Rows that were rejected or failed and still need attention.
+    pub fn unresolved(&self) -> impl Iterator<Item = &ImportItem> {
+        self.items.iter().filter(|item| {
+            matches!(
+                item.outcome,
+                ImportOutcome::Invalid(_) | ImportOutcome::Failed(_)
+            )
+        })
+    }
+
+    pub fn load(path: impl AsRef<Path>) -> Result<Self, NebulAuthError> {
+        let contents = std::fs::read_to_string(path)?;
+        serde_json::from_str(&contents)
+            .map_err(|e| NebulAuthError::Decode(format!("invalid import report: {e}")))
+    }
+
+    pub fn save(&self, path: impl AsRef<Path>) -> Result<(), NebulAuthError> {
+        let contents = serde_json::to_string_pretty(self).map_err(|e| {
+            NebulAuthError::Config(format!("failed to serialize import report: {e}"))
+        })?;
+        std::fs::write(path, contents)?;
+        Ok(())
+    }
+}
+
+impl fmt::Display for ImportReport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let created = if self.dry_run {
+            self.count(|o| *o == ImportOutcome::WouldCreate)
+        } else {
+            self.created().count()
+        };
+        write!(
+            f,
+            "{} rows: {} {}, {} already imported, {} invalid, {} failed",
+            self.items.len(),
+            created,
+            if self.dry_run { "to create" } else { "created" },
+            self.already_imported().count(),
+            self.count(|o| matches!(o, ImportOutcome::Invalid(_))),
+            self.count(|o| matches!(o, ImportOutcome::Failed(_))),
+        )
+    }
+}
+
+pub async fn import_keys(
+    client: &NebulAuthDashboardClient,
+    records: &[ImportRecord],
+    mapping: &ImportMapping,
+    import_options: ImportOptions,
+    options: DashboardRequestOptions,
+) -> Result<ImportReport, NebulAuthError> {
+    if mapping.id.trim().is_empty() {
+        return Err(NebulAuthError::Config(
+            "import mapping needs an id column".to_string(),
+        ));
+    }
+
+    let existing: Vec<DashboardKey> = client
+        .list_keys(options.clone())
+        .await?
+        .error_for_status()?
+        .items()?;
+    let imported: HashMap<String, String> = existing
+        .into_iter()
+        .filter_map(|key| Some((mapping.imported_id(&key)?, key.id)))
+        .collect();
+
+    let mut pacer =
+        (import_options.requests_per_second > 0 && !import_options.dry_run).then(|| {
+            let mut interval = tokio::time::interval(Duration::from_secs_f64(
+                1.0 / f64::from(import_options.requests_per_second),
+            ));
+            interval.set_missed_tick_behavior(tokio::time::MissedTickBehavior::Delay);
+            interval
+        });
+
+    let now_ms = current_timestamp_ms() as i64;
+    let mut seen = HashSet::new();
+    let mut report = ImportReport {
+        dry_run: import_options.dry_run,
+        items: Vec::with_capacity(records.len()),
+    };
+
+    for (index, record) in records.iter().enumerate() {
+        let mut item = ImportItem {
+            row: index + 1,
+            source_id: field(record, &mapping.id),
+            label: None,
+            key_id: None,
+            outcome: ImportOutcome::WouldCreate,
+        };
+
+        let (source_id, payload) = match mapping.prepare(record, now_ms) {
+            Ok(prepared) => prepared,
+            Err(reason) => {
+                item.outcome = ImportOutcome::Invalid(reason);
+                report.items.push(item);
+                continue;
+            }
+        };
+        item.label = payload.label.clone();
+
+        if let Some(key_id) = imported.get(&source_id) {
+            item.key_id = Some(key_id.clone());
+            item.outcome = ImportOutcome::AlreadyImported;
+        } else if !seen.insert(source_id) {
+            item.outcome = ImportOutcome::Invalid("duplicate id in input".to_string());
+        } else if !import_options.dry_run {
+            match create_with_backoff(client, payload, &mut pacer, &import_options, &options).await
+            {
+                Ok(response) => {
+                    item.key_id = response_id(&response.data);
+                    item.outcome = ImportOutcome::Created;
+                }
+                Err(e) => item.outcome = ImportOutcome::Failed(e.to_string()),
+            }
+        }
+        report.items.push(item);
+    }
+
+    Ok(report)
+}
+
+async fn create_with_backoff(
+    client: &NebulAuthDashboardClient,
+    payload: KeyCreateRequest,
+    pacer: &mut Option<tokio::time::Interval>,
+    import_options: &ImportOptions,
+    options: &DashboardRequestOptions,
+) -> Result<DashboardResponse, NebulAuthError> {
+    let mut retries = 0;
+    loop {
+        if let Some(pacer) = pacer.as_mut() {
+            pacer.tick().await;
+        }
+        let response = client.create_key(payload.clone(), options.clone()).await?;
+        if response.status_code != 429 || retries >= import_options.rate_limit_retries {
+            return response.error_for_status();
+        }
+        retries += 1;
+        let wait = response
+            .headers
+            .get("retry-after")
+            .and_then(|value| value.trim().parse::<u64>().ok())
+            .unwrap_or(1);
+        tokio::time::sleep(Duration::from_secs(wait)).await;
+    }
+}
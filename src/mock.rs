@@ -0,0 +1,185 @@
+use serde_json::Value;
+use std::collections::{HashMap, VecDeque};
+use std::fmt;
+use std::sync::{Arc, Mutex};
+
+use crate::verifier::{LicenseVerifier, VerifierFuture};
+use crate::{NebulAuthError, NebulAuthResponse, RedeemKeyInput, ResetHwidInput, VerifyKeyInput};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum MockOperation {
+    VerifyKey,
+    RedeemKey,
+    ResetHwid,
+}
+
+/// A call received by a [`MockLicenseVerifier`], with its input.
+#[derive(Debug, Clone)]
+pub enum MockCall {
+    VerifyKey(VerifyKeyInput),
+    RedeemKey(RedeemKeyInput),
+    ResetHwid(ResetHwidInput),
+}
+
+impl MockCall {
+    pub fn operation(&self) -> MockOperation {
+        match self {
+            MockCall::VerifyKey(_) => MockOperation::VerifyKey,
+            MockCall::RedeemKey(_) => MockOperation::RedeemKey,
+            MockCall::ResetHwid(_) => MockOperation::ResetHwid,
+        }
+    }
+
+    /// The license key in the input, if any.
+    pub fn key(&self) -> Option<&str> {
+        match self {
+            MockCall::VerifyKey(input) => Some(&input.key),
+            MockCall::RedeemKey(input) => Some(&input.key),
+            MockCall::ResetHwid(input) => input.key.as_deref(),
+        }
+    }
+}
+
+pub type MockResponder =
+    Arc<dyn Fn(&MockCall) -> Result<NebulAuthResponse, NebulAuthError> + Send + Sync>;
+
+/// A response with `ok` derived from the status code and no headers.
+pub fn response(status_code: u16, data: Value) -> NebulAuthResponse {
+    NebulAuthResponse {
+        status_code,
+        ok: (200..300).contains(&status_code),
+        data,
+        headers: HashMap::new(),
+    }
+}
+
+#[derive(Default)]
+struct MockState {
+    queued: HashMap<MockOperation, VecDeque<Result<NebulAuthResponse, NebulAuthError>>>,
+    responders: HashMap<MockOperation, MockResponder>,
+    calls: Vec<MockCall>,
+}
+
+/// [`LicenseVerifier`] that answers from programmed responses and records every call.
+///
+/// Queued responses are returned first, in order; once an operation's queue is empty its
+/// responder is used. A call with neither fails with a configuration error.
+#[derive(Default)]
+pub struct MockLicenseVerifier {
+    state: Mutex<MockState>,
+}
+
+impl fmt::Debug for MockLicenseVerifier {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("MockLicenseVerifier")
+            .field("calls", &self.lock().calls)
+            .finish()
+    }
+}
+
+impl MockLicenseVerifier {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    fn lock(&self) -> std::sync::MutexGuard<'_, MockState> {
+        self.state.lock().unwrap_or_else(|e| e.into_inner())
+    }
+
+    /// Queues one result for the next unanswered call to `operation`.
+    pub fn push(
+        &self,
+        operation: MockOperation,
+        result: Result<NebulAuthResponse, NebulAuthError>,
+    ) -> &Self {
+        self.lock()
+            .queued
+            .entry(operation)
+            .or_default()
+            .push_back(result);
+        self
+    }
+
+    /// Answers calls to `operation` once its queue is empty.
+    pub fn respond_with<F>(&self, operation: MockOperation, responder: F) -> &Self
+    where
+        F: Fn(&MockCall) -> Result<NebulAuthResponse, NebulAuthError> + Send + Sync + 'static,
+    {
+        self.lock()
+            .responders
+            .insert(operation, Arc::new(responder));
+        self
+    }
+
+    pub fn calls(&self) -> Vec<MockCall> {
+        self.lock().calls.clone()
+    }
+
+    pub fn call_count(&self, operation: MockOperation) -> usize {
+        self.lock()
+            .calls
+            .iter()
+            .filter(|call| call.operation() == operation)
+            .count()
+    }
+
+    /// Panics unless `operation` was called exactly `times` times.
+    pub fn assert_called(&self, operation: MockOperation, times: usize) {
+        let count = self.call_count(operation);
+        assert_eq!(
+            count, times,
+            "expected {operation:?} to be called {times} times, but it was called {count} times"
+        );
+    }
+
+    /// Panics if any queued response was never used.
+    pub fn assert_drained(&self) {
+        let state = self.lock();
+        let pending: Vec<_> = state
+            .queued
+            .iter()
+            .filter(|(_, queue)| !queue.is_empty())
+            .map(|(operation, queue)| format!("{operation:?} ({})", queue.len()))
+            .collect();
+        assert!(
+            pending.is_empty(),
+            "unused mock responses: {}",
+            pending.join(", ")
+        );
+    }
+
+    fn answer(&self, call: MockCall) -> Result<NebulAuthResponse, NebulAuthError> {
+        let operation = call.operation();
+        let mut state = self.lock();
+        state.calls.push(call.clone());
+        if let Some(result) = state
+            .queued
+            .get_mut(&operation)
+            .and_then(VecDeque::pop_front)
+        {
+            return result;
+        }
+        let responder = state.responders.get(&operation).cloned();
+        drop(state);
+        match responder {
+            Some(responder) => responder(&call),
+            None => Err(NebulAuthError::Config(format!(
+                "no mock response programmed for {operation:?}"
+            ))),
+        }
+    }
+}
+
+impl LicenseVerifier for MockLicenseVerifier {
+    fn verify_key<'a>(&'a self, input: VerifyKeyInput) -> VerifierFuture<'a> {
+        Box::pin(async move { self.answer(MockCall::VerifyKey(input)) })
+    }
+
+    fn redeem_key<'a>(&'a self, input: RedeemKeyInput) -> VerifierFuture<'a> {
+        Box::pin(async move { self.answer(MockCall::RedeemKey(input)) })
+    }
+
+    fn reset_hwid<'a>(&'a self, input: ResetHwidInput) -> VerifierFuture<'a> {
+        Box::pin(async move { self.answer(MockCall::ResetHwid(input)) })
+    }
+}
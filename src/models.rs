@@ -0,0 +1,117 @@
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+use crate::{ApiAuthMode, ApiTokenScope, BlacklistType, ReplayProtectionMode, TeamRole};
+
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Customer {
+    pub id: Option<String>,
+    #[serde(alias = "requireDiscordRedeem")]
+    pub require_discord_redeem: Option<bool>,
+    #[serde(alias = "requireHwid")]
+    pub require_hwid: Option<bool>,
+    pub paused: Option<bool>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct TeamMember {
+    pub id: String,
+    pub email: String,
+    #[serde(default)]
+    pub role: Option<TeamRole>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct CheckpointStep {
+    #[serde(alias = "adUrl")]
+    pub ad_url: String,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Checkpoint {
+    pub id: String,
+    pub name: String,
+    #[serde(default, alias = "durationHours")]
+    pub duration_hours: Option<i64>,
+    #[serde(default, alias = "isActive")]
+    pub is_active: Option<bool>,
+    #[serde(default, alias = "referrerDomainOnly")]
+    pub referrer_domain_only: Option<bool>,
+    #[serde(default)]
+    pub steps: Vec<CheckpointStep>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct BlacklistEntry {
+    pub id: String,
+    pub r#type: BlacklistType,
+    pub value: String,
+    #[serde(default)]
+    pub reason: Option<String>,
+    #[serde(default, alias = "createdAt")]
+    pub created_at: Option<String>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ApiToken {
+    pub id: String,
+    #[serde(default)]
+    pub scopes: Vec<ApiTokenScope>,
+    #[serde(default, alias = "replayProtection")]
+    pub replay_protection: Option<ReplayProtectionMode>,
+    #[serde(default, alias = "authMode")]
+    pub auth_mode: Option<ApiAuthMode>,
+    #[serde(default, alias = "expiresAt")]
+    pub expires_at: Option<String>,
+    #[serde(default, alias = "createdAt")]
+    pub created_at: Option<String>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct DashboardKey {
+    pub id: String,
+    #[serde(default)]
+    pub key: Option<String>,
+    #[serde(default)]
+    pub label: Option<String>,
+    #[serde(default, alias = "durationHours")]
+    pub duration_hours: Option<i64>,
+    #[serde(default, alias = "expiresAt")]
+    pub expires_at: Option<String>,
+    #[serde(default)]
+    pub metadata: Option<Value>,
+    #[serde(default)]
+    pub hwid: Option<String>,
+    #[serde(default, alias = "discordId")]
+    pub discord_id: Option<String>,
+    #[serde(default)]
+    pub revoked: Option<bool>,
+    #[serde(default)]
+    pub status: Option<String>,
+    #[serde(default, alias = "createdAt")]
+    pub created_at: Option<String>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct KeySession {
+    pub id: String,
+    #[serde(default, alias = "keyId")]
+    pub key_id: Option<String>,
+    #[serde(default, alias = "tokenId")]
+    pub token_id: Option<String>,
+    #[serde(default)]
+    pub hwid: Option<String>,
+    #[serde(default, alias = "ipAddress")]
+    pub ip: Option<String>,
+    #[serde(default, alias = "discordId")]
+    pub discord_id: Option<String>,
+    #[serde(default)]
+    pub country: Option<String>,
+    #[serde(default, alias = "createdAt")]
+    pub created_at: Option<String>,
+    #[serde(default, alias = "lastSeenAt")]
+    pub last_seen_at: Option<String>,
+    #[serde(default, alias = "expiresAt")]
+    pub expires_at: Option<String>,
+}
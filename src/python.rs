@@ -0,0 +1,883 @@
+//! Python bindings (`nebulauth` module), built into a wheel with maturin; see
+//! `pyproject.toml` and the `nebulauth.pyi` stubs.
+//!
+//! Every call has a blocking form that releases the GIL and an `_async` form that returns an
+//! asyncio awaitable. Both run on one shared Tokio runtime.
+
+use pyo3::prelude::*;
+use pyo3::sync::GILOnceCell;
+use pyo3::types::PyDict;
+use serde_json::Value;
+use std::collections::HashMap;
+use std::future::Future;
+use std::path::PathBuf;
+use std::sync::Arc;
+
+use crate::{
+    AuthVerifyInput, DashboardAuth, DashboardRequestOptions, DashboardResponse, KeyCreateRequest,
+    KeyRevokeRequest, KeyUpdateRequest, LoginRequest, NebulAuthClientOptions,
+    NebulAuthDashboardClientOptions, NebulAuthResponse, RedeemKeyInput, ReplayProtectionMode,
+    ResetHwidInput, VerifyKeyInput,
+};
+
+mod exceptions {
+    use pyo3::create_exception;
+    use pyo3::exceptions::PyException;
+
+    create_exception!(nebulauth, NebulAuthError, PyException);
+    create_exception!(nebulauth, ConfigError, NebulAuthError);
+    create_exception!(nebulauth, RequestError, NebulAuthError);
+    create_exception!(nebulauth, RequestTimeoutError, RequestError);
+    create_exception!(nebulauth, UrlError, NebulAuthError);
+    create_exception!(nebulauth, CryptoError, NebulAuthError);
+    create_exception!(nebulauth, IoError, NebulAuthError);
+    create_exception!(nebulauth, ApiError, NebulAuthError);
+    create_exception!(nebulauth, DecodeError, NebulAuthError);
+}
+
+use exceptions::*;
+
+fn to_py_err(error: crate::NebulAuthError) -> PyErr {
+    let message = error.to_string();
+    match error {
+        crate::NebulAuthError::Config(_) => ConfigError::new_err(message),
+        crate::NebulAuthError::Request(e) if e.is_timeout() => {
+            RequestTimeoutError::new_err(message)
+        }
+        crate::NebulAuthError::Request(_) => RequestError::new_err(message),
+        crate::NebulAuthError::Url(_) => UrlError::new_err(message),
+        crate::NebulAuthError::Crypto(_) => CryptoError::new_err(message),
+        crate::NebulAuthError::Io(_) => IoError::new_err(message),
+        crate::NebulAuthError::Decode(_) => DecodeError::new_err(message),
+        crate::NebulAuthError::Api { status_code, .. } => Python::with_gil(|py| {
+            let error = ApiError::new_err(message);
+            match error.value(py).setattr("status_code", status_code) {
+                Ok(()) => error,
+                Err(e) => e,
+            }
+        }),
+    }
+}
+
+fn json_to_py(py: Python<'_>, value: &Value) -> PyResult<PyObject> {
+    Ok(py
+        .import("json")?
+        .call_method1("loads", (value.to_string(),))?
+        .unbind())
+}
+
+fn py_to_json(value: &Bound<'_, PyAny>) -> PyResult<Value> {
+    let text: String = value
+        .py()
+        .import("json")?
+        .call_method1("dumps", (value,))?
+        .extract()?;
+    serde_json::from_str(&text).map_err(|e| ConfigError::new_err(e.to_string()))
+}
+
+fn block_on<F, T>(py: Python<'_>, future: F) -> PyResult<T>
+where
+    F: Future<Output = Result<T, crate::NebulAuthError>> + Send,
+    T: Send,
+{
+    py.allow_threads(|| pyo3_async_runtimes::tokio::get_runtime().block_on(future))
+        .map_err(to_py_err)
+}
+
+fn spawn<'py, F, T>(py: Python<'py>, future: F) -> PyResult<Bound<'py, PyAny>>
+where
+    F: Future<Output = Result<T, crate::NebulAuthError>> + Send + 'static,
+    T: for<'a> IntoPyObject<'a> + Send + 'static,
+{
+    pyo3_async_runtimes::tokio::future_into_py(py, async move { future.await.map_err(to_py_err) })
+}
+
+const REPR_DATA_LIMIT: usize = 200;
+
+/// Response of either client. `data` is the decoded JSON body.
+#[pyclass(module = "nebulauth", frozen)]
+pub struct Response {
+    #[pyo3(get)]
+    status_code: u16,
+    #[pyo3(get)]
+    ok: bool,
+    #[pyo3(get)]
+    headers: HashMap<String, String>,
+    body: Value,
+    data: GILOnceCell<PyObject>,
+}
+
+impl From<NebulAuthResponse> for Response {
+    fn from(response: NebulAuthResponse) -> Self {
+        Self {
+            status_code: response.status_code,
+            ok: response.ok,
+            headers: response.headers,
+            body: response.data,
+            data: GILOnceCell::new(),
+        }
+    }
+}
+
+impl From<DashboardResponse> for Response {
+    fn from(response: DashboardResponse) -> Self {
+        Self {
+            status_code: response.status_code,
+            ok: response.ok,
+            headers: response.headers,
+            body: response.data,
+            data: GILOnceCell::new(),
+        }
+    }
+}
+
+#[pymethods]
+impl Response {
+    #[getter]
+    fn data(&self, py: Python<'_>) -> PyResult<PyObject> {
+        self.data
+            .get_or_try_init(py, || json_to_py(py, &self.body))
+            .map(|data| data.clone_ref(py))
+    }
+
+    /// Raises `ApiError` unless the status is 2xx.
+    fn raise_for_status(slf: Py<Self>) -> PyResult<Py<Self>> {
+        let response = slf.get();
+        if response.ok {
+            return Ok(slf);
+        }
+        DashboardResponse {
+            status_code: response.status_code,
+            ok: response.ok,
+            data: response.body.clone(),
+            headers: HashMap::new(),
+        }
+        .error_for_status()
+        .map_err(to_py_err)?;
+        Ok(slf)
+    }
+
+    fn to_dict<'py>(&self, py: Python<'py>) -> PyResult<Bound<'py, PyDict>> {
+        let dict = PyDict::new(py);
+        dict.set_item("status_code", self.status_code)?;
+        dict.set_item("ok", self.ok)?;
+        dict.set_item("data", self.data(py)?)?;
+        dict.set_item("headers", self.headers.clone())?;
+        Ok(dict)
+    }
+
+    /// Shows the body with secrets redacted as in the audit log, cut to
+    /// `REPR_DATA_LIMIT` characters.
+    fn __repr__(&self) -> String {
+        let mut data = crate::audit::redact(&self.body).to_string();
+        if let Some((cut, _)) = data.char_indices().nth(REPR_DATA_LIMIT) {
+            data.truncate(cut);
+            data.push_str("...");
+        }
+        format!(
+            "Response(status_code={}, ok={}, data={data})",
+            self.status_code,
+            if self.ok { "True" } else { "False" },
+        )
+    }
+}
+
+fn replay_protection(value: &str) -> PyResult<ReplayProtectionMode> {
+    match value.to_lowercase().as_str() {
+        "strict" => Ok(ReplayProtectionMode::Strict),
+        "nonce" => Ok(ReplayProtectionMode::Nonce),
+        "none" => Ok(ReplayProtectionMode::None),
+        other => Err(ConfigError::new_err(format!(
+            "replay_protection must be 'strict', 'nonce' or 'none', not '{other}'"
+        ))),
+    }
+}
+
+#[pyclass(name = "NebulAuthClient", module = "nebulauth", frozen)]
+pub struct PyNebulAuthClient {
+    inner: Arc<crate::NebulAuthClient>,
+}
+
+impl PyNebulAuthClient {
+    fn verify_key_call(
+        &self,
+        input: VerifyKeyInput,
+    ) -> impl Future<Output = Result<Response, crate::NebulAuthError>> + Send + 'static {
+        let client = self.inner.clone();
+        async move { client.verify_key(input).await.map(Response::from) }
+    }
+
+    fn auth_verify_call(
+        &self,
+        input: AuthVerifyInput,
+    ) -> impl Future<Output = Result<Response, crate::NebulAuthError>> + Send + 'static {
+        let client = self.inner.clone();
+        async move { client.auth_verify(input).await.map(Response::from) }
+    }
+
+    fn redeem_key_call(
+        &self,
+        input: RedeemKeyInput,
+    ) -> impl Future<Output = Result<Response, crate::NebulAuthError>> + Send + 'static {
+        let client = self.inner.clone();
+        async move { client.redeem_key(input).await.map(Response::from) }
+    }
+
+    fn reset_hwid_call(
+        &self,
+        input: ResetHwidInput,
+    ) -> impl Future<Output = Result<Response, crate::NebulAuthError>> + Send + 'static {
+        let client = self.inner.clone();
+        async move { client.reset_hwid(input).await.map(Response::from) }
+    }
+}
+
+#[pymethods]
+impl PyNebulAuthClient {
+    #[new]
+    #[pyo3(signature = (
+        *,
+        base_url = None,
+        bearer_token = None,
+        signing_secret = None,
+        service_slug = None,
+        replay_protection = "strict",
+        timeout_ms = 15_000,
+    ))]
+    fn new(
+        base_url: Option<String>,
+        bearer_token: Option<String>,
+        signing_secret: Option<String>,
+        service_slug: Option<String>,
+        replay_protection: &str,
+        timeout_ms: u64,
+    ) -> PyResult<Self> {
+        let defaults = NebulAuthClientOptions::default();
+        let client = crate::NebulAuthClient::new(NebulAuthClientOptions {
+            base_url: base_url.unwrap_or(defaults.base_url.clone()),
+            bearer_token,
+            signing_secret,
+            service_slug,
+            replay_protection: self::replay_protection(replay_protection)?,
+            timeout_ms,
+            ..defaults
+        })
+        .map_err(to_py_err)?;
+        Ok(Self {
+            inner: Arc::new(client),
+        })
+    }
+
+    #[pyo3(signature = (key, *, hwid = None, request_id = None, use_pop = false, access_token = None, pop_key = None))]
+    #[allow(clippy::too_many_arguments)]
+    fn verify_key(
+        &self,
+        py: Python<'_>,
+        key: String,
+        hwid: Option<String>,
+        request_id: Option<String>,
+        use_pop: bool,
+        access_token: Option<String>,
+        pop_key: Option<String>,
+    ) -> PyResult<Response> {
+        let input = VerifyKeyInput {
+            key,
+            request_id,
+            hwid,
+            use_pop,
+            access_token,
+            pop_key,
+        };
+        block_on(py, self.verify_key_call(input))
+    }
+
+    #[pyo3(signature = (key, *, hwid = None, request_id = None, use_pop = false, access_token = None, pop_key = None))]
+    #[allow(clippy::too_many_arguments)]
+    fn verify_key_async<'py>(
+        &self,
+        py: Python<'py>,
+        key: String,
+        hwid: Option<String>,
+        request_id: Option<String>,
+        use_pop: bool,
+        access_token: Option<String>,
+        pop_key: Option<String>,
+    ) -> PyResult<Bound<'py, PyAny>> {
+        let input = VerifyKeyInput {
+            key,
+            request_id,
+            hwid,
+            use_pop,
+            access_token,
+            pop_key,
+        };
+        spawn(py, self.verify_key_call(input))
+    }
+
+    #[pyo3(signature = (key, *, hwid = None, request_id = None))]
+    fn auth_verify(
+        &self,
+        py: Python<'_>,
+        key: String,
+        hwid: Option<String>,
+        request_id: Option<String>,
+    ) -> PyResult<Response> {
+        let input = AuthVerifyInput {
+            key,
+            hwid,
+            request_id,
+        };
+        block_on(py, self.auth_verify_call(input))
+    }
+
+    #[pyo3(signature = (key, *, hwid = None, request_id = None))]
+    fn auth_verify_async<'py>(
+        &self,
+        py: Python<'py>,
+        key: String,
+        hwid: Option<String>,
+        request_id: Option<String>,
+    ) -> PyResult<Bound<'py, PyAny>> {
+        let input = AuthVerifyInput {
+            key,
+            hwid,
+            request_id,
+        };
+        spawn(py, self.auth_verify_call(input))
+    }
+
+    #[pyo3(signature = (key, discord_id, *, service_slug = None, request_id = None, use_pop = false, access_token = None, pop_key = None))]
+    #[allow(clippy::too_many_arguments)]
+    fn redeem_key(
+        &self,
+        py: Python<'_>,
+        key: String,
+        discord_id: String,
+        service_slug: Option<String>,
+        request_id: Option<String>,
+        use_pop: bool,
+        access_token: Option<String>,
+        pop_key: Option<String>,
+    ) -> PyResult<Response> {
+        let input = RedeemKeyInput {
+            key,
+            discord_id,
+            service_slug,
+            request_id,
+            use_pop,
+            access_token,
+            pop_key,
+        };
+        block_on(py, self.redeem_key_call(input))
+    }
+
+    #[pyo3(signature = (key, discord_id, *, service_slug = None, request_id = None, use_pop = false, access_token = None, pop_key = None))]
+    #[allow(clippy::too_many_arguments)]
+    fn redeem_key_async<'py>(
+        &self,
+        py: Python<'py>,
+        key: String,
+        discord_id: String,
+        service_slug: Option<String>,
+        request_id: Option<String>,
+        use_pop: bool,
+        access_token: Option<String>,
+        pop_key: Option<String>,
+    ) -> PyResult<Bound<'py, PyAny>> {
+        let input = RedeemKeyInput {
+            key,
+            discord_id,
+            service_slug,
+            request_id,
+            use_pop,
+            access_token,
+            pop_key,
+        };
+        spawn(py, self.redeem_key_call(input))
+    }
+
+    #[pyo3(signature = (*, discord_id = None, key = None, request_id = None, use_pop = false, access_token = None, pop_key = None))]
+    #[allow(clippy::too_many_arguments)]
+    fn reset_hwid(
+        &self,
+        py: Python<'_>,
+        discord_id: Option<String>,
+        key: Option<String>,
+        request_id: Option<String>,
+        use_pop: bool,
+        access_token: Option<String>,
+        pop_key: Option<String>,
+    ) -> PyResult<Response> {
+        let input = ResetHwidInput {
+            discord_id,
+            key,
+            request_id,
+            use_pop,
+            access_token,
+            pop_key,
+        };
+        block_on(py, self.reset_hwid_call(input))
+    }
+
+    #[pyo3(signature = (*, discord_id = None, key = None, request_id = None, use_pop = false, access_token = None, pop_key = None))]
+    #[allow(clippy::too_many_arguments)]
+    fn reset_hwid_async<'py>(
+        &self,
+        py: Python<'py>,
+        discord_id: Option<String>,
+        key: Option<String>,
+        request_id: Option<String>,
+        use_pop: bool,
+        access_token: Option<String>,
+        pop_key: Option<String>,
+    ) -> PyResult<Bound<'py, PyAny>> {
+        let input = ResetHwidInput {
+            discord_id,
+            key,
+            request_id,
+            use_pop,
+            access_token,
+            pop_key,
+        };
+        spawn(py, self.reset_hwid_call(input))
+    }
+}
+
+#[pyclass(name = "NebulAuthDashboardClient", module = "nebulauth", frozen)]
+pub struct PyNebulAuthDashboardClient {
+    inner: Arc<crate::NebulAuthDashboardClient>,
+}
+
+fn request_options(query: Option<HashMap<String, String>>) -> DashboardRequestOptions {
+    DashboardRequestOptions {
+        query: query.unwrap_or_default(),
+        ..Default::default()
+    }
+}
+
+impl PyNebulAuthDashboardClient {
+    /// Runs `call` against the shared client and converts its response.
+    fn call<F, Fut>(
+        &self,
+        call: F,
+    ) -> impl Future<Output = Result<Response, crate::NebulAuthError>> + Send + 'static
+    where
+        F: FnOnce(Arc<crate::NebulAuthDashboardClient>) -> Fut + Send + 'static,
+        Fut: Future<Output = Result<DashboardResponse, crate::NebulAuthError>> + Send,
+    {
+        let client = self.inner.clone();
+        async move { call(client).await.map(Response::from) }
+    }
+
+    fn login_call(
+        &self,
+        email: String,
+        password: String,
+    ) -> impl Future<Output = Result<Response, crate::NebulAuthError>> + Send + 'static {
+        self.call(move |client| async move {
+            client
+                .login(
+                    LoginRequest { email, password },
+                    DashboardRequestOptions::default(),
+                )
+                .await
+        })
+    }
+
+    fn logout_call(
+        &self,
+    ) -> impl Future<Output = Result<Response, crate::NebulAuthError>> + Send + 'static {
+        self.call(|client| async move { client.logout(DashboardRequestOptions::default()).await })
+    }
+
+    fn me_call(
+        &self,
+    ) -> impl Future<Output = Result<Response, crate::NebulAuthError>> + Send + 'static {
+        self.call(|client| async move { client.me(DashboardRequestOptions::default()).await })
+    }
+
+    fn list_keys_call(
+        &self,
+        query: Option<HashMap<String, String>>,
+    ) -> impl Future<Output = Result<Response, crate::NebulAuthError>> + Send + 'static {
+        self.call(move |client| async move { client.list_keys(request_options(query)).await })
+    }
+
+    fn list_key_sessions_call(
+        &self,
+        query: Option<HashMap<String, String>>,
+    ) -> impl Future<Output = Result<Response, crate::NebulAuthError>> + Send + 'static {
+        self.call(
+            move |client| async move { client.list_key_sessions(request_options(query)).await },
+        )
+    }
+
+    fn get_key_call(
+        &self,
+        id: String,
+    ) -> impl Future<Output = Result<Response, crate::NebulAuthError>> + Send + 'static {
+        self.call(move |client| async move {
+            client
+                .get_key(&id, DashboardRequestOptions::default())
+                .await
+        })
+    }
+
+    fn create_key_call(
+        &self,
+        payload: KeyCreateRequest,
+    ) -> impl Future<Output = Result<Response, crate::NebulAuthError>> + Send + 'static {
+        self.call(move |client| async move {
+            client
+                .create_key(payload, DashboardRequestOptions::default())
+                .await
+        })
+    }
+
+    fn update_key_call(
+        &self,
+        id: String,
+        payload: KeyUpdateRequest,
+    ) -> impl Future<Output = Result<Response, crate::NebulAuthError>> + Send + 'static {
+        self.call(move |client| async move {
+            client
+                .update_key(&id, payload, DashboardRequestOptions::default())
+                .await
+        })
+    }
+
+    fn delete_key_call(
+        &self,
+        id: String,
+        reason: Option<String>,
+    ) -> impl Future<Output = Result<Response, crate::NebulAuthError>> + Send + 'static {
+        self.call(move |client| async move {
+            client
+                .delete_key(
+                    &id,
+                    KeyRevokeRequest { reason },
+                    DashboardRequestOptions::default(),
+                )
+                .await
+        })
+    }
+
+    fn reset_key_hwid_call(
+        &self,
+        id: String,
+    ) -> impl Future<Output = Result<Response, crate::NebulAuthError>> + Send + 'static {
+        self.call(move |client| async move {
+            client
+                .reset_key_hwid(&id, DashboardRequestOptions::default())
+                .await
+        })
+    }
+
+    fn analytics_summary_call(
+        &self,
+        days: Option<i64>,
+    ) -> impl Future<Output = Result<Response, crate::NebulAuthError>> + Send + 'static {
+        self.call(move |client| async move {
+            client
+                .analytics_summary(days, DashboardRequestOptions::default())
+                .await
+        })
+    }
+
+    fn request_call(
+        &self,
+        method: String,
+        path: String,
+        body: Option<Value>,
+        query: Option<HashMap<String, String>>,
+    ) -> impl Future<Output = Result<Response, crate::NebulAuthError>> + Send + 'static {
+        self.call(move |client| async move {
+            client
+                .request(&method, &path, body, request_options(query))
+                .await
+        })
+    }
+}
+
+fn key_metadata(metadata: Option<&Bound<'_, PyAny>>) -> PyResult<Option<Value>> {
+    metadata.map(py_to_json).transpose()
+}
+
+#[pymethods]
+impl PyNebulAuthDashboardClient {
+    /// `bearer_token` and `session_cookie` are mutually exclusive. With neither, a session is
+    /// read from `session_file` if given, or created by `login`.
+    #[new]
+    #[pyo3(signature = (
+        *,
+        base_url = None,
+        bearer_token = None,
+        session_cookie = None,
+        session_file = None,
+        auto_relogin = true,
+        timeout_ms = 15_000,
+    ))]
+    fn new(
+        base_url: Option<String>,
+        bearer_token: Option<String>,
+        session_cookie: Option<String>,
+        session_file: Option<PathBuf>,
+        auto_relogin: bool,
+        timeout_ms: u64,
+    ) -> PyResult<Self> {
+        let auth = match (bearer_token, session_cookie) {
+            (Some(_), Some(_)) => {
+                return Err(ConfigError::new_err(
+                    "pass either bearer_token or session_cookie, not both",
+                ))
+            }
+            (Some(bearer_token), None) => Some(DashboardAuth::Bearer { bearer_token }),
+            (None, Some(session_cookie)) => Some(DashboardAuth::Session { session_cookie }),
+            (None, None) => None,
+        };
+        let defaults = NebulAuthDashboardClientOptions::default();
+        let client = crate::NebulAuthDashboardClient::new(NebulAuthDashboardClientOptions {
+            base_url: base_url.unwrap_or(defaults.base_url.clone()),
+            auth,
+            timeout_ms,
+            session_file,
+            auto_relogin,
+            ..defaults
+        })
+        .map_err(to_py_err)?;
+        Ok(Self {
+            inner: Arc::new(client),
+        })
+    }
+
+    #[getter]
+    fn session_cookie(&self) -> Option<String> {
+        self.inner.session_cookie()
+    }
+
+    fn login(&self, py: Python<'_>, email: String, password: String) -> PyResult<Response> {
+        block_on(py, self.login_call(email, password))
+    }
+
+    fn login_async<'py>(
+        &self,
+        py: Python<'py>,
+        email: String,
+        password: String,
+    ) -> PyResult<Bound<'py, PyAny>> {
+        spawn(py, self.login_call(email, password))
+    }
+
+    fn logout(&self, py: Python<'_>) -> PyResult<Response> {
+        block_on(py, self.logout_call())
+    }
+
+    fn logout_async<'py>(&self, py: Python<'py>) -> PyResult<Bound<'py, PyAny>> {
+        spawn(py, self.logout_call())
+    }
+
+    fn me(&self, py: Python<'_>) -> PyResult<Response> {
+        block_on(py, self.me_call())
+    }
+
+    fn me_async<'py>(&self, py: Python<'py>) -> PyResult<Bound<'py, PyAny>> {
+        spawn(py, self.me_call())
+    }
+
+    #[pyo3(signature = (*, query = None))]
+    fn list_keys(
+        &self,
+        py: Python<'_>,
+        query: Option<HashMap<String, String>>,
+    ) -> PyResult<Response> {
+        block_on(py, self.list_keys_call(query))
+    }
+
+    #[pyo3(signature = (*, query = None))]
+    fn list_keys_async<'py>(
+        &self,
+        py: Python<'py>,
+        query: Option<HashMap<String, String>>,
+    ) -> PyResult<Bound<'py, PyAny>> {
+        spawn(py, self.list_keys_call(query))
+    }
+
+    #[pyo3(signature = (*, query = None))]
+    fn list_key_sessions(
+        &self,
+        py: Python<'_>,
+        query: Option<HashMap<String, String>>,
+    ) -> PyResult<Response> {
+        block_on(py, self.list_key_sessions_call(query))
+    }
+
+    #[pyo3(signature = (*, query = None))]
+    fn list_key_sessions_async<'py>(
+        &self,
+        py: Python<'py>,
+        query: Option<HashMap<String, String>>,
+    ) -> PyResult<Bound<'py, PyAny>> {
+        spawn(py, self.list_key_sessions_call(query))
+    }
+
+    fn get_key(&self, py: Python<'_>, id: String) -> PyResult<Response> {
+        block_on(py, self.get_key_call(id))
+    }
+
+    fn get_key_async<'py>(&self, py: Python<'py>, id: String) -> PyResult<Bound<'py, PyAny>> {
+        spawn(py, self.get_key_call(id))
+    }
+
+    #[pyo3(signature = (*, label = None, duration_hours = None, metadata = None))]
+    fn create_key(
+        &self,
+        py: Python<'_>,
+        label: Option<String>,
+        duration_hours: Option<i64>,
+        metadata: Option<&Bound<'_, PyAny>>,
+    ) -> PyResult<Response> {
+        let payload = KeyCreateRequest {
+            label,
+            duration_hours,
+            metadata: key_metadata(metadata)?,
+        };
+        block_on(py, self.create_key_call(payload))
+    }
+
+    #[pyo3(signature = (*, label = None, duration_hours = None, metadata = None))]
+    fn create_key_async<'py>(
+        &self,
+        py: Python<'py>,
+        label: Option<String>,
+        duration_hours: Option<i64>,
+        metadata: Option<&Bound<'_, PyAny>>,
+    ) -> PyResult<Bound<'py, PyAny>> {
+        let payload = KeyCreateRequest {
+            label,
+            duration_hours,
+            metadata: key_metadata(metadata)?,
+        };
+        spawn(py, self.create_key_call(payload))
+    }
+
+    #[pyo3(signature = (id, *, label = None, duration_hours = None, metadata = None))]
+    fn update_key(
+        &self,
+        py: Python<'_>,
+        id: String,
+        label: Option<String>,
+        duration_hours: Option<i64>,
+        metadata: Option<&Bound<'_, PyAny>>,
+    ) -> PyResult<Response> {
+        let payload = KeyUpdateRequest {
+            label,
+            duration_hours,
+            metadata: key_metadata(metadata)?,
+        };
+        block_on(py, self.update_key_call(id, payload))
+    }
+
+    #[pyo3(signature = (id, *, label = None, duration_hours = None, metadata = None))]
+    fn update_key_async<'py>(
+        &self,
+        py: Python<'py>,
+        id: String,
+        label: Option<String>,
+        duration_hours: Option<i64>,
+        metadata: Option<&Bound<'_, PyAny>>,
+    ) -> PyResult<Bound<'py, PyAny>> {
+        let payload = KeyUpdateRequest {
+            label,
+            duration_hours,
+            metadata: key_metadata(metadata)?,
+        };
+        spawn(py, self.update_key_call(id, payload))
+    }
+
+    #[pyo3(signature = (id, *, reason = None))]
+    fn delete_key(&self, py: Python<'_>, id: String, reason: Option<String>) -> PyResult<Response> {
+        block_on(py, self.delete_key_call(id, reason))
+    }
+
+    #[pyo3(signature = (id, *, reason = None))]
+    fn delete_key_async<'py>(
+        &self,
+        py: Python<'py>,
+        id: String,
+        reason: Option<String>,
+    ) -> PyResult<Bound<'py, PyAny>> {
+        spawn(py, self.delete_key_call(id, reason))
+    }
+
+    fn reset_key_hwid(&self, py: Python<'_>, id: String) -> PyResult<Response> {
+        block_on(py, self.reset_key_hwid_call(id))
+    }
+
+    fn reset_key_hwid_async<'py>(
+        &self,
+        py: Python<'py>,
+        id: String,
+    ) -> PyResult<Bound<'py, PyAny>> {
+        spawn(py, self.reset_key_hwid_call(id))
+    }
+
+    #[pyo3(signature = (*, days = None))]
+    fn analytics_summary(&self, py: Python<'_>, days: Option<i64>) -> PyResult<Response> {
+        block_on(py, self.analytics_summary_call(days))
+    }
+
+    #[pyo3(signature = (*, days = None))]
+    fn analytics_summary_async<'py>(
+        &self,
+        py: Python<'py>,
+        days: Option<i64>,
+    ) -> PyResult<Bound<'py, PyAny>> {
+        spawn(py, self.analytics_summary_call(days))
+    }
+
+    /// Any dashboard endpoint, for calls without a dedicated method.
+    #[pyo3(signature = (method, path, body = None, *, query = None))]
+    fn request(
+        &self,
+        py: Python<'_>,
+        method: String,
+        path: String,
+        body: Option<&Bound<'_, PyAny>>,
+        query: Option<HashMap<String, String>>,
+    ) -> PyResult<Response> {
+        let body = body.map(py_to_json).transpose()?;
+        block_on(py, self.request_call(method, path, body, query))
+    }
+
+    #[pyo3(signature = (method, path, body = None, *, query = None))]
+    fn request_async<'py>(
+        &self,
+        py: Python<'py>,
+        method: String,
+        path: String,
+        body: Option<&Bound<'_, PyAny>>,
+        query: Option<HashMap<String, String>>,
+    ) -> PyResult<Bound<'py, PyAny>> {
+        let body = body.map(py_to_json).transpose()?;
+        spawn(py, self.request_call(method, path, body, query))
+    }
+}
+
+#[pymodule]
+fn nebulauth(m: &Bound<'_, PyModule>) -> PyResult<()> {
+    let py = m.py();
+    m.add_class::<Response>()?;
+    m.add_class::<PyNebulAuthClient>()?;
+    m.add_class::<PyNebulAuthDashboardClient>()?;
+    m.add("NebulAuthError", py.get_type::<NebulAuthError>())?;
+    m.add("ConfigError", py.get_type::<ConfigError>())?;
+    m.add("RequestError", py.get_type::<RequestError>())?;
+    m.add("RequestTimeoutError", py.get_type::<RequestTimeoutError>())?;
+    m.add("UrlError", py.get_type::<UrlError>())?;
+    m.add("CryptoError", py.get_type::<CryptoError>())?;
+    m.add("IoError", py.get_type::<IoError>())?;
+    m.add("ApiError", py.get_type::<ApiError>())?;
+    m.add("DecodeError", py.get_type::<DecodeError>())?;
+    m.add("__version__", env!("CARGO_PKG_VERSION"))?;
+    Ok(())
+}
@@ -0,0 +1,287 @@
+use chrono::{DateTime, Duration, Utc};
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, BTreeSet};
+use std::fs::OpenOptions;
+use std::io::Write;
+use std::path::PathBuf;
+
+use crate::bulk::{parse_timestamp, BulkOutcome};
+use crate::{
+    current_timestamp_ms, DashboardRequestOptions, KeySession, NebulAuthDashboardClient,
+    NebulAuthError, RevokeSessionRequest,
+};
+
+const DEFAULT_WINDOW_HOURS: i64 = 1;
+
+#[derive(Debug, Clone)]
+pub struct SharingThresholds {
+    /// Only sessions seen within this window count.
+    pub window: Duration,
+    /// Open sessions of one key seen within `window`, whether or not they overlapped.
+    pub max_sessions_in_window: Option<usize>,
+    pub max_distinct_hwids: Option<usize>,
+    pub max_distinct_ips: Option<usize>,
+}
+
+impl Default for SharingThresholds {
+    fn default() -> Self {
+        Self {
+            window: Duration::hours(DEFAULT_WINDOW_HOURS),
+            max_sessions_in_window: None,
+            max_distinct_hwids: None,
+            max_distinct_ips: None,
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "rule", rename_all = "snake_case")]
+pub enum Violation {
+    SessionsInWindow { count: usize, limit: usize },
+    DistinctHwids { count: usize, limit: usize },
+    DistinctIps { count: usize, limit: usize },
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SharingFinding {
+    pub key_id: String,
+    /// Sessions in the window, oldest first.
+    pub sessions: Vec<KeySession>,
+    pub hwids: BTreeSet<String>,
+    pub ips: BTreeSet<String>,
+    pub violations: Vec<Violation>,
+}
+
+fn session_time(session: &KeySession) -> Option<DateTime<Utc>> {
+    session
+        .last_seen_at
+        .as_deref()
+        .or(session.created_at.as_deref())
+        .and_then(parse_timestamp)
+}
+
+/// Groups sessions by key and returns the keys that exceed any threshold.
+pub fn analyze(
+    sessions: &[KeySession],
+    thresholds: &SharingThresholds,
+    now: DateTime<Utc>,
+) -> Vec<SharingFinding> {
+    let since = now - thresholds.window;
+    let mut by_key: BTreeMap<&str, Vec<&KeySession>> = BTreeMap::new();
+    for session in sessions {
+        let Some(key_id) = session.key_id.as_deref() else {
+            continue;
+        };
+        if session
+            .expires_at
+            .as_deref()
+            .and_then(parse_timestamp)
+            .is_some_and(|at| at <= now)
+        {
+            continue;
+        }
+        if session_time(session).is_some_and(|at| at < since) {
+            continue;
+        }
+        by_key.entry(key_id).or_default().push(session);
+    }
+
+    let mut findings = Vec::new();
+    for (key_id, mut sessions) in by_key {
+        // Oldest first; sessions without a timestamp go last so they are never the one kept.
+        sessions.sort_by_key(|session| {
+            let time = session_time(session);
+            (time.is_none(), time)
+        });
+        let hwids: BTreeSet<String> = sessions.iter().filter_map(|s| s.hwid.clone()).collect();
+        let ips: BTreeSet<String> = sessions.iter().filter_map(|s| s.ip.clone()).collect();
+
+        let mut violations = Vec::new();
+        if let Some(limit) = thresholds.max_sessions_in_window {
+            if sessions.len() > limit {
+                violations.push(Violation::SessionsInWindow {
+                    count: sessions.len(),
+                    limit,
+                });
+            }
+        }
+        if let Some(limit) = thresholds.max_distinct_hwids {
+            if hwids.len() > limit {
+                violations.push(Violation::DistinctHwids {
+                    count: hwids.len(),
+                    limit,
+                });
+            }
+        }
+        if let Some(limit) = thresholds.max_distinct_ips {
+            if ips.len() > limit {
+                violations.push(Violation::DistinctIps {
+                    count: ips.len(),
+                    limit,
+                });
+            }
+        }
+
+        if !violations.is_empty() {
+            findings.push(SharingFinding {
+                key_id: key_id.to_string(),
+                sessions: sessions.into_iter().cloned().collect(),
+                hwids,
+                ips,
+                violations,
+            });
+        }
+    }
+    findings
+}
+
+/// What to do with a flagged key. With `terminate_all_for_key` a single revoke call ends
+/// every session of the key; otherwise each session except the oldest is revoked. The key-level
+/// actions (`revoke_key`, `reset_hwid`, `blacklist_discord`) ride along with each revoke call
+/// of a key until one of them succeeds.
+#[derive(Debug, Clone, Default)]
+pub struct EnforcementPolicy {
+    pub reason: Option<String>,
+    pub revoke_key: bool,
+    pub reset_hwid: bool,
+    pub blacklist_discord: bool,
+    pub terminate_all_for_key: bool,
+}
+
+impl EnforcementPolicy {
+    fn request(&self) -> RevokeSessionRequest {
+        RevokeSessionRequest {
+            reason: self.reason.clone(),
+            revoke_key: self.revoke_key.then_some(true),
+            reset_hwid: self.reset_hwid.then_some(true),
+            blacklist_discord: self.blacklist_discord.then_some(true),
+            terminate_all_for_key: self.terminate_all_for_key.then_some(true),
+            ..Default::default()
+        }
+    }
+
+    /// Plain session revoke once the key-level actions have been applied.
+    fn session_request(&self) -> RevokeSessionRequest {
+        RevokeSessionRequest {
+            reason: self.reason.clone(),
+            ..Default::default()
+        }
+    }
+
+    fn targets<'f>(&self, finding: &'f SharingFinding) -> Vec<&'f KeySession> {
+        if self.terminate_all_for_key {
+            finding.sessions.last().into_iter().collect()
+        } else {
+            finding.sessions.iter().skip(1).collect()
+        }
+    }
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct EnforceOptions {
+    pub dry_run: bool,
+    /// Every planned or executed revoke is appended here as one JSON line.
+    pub audit_log: Option<PathBuf>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct EnforcementAction {
+    pub key_id: String,
+    pub session_id: String,
+    pub outcome: BulkOutcome,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct EnforcementReport {
+    pub dry_run: bool,
+    pub findings: Vec<SharingFinding>,
+    pub actions: Vec<EnforcementAction>,
+}
+
+#[derive(Serialize)]
+struct AuditEntry<'a> {
+    timestamp_ms: u128,
+    dry_run: bool,
+    key_id: &'a str,
+    session_id: &'a str,
+    violations: &'a [Violation],
+    hwids: &'a BTreeSet<String>,
+    ips: &'a BTreeSet<String>,
+    request: &'a RevokeSessionRequest,
+    outcome: &'a BulkOutcome,
+}
+
+pub async fn enforce(
+    client: &NebulAuthDashboardClient,
+    thresholds: &SharingThresholds,
+    policy: &EnforcementPolicy,
+    enforce_options: EnforceOptions,
+    options: DashboardRequestOptions,
+) -> Result<EnforcementReport, NebulAuthError> {
+    let sessions: Vec<KeySession> = client
+        .list_key_sessions(options.clone())
+        .await?
+        .error_for_status()?
+        .items()?;
+    let now = DateTime::from_timestamp_millis(current_timestamp_ms() as i64).unwrap_or_default();
+    let findings = analyze(&sessions, thresholds, now);
+
+    let mut audit = match &enforce_options.audit_log {
+        Some(path) => Some(OpenOptions::new().create(true).append(true).open(path)?),
+        None => None,
+    };
+    let mut actions = Vec::new();
+
+    for finding in &findings {
+        let mut key_actions_applied = false;
+        for session in policy.targets(finding) {
+            let request = if key_actions_applied {
+                policy.session_request()
+            } else {
+                policy.request()
+            };
+            let outcome = if enforce_options.dry_run {
+                BulkOutcome::Planned
+            } else {
+                match client
+                    .revoke_key_session(&session.id, request.clone(), options.clone())
+                    .await
+                    .and_then(|response| response.error_for_status())
+                {
+                    Ok(_) => BulkOutcome::Succeeded,
+                    Err(e) => BulkOutcome::Failed(e.to_string()),
+                }
+            };
+            key_actions_applied |= !matches!(outcome, BulkOutcome::Failed(_));
+
+            if let Some(file) = audit.as_mut() {
+                let entry = AuditEntry {
+                    timestamp_ms: current_timestamp_ms(),
+                    dry_run: enforce_options.dry_run,
+                    key_id: &finding.key_id,
+                    session_id: &session.id,
+                    violations: &finding.violations,
+                    hwids: &finding.hwids,
+                    ips: &finding.ips,
+                    request: &request,
+                    outcome: &outcome,
+                };
+                let line = serde_json::to_string(&entry)
+                    .map_err(|e| NebulAuthError::Config(e.to_string()))?;
+                writeln!(file, "{line}")?;
+            }
+
+            actions.push(EnforcementAction {
+                key_id: finding.key_id.clone(),
+                session_id: session.id.clone(),
+                outcome,
+            });
+        }
+    }
+
+    Ok(EnforcementReport {
+        dry_run: enforce_options.dry_run,
+        findings,
+        actions,
+    })
+}
@@ -0,0 +1,244 @@
+use std::future::Future;
+#[cfg(feature = "dashboard")]
+use std::sync::atomic::{AtomicU32, Ordering};
+#[cfg(all(
+    any(feature = "tracing", feature = "metrics"),
+    not(target_arch = "wasm32")
+))]
+use std::time::Instant;
+#[cfg(all(any(feature = "tracing", feature = "metrics"), target_arch = "wasm32"))]
+use web_time::Instant;
+
+use crate::NebulAuthError;
+
+/// Path segments kept as-is in the `endpoint` metric label; anything else after the
+/// first segment is treated as an ID and replaced with `:id`.
+#[cfg(feature = "metrics")]
+const STATIC_SEGMENTS: &[&str] = &["redeem", "reset-hwid", "verify"];
+
+/// Dashboard-only segments, left out of runtime-only builds.
+#[cfg(all(feature = "metrics", feature = "dashboard"))]
+const DASHBOARD_STATIC_SEGMENTS: &[&str] = &[
+    "activity",
+    "batch",
+    "extend-duration",
+    "geo",
+    "login",
+    "logout",
+    "summary",
+];
+
+#[cfg(feature = "metrics")]
+fn is_static_segment(segment: &str) -> bool {
+    #[cfg(feature = "dashboard")]
+    if DASHBOARD_STATIC_SEGMENTS.contains(&segment) {
+        return true;
+    }
+    STATIC_SEGMENTS.contains(&segment)
+}
+
+/// Keeps per-ID paths like `/keys/abc123` from becoming separate metric series.
+#[cfg(feature = "metrics")]
+fn endpoint_label(path: &str) -> String {
+    let path = path.split('?').next().unwrap_or_default();
+    let mut label = String::new();
+    for (i, segment) in path.split('/').filter(|s| !s.is_empty()).enumerate() {
+        label.push('/');
+        if i == 0 || is_static_segment(segment) {
+            label.push_str(segment);
+        } else {
+            label.push_str(":id");
+        }
+    }
+    if label.is_empty() {
+        label.push('/');
+    }
+    label
+}
+
+#[cfg(feature = "metrics")]
+fn status_class(result: Result<u16, &NebulAuthError>) -> &'static str {
+    match result {
+        Ok(100..=199) => "1xx",
+        Ok(200..=299) => "2xx",
+        Ok(300..=399) => "3xx",
+        Ok(400..=499) => "4xx",
+        Ok(_) => "5xx",
+        Err(NebulAuthError::Request(e)) if e.is_timeout() => "timeout",
+        Err(_) => "error",
+    }
+}
+
+/// Response headers the API may use for its own request ID, in order of preference.
+#[cfg(feature = "tracing")]
+const REQUEST_ID_HEADERS: &[&str] = &["x-request-id", "request-id"];
+
+/// Adds W3C `traceparent`/`tracestate` headers for the current span, so the server's trace
+/// continues from the caller's.
+#[cfg(feature = "opentelemetry")]
+pub(crate) fn propagate(headers: &mut reqwest::header::HeaderMap) {
+    use opentelemetry::trace::TraceContextExt;
+    use reqwest::header::HeaderValue;
+    use tracing_opentelemetry::OpenTelemetrySpanExt;
+
+    let context = tracing::Span::current().context();
+    let span = context.span();
+    let span_context = span.span_context();
+    if !span_context.is_valid() {
+        return;
+    }
+    let traceparent = format!(
+        "00-{}-{}-{:02x}",
+        span_context.trace_id(),
+        span_context.span_id(),
+        span_context.trace_flags().to_u8()
+    );
+    if let Ok(value) = HeaderValue::from_str(&traceparent) {
+        headers.insert("traceparent", value);
+    }
+    let state = span_context.trace_state().header();
+    if let Ok(value) = HeaderValue::from_str(&state) {
+        if !state.is_empty() {
+            headers.insert("tracestate", value);
+        }
+    }
+}
+
+#[cfg(not(feature = "opentelemetry"))]
+pub(crate) fn propagate(_headers: &mut reqwest::header::HeaderMap) {}
+
+/// Records the server's request ID on the current span for correlating with NebulAuth logs.
+#[cfg_attr(not(feature = "tracing"), allow(unused_variables))]
+pub(crate) fn record_response(headers: &[(String, String)]) {
+    #[cfg(feature = "tracing")]
+    if let Some(id) = REQUEST_ID_HEADERS.iter().find_map(|candidate| {
+        headers
+            .iter()
+            .find(|(name, _)| name.eq_ignore_ascii_case(candidate))
+            .map(|(_, value)| value.as_str())
+    }) {
+        tracing::Span::current().record("server_request_id", id);
+    }
+}
+
+/// Per-request instrumentation shared by both clients. Only the endpoint path, method,
+/// status, latency, retry count and the caller's request ID are recorded; headers and
+/// bodies (tokens, keys, signatures) never are.
+pub(crate) struct RequestTelemetry {
+    #[cfg(feature = "tracing")]
+    span: tracing::Span,
+    #[cfg(any(feature = "tracing", feature = "metrics"))]
+    started: Instant,
+    #[cfg(feature = "metrics")]
+    labels: [(&'static str, String); 3],
+    #[cfg(feature = "dashboard")]
+    retries: AtomicU32,
+}
+
+impl RequestTelemetry {
+    #[cfg_attr(not(feature = "tracing"), allow(unused_variables))]
+    pub(crate) fn start(
+        client: &'static str,
+        method: &str,
+        endpoint: &str,
+        request_id: Option<&str>,
+    ) -> Self {
+        Self {
+            #[cfg(feature = "tracing")]
+            span: tracing::info_span!(
+                "nebulauth.request",
+                client,
+                method,
+                endpoint,
+                request_id,
+                status = tracing::field::Empty,
+                latency_ms = tracing::field::Empty,
+                server_request_id = tracing::field::Empty,
+                retries = 0u32,
+            ),
+            #[cfg(any(feature = "tracing", feature = "metrics"))]
+            started: Instant::now(),
+            #[cfg(feature = "metrics")]
+            labels: [
+                ("client", client.to_string()),
+                ("method", method.to_uppercase()),
+                ("endpoint", endpoint_label(endpoint)),
+            ],
+            #[cfg(feature = "dashboard")]
+            retries: AtomicU32::new(0),
+        }
+    }
+
+    #[cfg(feature = "dashboard")]
+    #[cfg_attr(not(feature = "tracing"), allow(unused_variables))]
+    pub(crate) fn retried(&self) {
+        let retries = self.retries.fetch_add(1, Ordering::Relaxed) + 1;
+        #[cfg(feature = "tracing")]
+        self.span.record("retries", retries);
+        #[cfg(feature = "metrics")]
+        metrics::counter!("nebulauth_client_retries_total", &self.labels).increment(1);
+    }
+
+    /// Whether a dashboard request could use the stored session (`hit`) or had to log in
+    /// again because it expired (`miss`).
+    #[cfg(feature = "dashboard")]
+    #[cfg_attr(not(feature = "metrics"), allow(unused_variables))]
+    pub(crate) fn session_cache(&self, hit: bool) {
+        #[cfg(feature = "metrics")]
+        metrics::counter!(
+            "nebulauth_client_session_cache_total",
+            "client" => self.labels[0].1.clone(),
+            "result" => if hit { "hit" } else { "miss" },
+        )
+        .increment(1);
+    }
+
+    #[cfg(feature = "tracing")]
+    pub(crate) fn instrument<F: Future>(&self, future: F) -> impl Future<Output = F::Output> {
+        tracing::Instrument::instrument(future, self.span.clone())
+    }
+
+    #[cfg(not(feature = "tracing"))]
+    pub(crate) fn instrument<F: Future>(&self, future: F) -> F {
+        future
+    }
+
+    #[cfg_attr(
+        not(any(feature = "tracing", feature = "metrics")),
+        allow(unused_variables)
+    )]
+    pub(crate) fn finish(&self, result: Result<u16, &NebulAuthError>) {
+        #[cfg(feature = "metrics")]
+        {
+            let class = status_class(result);
+            let mut labels = self.labels.to_vec();
+            labels.push(("status_class", class.to_string()));
+            metrics::counter!("nebulauth_client_requests_total", &labels).increment(1);
+            metrics::histogram!("nebulauth_client_request_duration_seconds", &self.labels)
+                .record(self.started.elapsed().as_secs_f64());
+            if class == "timeout" {
+                metrics::counter!("nebulauth_client_timeouts_total", &self.labels).increment(1);
+            }
+        }
+
+        #[cfg(feature = "tracing")]
+        {
+            let latency_ms = self.started.elapsed().as_millis() as u64;
+            self.span.record("latency_ms", latency_ms);
+            let _entered = self.span.enter();
+            match result {
+                Ok(status) => {
+                    self.span.record("status", status);
+                    if status >= 500 {
+                        tracing::warn!(status, latency_ms, "nebulauth request failed");
+                    } else {
+                        tracing::debug!(status, latency_ms, "nebulauth request finished");
+                    }
+                }
+                Err(error) => {
+                    tracing::warn!(%error, latency_ms, "nebulauth request errored");
+                }
+            }
+        }
+    }
+}
@@ -0,0 +1,255 @@
+use reqwest::header::{HeaderMap, HeaderName, HeaderValue};
+use serde::{Deserialize, Serialize};
+use std::future::Future;
+use std::pin::Pin;
+#[cfg(target_arch = "wasm32")]
+use std::time::Duration;
+
+use crate::NebulAuthError;
+
+/// A request as handed to a [`Transport`]. Header names are lowercase.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct HttpRequest {
+    pub method: String,
+    pub url: String,
+    pub headers: Vec<(String, String)>,
+    pub body: Option<String>,
+}
+
+impl HttpRequest {
+    #[cfg(any(feature = "runtime", feature = "dashboard"))]
+    pub(crate) fn new(
+        method: &str,
+        url: String,
+        headers: &HeaderMap,
+        body: Option<String>,
+    ) -> Self {
+        Self {
+            method: method.to_uppercase(),
+            url,
+            headers: header_pairs(headers),
+            body,
+        }
+    }
+
+    pub fn header(&self, name: &str) -> Option<&str> {
+        find_header(&self.headers, name)
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct HttpResponse {
+    pub status: u16,
+    pub headers: Vec<(String, String)>,
+    pub body: String,
+}
+
+impl HttpResponse {
+    pub fn header(&self, name: &str) -> Option<&str> {
+        find_header(&self.headers, name)
+    }
+}
+
+fn find_header<'a>(headers: &'a [(String, String)], name: &str) -> Option<&'a str> {
+    headers
+        .iter()
+        .find(|(key, _)| key.eq_ignore_ascii_case(name))
+        .map(|(_, value)| value.as_str())
+}
+
+fn header_pairs(headers: &HeaderMap) -> Vec<(String, String)> {
+    headers
+        .iter()
+        .map(|(key, value)| {
+            (
+                key.to_string(),
+                value.to_str().unwrap_or_default().to_string(),
+            )
+        })
+        .collect()
+}
+
+pub type TransportFuture<'a> =
+    Pin<Box<dyn Future<Output = Result<HttpResponse, NebulAuthError>> + Send + 'a>>;
+
+/// Response body chunks, in the order they arrived.
+#[cfg(feature = "dashboard")]
+pub type BodyStream<'a> =
+    Pin<Box<dyn futures_util::Stream<Item = Result<Vec<u8>, NebulAuthError>> + Send + 'a>>;
+
+/// A response whose body is read as it arrives.
+#[cfg(feature = "dashboard")]
+pub struct StreamingResponse<'a> {
+    pub status: u16,
+    pub headers: Vec<(String, String)>,
+    pub body: BodyStream<'a>,
+}
+
+#[cfg(feature = "dashboard")]
+impl StreamingResponse<'_> {
+    /// Wraps an already-read response; the body arrives as a single chunk.
+    pub fn buffered(response: HttpResponse) -> Self {
+        let body = response.body.into_bytes();
+        Self {
+            status: response.status,
+            headers: response.headers,
+            body: Box::pin(futures_util::stream::iter(
+                (!body.is_empty()).then_some(Ok(body)),
+            )),
+        }
+    }
+
+    pub async fn text(self) -> Result<String, NebulAuthError> {
+        use futures_util::TryStreamExt;
+
+        let body: Vec<u8> = self
+            .body
+            .try_fold(Vec::new(), |mut body, chunk| async move {
+                body.extend_from_slice(&chunk);
+                Ok(body)
+            })
+            .await?;
+        Ok(String::from_utf8_lossy(&body).into_owned())
+    }
+}
+
+#[cfg(feature = "dashboard")]
+pub type StreamingFuture<'a> =
+    Pin<Box<dyn Future<Output = Result<StreamingResponse<'a>, NebulAuthError>> + Send + 'a>>;
+
+/// Sends the requests of both clients. Swap it with `with_transport` to record, replay or
+/// otherwise intercept traffic.
+pub trait Transport: Send + Sync {
+    fn send<'a>(&'a self, request: HttpRequest) -> TransportFuture<'a>;
+
+    /// Sends `request` and hands back the body as it arrives. The default reads the whole
+    /// response with [`send`](Transport::send), which is what recording and replay need.
+    #[cfg(feature = "dashboard")]
+    fn send_streaming<'a>(&'a self, request: HttpRequest) -> StreamingFuture<'a> {
+        Box::pin(async move { Ok(StreamingResponse::buffered(self.send(request).await?)) })
+    }
+}
+
+/// Builds the default transport for this target, with `timeout` applied to every request.
+#[cfg(any(feature = "runtime", all(feature = "vcr", feature = "dashboard")))]
+pub(crate) fn default_transport(
+    timeout: std::time::Duration,
+) -> Result<std::sync::Arc<dyn Transport>, NebulAuthError> {
+    #[cfg(not(target_arch = "wasm32"))]
+    let transport = ReqwestTransport::new(reqwest::Client::builder().timeout(timeout).build()?);
+    #[cfg(target_arch = "wasm32")]
+    let transport = FetchTransport::new(timeout);
+    Ok(std::sync::Arc::new(transport))
+}
+
+/// The default transport.
+#[cfg(not(target_arch = "wasm32"))]
+#[derive(Debug, Clone)]
+pub struct ReqwestTransport {
+    client: reqwest::Client,
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+impl ReqwestTransport {
+    pub fn new(client: reqwest::Client) -> Self {
+        Self { client }
+    }
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+impl Transport for ReqwestTransport {
+    fn send<'a>(&'a self, request: HttpRequest) -> TransportFuture<'a> {
+        Box::pin(async move {
+            let response = build_request(&self.client, request)?.send().await?;
+            read_response(response).await
+        })
+    }
+
+    #[cfg(feature = "dashboard")]
+    fn send_streaming<'a>(&'a self, request: HttpRequest) -> StreamingFuture<'a> {
+        Box::pin(async move {
+            let response = build_request(&self.client, request)?.send().await?;
+            let status = response.status().as_u16();
+            let headers = header_pairs(response.headers());
+            let body = futures_util::stream::try_unfold(response, |mut response| async move {
+                Ok(response
+                    .chunk()
+                    .await?
+                    .map(|chunk| (chunk.to_vec(), response)))
+            });
+            Ok(StreamingResponse {
+                status,
+                headers,
+                body: Box::pin(body),
+            })
+        })
+    }
+}
+
+/// The default transport on wasm32. Requests go through the global `fetch`, so it works in
+/// browsers, service workers, edge runtimes and Node 18+.
+#[cfg(target_arch = "wasm32")]
+#[derive(Debug, Clone)]
+pub struct FetchTransport {
+    client: reqwest::Client,
+    timeout: Duration,
+}
+
+#[cfg(target_arch = "wasm32")]
+impl FetchTransport {
+    pub fn new(timeout: Duration) -> Self {
+        Self {
+            client: reqwest::Client::new(),
+            timeout,
+        }
+    }
+}
+
+#[cfg(target_arch = "wasm32")]
+impl Transport for FetchTransport {
+    fn send<'a>(&'a self, request: HttpRequest) -> TransportFuture<'a> {
+        // JS promises are not `Send`; wasm32 runs everything on one thread, and `SendWrapper`
+        // panics rather than misbehaving if that ever changes.
+        Box::pin(send_wrapper::SendWrapper::new(async move {
+            let response = build_request(&self.client, request)?
+                .timeout(self.timeout)
+                .send()
+                .await?;
+            read_response(response).await
+        }))
+    }
+}
+
+fn build_request(
+    client: &reqwest::Client,
+    request: HttpRequest,
+) -> Result<reqwest::RequestBuilder, NebulAuthError> {
+    let method = reqwest::Method::from_bytes(request.method.as_bytes())
+        .map_err(|e| NebulAuthError::Config(format!("invalid method '{}': {e}", request.method)))?;
+    let mut headers = HeaderMap::new();
+    for (key, value) in &request.headers {
+        let header_name = HeaderName::from_bytes(key.as_bytes())
+            .map_err(|e| NebulAuthError::Config(format!("invalid header name '{key}': {e}")))?;
+        let header_value = HeaderValue::from_str(value).map_err(|e| {
+            NebulAuthError::Config(format!("invalid header value for '{key}': {e}"))
+        })?;
+        headers.append(header_name, header_value);
+    }
+
+    let mut builder = client.request(method, &request.url).headers(headers);
+    if let Some(body) = request.body {
+        builder = builder.body(body);
+    }
+    Ok(builder)
+}
+
+async fn read_response(response: reqwest::Response) -> Result<HttpResponse, NebulAuthError> {
+    let status = response.status().as_u16();
+    let headers = header_pairs(response.headers());
+    let body = response.text().await?;
+    Ok(HttpResponse {
+        status,
+        headers,
+        body,
+    })
+}
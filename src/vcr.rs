@@ -0,0 +1,295 @@
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::fmt;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+use crate::audit::{redact, REDACTED};
+use crate::transport::{self, HttpRequest, HttpResponse, Transport, TransportFuture};
+use crate::NebulAuthError;
+
+/// Set to `record` to hit the real API and rewrite cassettes in [`from_env`].
+pub const VCR_MODE_ENV: &str = "NEBULAUTH_VCR";
+
+/// Request signing headers. Their values change on every request, so they are redacted when
+/// recording and compared with a [`SignatureMatcher`] when replaying.
+pub const SIGNATURE_HEADERS: &[&str] = &["x-signature", "x-nonce", "x-timestamp", "x-body-sha256"];
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Interaction {
+    pub request: HttpRequest,
+    pub response: HttpResponse,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct Cassette {
+    pub interactions: Vec<Interaction>,
+}
+
+impl Cassette {
+    pub fn load(path: &Path) -> Result<Self, NebulAuthError> {
+        let contents = std::fs::read_to_string(path)?;
+        serde_json::from_str(&contents)
+            .map_err(|e| NebulAuthError::Decode(format!("invalid cassette: {e}")))
+    }
+
+    pub fn save(&self, path: &Path) -> Result<(), NebulAuthError> {
+        let contents = serde_json::to_string_pretty(self)
+            .map_err(|e| NebulAuthError::Config(format!("failed to serialize cassette: {e}")))?;
+        std::fs::write(path, contents + "\n")?;
+        Ok(())
+    }
+}
+
+/// Redacts the value of every `name=value` pair, or only the first one for `set-cookie`
+/// (the rest are attributes like `Path=/`).
+fn redact_cookies(value: &str, first_only: bool) -> String {
+    value
+        .split(';')
+        .enumerate()
+        .map(|(i, part)| match part.split_once('=') {
+            Some((name, _)) if i == 0 || !first_only => format!("{name}={REDACTED}"),
+            _ => part.to_string(),
+        })
+        .collect::<Vec<_>>()
+        .join(";")
+}
+
+fn redact_header(name: &str, value: &str) -> String {
+    match name {
+        "authorization" => match value.split_once(' ') {
+            Some((scheme, _)) => format!("{scheme} {REDACTED}"),
+            None => REDACTED.to_string(),
+        },
+        "cookie" => redact_cookies(value, false),
+        "set-cookie" => redact_cookies(value, true),
+        _ if SIGNATURE_HEADERS.contains(&name) => REDACTED.to_string(),
+        _ => value.to_string(),
+    }
+}
+
+fn redact_body(body: &str) -> String {
+    match serde_json::from_str::<Value>(body) {
+        Ok(value) => serde_json::to_string(&redact(&value)).unwrap_or_else(|_| body.to_string()),
+        Err(_) => body.to_string(),
+    }
+}
+
+fn redact_headers(headers: &[(String, String)]) -> Vec<(String, String)> {
+    headers
+        .iter()
+        .map(|(name, value)| {
+            let name = name.to_lowercase();
+            let value = redact_header(&name, value);
+            (name, value)
+        })
+        .collect()
+}
+
+/// Copy of `request` with credentials, signatures, secrets and license keys removed.
+pub fn scrub_request(request: &HttpRequest) -> HttpRequest {
+    HttpRequest {
+        method: request.method.clone(),
+        url: request.url.clone(),
+        headers: redact_headers(&request.headers),
+        body: request.body.as_deref().map(redact_body),
+    }
+}
+
+pub fn scrub_response(response: &HttpResponse) -> HttpResponse {
+    HttpResponse {
+        status: response.status,
+        headers: redact_headers(&response.headers),
+        body: redact_body(&response.body),
+    }
+}
+
+/// Path and query of `url` with sorted parameters, so cassettes replay against any host.
+fn target(url: &str) -> String {
+    let Ok(url) = url::Url::parse(url) else {
+        return url.to_string();
+    };
+    let mut pairs: Vec<_> = url.query_pairs().collect();
+    if pairs.is_empty() {
+        return url.path().to_string();
+    }
+    pairs.sort();
+    let query = pairs
+        .iter()
+        .map(|(key, value)| format!("{key}={value}"))
+        .collect::<Vec<_>>()
+        .join("&");
+    format!("{}?{query}", url.path())
+}
+
+fn same_body(recorded: Option<&str>, live: Option<&str>) -> bool {
+    let parse = |body: Option<&str>| body.and_then(|body| serde_json::from_str::<Value>(body).ok());
+    match (parse(recorded), parse(live)) {
+        (Some(recorded), Some(live)) => recorded == live,
+        _ => recorded.unwrap_or_default() == live.unwrap_or_default(),
+    }
+}
+
+/// Called with the recorded (scrubbed) request and the live one, unscrubbed.
+pub type RequestMatcher = Arc<dyn Fn(&HttpRequest, &HttpRequest) -> bool + Send + Sync>;
+
+/// How signature headers are compared when replaying.
+#[derive(Clone, Default)]
+pub enum SignatureMatcher {
+    /// The live request must carry exactly the signature headers the recorded one had.
+    #[default]
+    Present,
+    /// Signature headers are not compared.
+    Ignore,
+    Custom(RequestMatcher),
+}
+
+impl fmt::Debug for SignatureMatcher {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            SignatureMatcher::Present => f.write_str("Present"),
+            SignatureMatcher::Ignore => f.write_str("Ignore"),
+            SignatureMatcher::Custom(_) => f.write_str("Custom(..)"),
+        }
+    }
+}
+
+impl SignatureMatcher {
+    fn matches(&self, recorded: &HttpRequest, live: &HttpRequest) -> bool {
+        match self {
+            SignatureMatcher::Present => SIGNATURE_HEADERS
+                .iter()
+                .all(|name| recorded.header(name).is_some() == live.header(name).is_some()),
+            SignatureMatcher::Ignore => true,
+            SignatureMatcher::Custom(matcher) => matcher(recorded, live),
+        }
+    }
+}
+
+/// Forwards to `inner` and writes every exchange, scrubbed, to a cassette file. The file is
+/// rewritten after each request, starting from an empty cassette.
+pub struct RecordingTransport {
+    inner: Arc<dyn Transport>,
+    path: PathBuf,
+    cassette: Mutex<Cassette>,
+}
+
+impl RecordingTransport {
+    pub fn new(inner: Arc<dyn Transport>, path: impl Into<PathBuf>) -> Self {
+        Self {
+            inner,
+            path: path.into(),
+            cassette: Mutex::new(Cassette::default()),
+        }
+    }
+
+    pub fn cassette(&self) -> Cassette {
+        self.cassette
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .clone()
+    }
+}
+
+impl Transport for RecordingTransport {
+    fn send<'a>(&'a self, request: HttpRequest) -> TransportFuture<'a> {
+        Box::pin(async move {
+            let scrubbed = scrub_request(&request);
+            let response = self.inner.send(request).await?;
+            let mut cassette = self.cassette.lock().unwrap_or_else(|e| e.into_inner());
+            cassette.interactions.push(Interaction {
+                request: scrubbed,
+                response: scrub_response(&response),
+            });
+            cassette.save(&self.path)?;
+            Ok(response)
+        })
+    }
+}
+
+/// Serves responses from a cassette without any network access. Each recorded interaction is
+/// used once, in order, so repeated requests get their recorded responses in sequence.
+/// Method, path, query and JSON body must match; headers other than the signature headers
+/// are not compared.
+pub struct ReplayTransport {
+    cassette: Cassette,
+    used: Mutex<Vec<bool>>,
+    matcher: SignatureMatcher,
+}
+
+impl ReplayTransport {
+    pub fn new(cassette: Cassette) -> Self {
+        Self {
+            used: Mutex::new(vec![false; cassette.interactions.len()]),
+            cassette,
+            matcher: SignatureMatcher::default(),
+        }
+    }
+
+    pub fn load(path: &Path) -> Result<Self, NebulAuthError> {
+        Ok(Self::new(Cassette::load(path)?))
+    }
+
+    pub fn with_matcher(mut self, matcher: SignatureMatcher) -> Self {
+        self.matcher = matcher;
+        self
+    }
+
+    /// Interactions not replayed yet.
+    pub fn remaining(&self) -> usize {
+        self.used
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .iter()
+            .filter(|used| !**used)
+            .count()
+    }
+
+    fn find(&self, live: &HttpRequest) -> Option<HttpResponse> {
+        let scrubbed = scrub_request(live);
+        let mut used = self.used.lock().unwrap_or_else(|e| e.into_inner());
+        let index =
+            self.cassette
+                .interactions
+                .iter()
+                .enumerate()
+                .position(|(index, interaction)| {
+                    let recorded = &interaction.request;
+                    !used[index]
+                        && recorded.method == scrubbed.method
+                        && target(&recorded.url) == target(&scrubbed.url)
+                        && same_body(recorded.body.as_deref(), scrubbed.body.as_deref())
+                        && self.matcher.matches(recorded, live)
+                })?;
+        used[index] = true;
+        Some(self.cassette.interactions[index].response.clone())
+    }
+}
+
+impl Transport for ReplayTransport {
+    fn send<'a>(&'a self, request: HttpRequest) -> TransportFuture<'a> {
+        Box::pin(async move {
+            self.find(&request).ok_or_else(|| {
+                NebulAuthError::Config(format!(
+                    "no recorded interaction matches {} {}",
+                    request.method,
+                    target(&request.url)
+                ))
+            })
+        })
+    }
+}
+
+/// Records to `path` through a fresh HTTP client when `NEBULAUTH_VCR=record`, otherwise
+/// replays `path`.
+pub fn from_env(path: impl Into<PathBuf>) -> Result<Arc<dyn Transport>, NebulAuthError> {
+    let path = path.into();
+    match std::env::var(VCR_MODE_ENV).as_deref() {
+        Ok("record") => {
+            let inner = transport::default_transport(Duration::from_secs(15))?;
+            Ok(Arc::new(RecordingTransport::new(inner, path)))
+        }
+        _ => Ok(Arc::new(ReplayTransport::load(&path)?)),
+    }
+}
@@ -0,0 +1,32 @@
+use std::future::Future;
+use std::pin::Pin;
+
+use crate::{
+    NebulAuthClient, NebulAuthError, NebulAuthResponse, RedeemKeyInput, ResetHwidInput,
+    VerifyKeyInput,
+};
+
+pub type VerifierFuture<'a> =
+    Pin<Box<dyn Future<Output = Result<NebulAuthResponse, NebulAuthError>> + Send + 'a>>;
+
+/// The license calls an application makes at runtime. Depend on this instead of
+/// [`NebulAuthClient`] to swap in a `MockLicenseVerifier` (`mock` feature) in tests.
+pub trait LicenseVerifier: Send + Sync {
+    fn verify_key<'a>(&'a self, input: VerifyKeyInput) -> VerifierFuture<'a>;
+    fn redeem_key<'a>(&'a self, input: RedeemKeyInput) -> VerifierFuture<'a>;
+    fn reset_hwid<'a>(&'a self, input: ResetHwidInput) -> VerifierFuture<'a>;
+}
+
+impl LicenseVerifier for NebulAuthClient {
+    fn verify_key<'a>(&'a self, input: VerifyKeyInput) -> VerifierFuture<'a> {
+        Box::pin(NebulAuthClient::verify_key(self, input))
+    }
+
+    fn redeem_key<'a>(&'a self, input: RedeemKeyInput) -> VerifierFuture<'a> {
+        Box::pin(NebulAuthClient::redeem_key(self, input))
+    }
+
+    fn reset_hwid<'a>(&'a self, input: ResetHwidInput) -> VerifierFuture<'a> {
+        Box::pin(NebulAuthClient::reset_hwid(self, input))
+    }
+}
@@ -0,0 +1,284 @@
+use futures_util::stream::{self, Stream};
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, VecDeque};
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+
+use crate::bulk::is_revoked;
+use crate::{
+    BlacklistEntry, DashboardKey, DashboardRequestOptions, KeySession, NebulAuthDashboardClient,
+    NebulAuthError,
+};
+
+const DEFAULT_POLL_INTERVAL: Duration = Duration::from_secs(30);
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(tag = "type", content = "resource", rename_all = "snake_case")]
+pub enum ChangeEvent {
+    KeyCreated(DashboardKey),
+    KeyUpdated(DashboardKey),
+    KeyRevoked(DashboardKey),
+    KeyDeleted(DashboardKey),
+    SessionStarted(KeySession),
+    SessionEnded(KeySession),
+    BlacklistAdded(BlacklistEntry),
+    BlacklistRemoved(BlacklistEntry),
+}
+
+/// Last seen state of each watched resource. Key strings are not stored.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct WatchCursor {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub keys: Option<BTreeMap<String, DashboardKey>>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub sessions: Option<BTreeMap<String, KeySession>>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub blacklist: Option<BTreeMap<String, BlacklistEntry>>,
+}
+
+impl WatchCursor {
+    pub fn load(path: impl AsRef<Path>) -> Result<Self, NebulAuthError> {
+        let contents = std::fs::read_to_string(path)?;
+        serde_json::from_str(&contents)
+            .map_err(|e| NebulAuthError::Decode(format!("invalid watch cursor: {e}")))
+    }
+
+    pub fn save(&self, path: impl AsRef<Path>) -> Result<(), NebulAuthError> {
+        let contents = serde_json::to_string(self).map_err(|e| {
+            NebulAuthError::Config(format!("failed to serialize watch cursor: {e}"))
+        })?;
+        // Cursors hold session IPs and HWIDs; keep them private like snapshots.
+        crate::dashboard::write_private_file(path.as_ref(), contents.as_bytes())
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct WatchOptions {
+    pub interval: Duration,
+    pub keys: bool,
+    pub sessions: bool,
+    pub blacklist: bool,
+    /// Starting state; without one the first poll only records a baseline.
+    pub cursor: Option<WatchCursor>,
+    /// Loaded on start when `cursor` is unset, and rewritten after every poll. A stream
+    /// rewrites it only once that poll's events have all been taken, so events a crash
+    /// interrupts are delivered again on restart.
+    pub cursor_file: Option<PathBuf>,
+}
+
+impl Default for WatchOptions {
+    fn default() -> Self {
+        Self {
+            interval: DEFAULT_POLL_INTERVAL,
+            keys: true,
+            sessions: true,
+            blacklist: true,
+            cursor: None,
+            cursor_file: None,
+        }
+    }
+}
+
+pub struct Watcher<'a> {
+    client: &'a NebulAuthDashboardClient,
+    watch_options: WatchOptions,
+    options: DashboardRequestOptions,
+    cursor: WatchCursor,
+}
+
+impl<'a> Watcher<'a> {
+    pub fn new(
+        client: &'a NebulAuthDashboardClient,
+        mut watch_options: WatchOptions,
+        options: DashboardRequestOptions,
+    ) -> Result<Self, NebulAuthError> {
+        let cursor = match (watch_options.cursor.take(), &watch_options.cursor_file) {
+            (Some(cursor), _) => cursor,
+            (None, Some(path)) if path.exists() => WatchCursor::load(path)?,
+            _ => WatchCursor::default(),
+        };
+        Ok(Self {
+            client,
+            watch_options,
+            options,
+            cursor,
+        })
+    }
+
+    pub fn cursor(&self) -> &WatchCursor {
+        &self.cursor
+    }
+
+    /// Fetches every watched resource once and returns what changed since the last poll.
+    pub async fn poll(&mut self) -> Result<Vec<ChangeEvent>, NebulAuthError> {
+        let (next, events) = self.diff().await?;
+        if let Some(path) = &self.watch_options.cursor_file {
+            next.save(path)?;
+        }
+        self.cursor = next;
+        Ok(events)
+    }
+
+    /// The cursor after one poll and the events leading to it; nothing is saved.
+    async fn diff(&self) -> Result<(WatchCursor, Vec<ChangeEvent>), NebulAuthError> {
+        let mut next = self.cursor.clone();
+        let mut events = Vec::new();
+
+        if self.watch_options.keys {
+            let keys = self
+                .client
+                .list_keys(self.options.clone())
+                .await?
+                .error_for_status()?
+                .items()?;
+            next.keys = Some(diff_keys(self.cursor.keys.as_ref(), keys, &mut events));
+        }
+        if self.watch_options.sessions {
+            let sessions = self
+                .client
+                .list_key_sessions(self.options.clone())
+                .await?
+                .error_for_status()?
+                .items()?;
+            next.sessions = Some(diff_sessions(
+                self.cursor.sessions.as_ref(),
+                sessions,
+                &mut events,
+            ));
+        }
+        if self.watch_options.blacklist {
+            let entries = self
+                .client
+                .list_blacklist(self.options.clone())
+                .await?
+                .error_for_status()?
+                .items()?;
+            next.blacklist = Some(diff_blacklist(
+                self.cursor.blacklist.as_ref(),
+                entries,
+                &mut events,
+            ));
+        }
+
+        Ok((next, events))
+    }
+
+    /// Polls forever, yielding events one at a time. A failed poll is yielded as an
+    /// error and retried after the next interval.
+    pub fn into_stream(self) -> impl Stream<Item = Result<ChangeEvent, NebulAuthError>> + 'a {
+        stream::unfold(
+            (self, VecDeque::new(), true, false),
+            |(mut watcher, mut pending, mut first, mut unsaved)| async move {
+                loop {
+                    if let Some(event) = pending.pop_front() {
+                        return Some((Ok(event), (watcher, pending, first, unsaved)));
+                    }
+                    // Every event of the last poll has been taken; only now may the cursor
+                    // move past them on disk.
+                    if unsaved {
+                        if let Some(path) = &watcher.watch_options.cursor_file {
+                            if let Err(e) = watcher.cursor.save(path) {
+                                return Some((Err(e), (watcher, pending, first, unsaved)));
+                            }
+                        }
+                        unsaved = false;
+                    }
+                    if !first {
+                        tokio::time::sleep(watcher.watch_options.interval).await;
+                    }
+                    first = false;
+                    match watcher.diff().await {
+                        Ok((next, events)) => {
+                            watcher.cursor = next;
+                            pending.extend(events);
+                            unsaved = true;
+                        }
+                        Err(e) => return Some((Err(e), (watcher, pending, first, unsaved))),
+                    }
+                }
+            },
+        )
+    }
+}
+
+fn diff_keys(
+    previous: Option<&BTreeMap<String, DashboardKey>>,
+    live: Vec<DashboardKey>,
+    events: &mut Vec<ChangeEvent>,
+) -> BTreeMap<String, DashboardKey> {
+    let mut seen = BTreeMap::new();
+    for key in live {
+        let stored = DashboardKey {
+            key: None,
+            ..key.clone()
+        };
+        match previous.map(|previous| previous.get(&key.id)) {
+            None => {}
+            Some(None) => events.push(ChangeEvent::KeyCreated(key)),
+            Some(Some(before)) if !is_revoked(before) && is_revoked(&stored) => {
+                events.push(ChangeEvent::KeyRevoked(key))
+            }
+            Some(Some(before)) if before != &stored => events.push(ChangeEvent::KeyUpdated(key)),
+            Some(Some(_)) => {}
+        }
+        seen.insert(stored.id.clone(), stored);
+    }
+    for (id, key) in previous.into_iter().flatten() {
+        if !seen.contains_key(id) {
+            events.push(ChangeEvent::KeyDeleted(key.clone()));
+        }
+    }
+    seen
+}
+
+fn diff_sessions(
+    previous: Option<&BTreeMap<String, KeySession>>,
+    live: Vec<KeySession>,
+    events: &mut Vec<ChangeEvent>,
+) -> BTreeMap<String, KeySession> {
+    let live: BTreeMap<String, KeySession> = live
+        .into_iter()
+        .map(|session| (session.id.clone(), session))
+        .collect();
+    let Some(previous) = previous else {
+        return live;
+    };
+
+    for (id, session) in &live {
+        if !previous.contains_key(id) {
+            events.push(ChangeEvent::SessionStarted(session.clone()));
+        }
+    }
+    for (id, session) in previous {
+        if !live.contains_key(id) {
+            events.push(ChangeEvent::SessionEnded(session.clone()));
+        }
+    }
+    live
+}
+
+fn diff_blacklist(
+    previous: Option<&BTreeMap<String, BlacklistEntry>>,
+    live: Vec<BlacklistEntry>,
+    events: &mut Vec<ChangeEvent>,
+) -> BTreeMap<String, BlacklistEntry> {
+    let live: BTreeMap<String, BlacklistEntry> = live
+        .into_iter()
+        .map(|entry| (entry.id.clone(), entry))
+        .collect();
+    let Some(previous) = previous else {
+        return live;
+    };
+
+    for (id, entry) in &live {
+        if !previous.contains_key(id) {
+            events.push(ChangeEvent::BlacklistAdded(entry.clone()));
+        }
+    }
+    for (id, entry) in previous {
+        if !live.contains_key(id) {
+            events.push(ChangeEvent::BlacklistRemoved(entry.clone()));
+        }
+    }
+    live
+}
@@ -0,0 +1,152 @@
+#![cfg(feature = "dashboard")]
+
+use mockito::{Matcher, Server};
+use nebulauth_sdk::alerts::{
+    Alert, AlertEngine, AlertRule, AlertSnapshot, Condition, DiscordWebhookNotifier, Metric,
+    Severity, WebhookNotifier,
+};
+use nebulauth_sdk::analytics::{AnalyticsRange, AnalyticsSummary, GeoEntry};
+use serde_json::json;
+use std::time::Duration;
+
+const MINUTE_MS: u128 = 60_000;
+
+fn snapshot(minute: u128, failures: i64, sessions: usize, geo: &[(&str, i64)]) -> AlertSnapshot {
+    AlertSnapshot {
+        summary: serde_json::from_value::<AnalyticsSummary>(json!({
+            "failedVerifications": failures
+        }))
+        .unwrap(),
+        countries: geo
+            .iter()
+            .map(|(country, count)| GeoEntry {
+                country: country.to_string(),
+                count: *count,
+            })
+            .collect(),
+        active_sessions: sessions,
+        taken_at_ms: minute * MINUTE_MS,
+    }
+}
+
+fn fired(alerts: &[Alert]) -> Vec<&str> {
+    alerts.iter().map(|alert| alert.rule.as_str()).collect()
+}
+
+#[test]
+fn rules_fire_once_while_active_and_respect_cooldown() {
+    let mut spike = AlertRule::new(
+        "failure spike",
+        Condition::IncreaseAbove {
+            metric: Metric::Summary("failedVerifications".to_string()),
+            percent: 100.0,
+        },
+        Severity::Warning,
+    );
+    spike.cooldown = Duration::from_secs(10 * 60);
+    let no_sessions = AlertRule::new(
+        "no sessions",
+        Condition::Below {
+            metric: Metric::ActiveSessions,
+            threshold: 1.0,
+        },
+        Severity::Critical,
+    );
+    let mut engine = AlertEngine::new(vec![spike, no_sessions], AnalyticsRange::days(1).unwrap());
+
+    assert!(engine.evaluate(snapshot(0, 10, 5, &[])).is_empty());
+
+    let alerts = engine.evaluate(snapshot(1, 30, 0, &[]));
+    assert_eq!(fired(&alerts), vec!["failure spike", "no sessions"]);
+    assert_eq!(
+        alerts[0].message,
+        "failedVerifications rose from 10 to 30 (+200.0%)"
+    );
+
+    // Still no sessions: deduplicated. Spike resolved, then fires again inside its cooldown.
+    assert!(engine.evaluate(snapshot(2, 30, 0, &[])).is_empty());
+    assert!(engine.evaluate(snapshot(3, 90, 0, &[])).is_empty());
+
+    // Sessions recovered and dropped again; the spike cooldown has passed.
+    assert!(engine.evaluate(snapshot(20, 90, 3, &[])).is_empty());
+    let alerts = engine.evaluate(snapshot(40, 400, 0, &[]));
+    assert_eq!(fired(&alerts), vec!["failure spike", "no sessions"]);
+}
+
+#[test]
+fn top_country_change_needs_a_dominant_newcomer() {
+    let mut engine = AlertEngine::new(
+        vec![AlertRule::new(
+            "geo shift",
+            Condition::TopCountryChanged { min_share: 0.6 },
+            Severity::Info,
+        )],
+        AnalyticsRange::days(1).unwrap(),
+    );
+
+    assert!(engine
+        .evaluate(snapshot(0, 0, 1, &[("US", 50), ("DE", 40)]))
+        .is_empty());
+    assert!(engine
+        .evaluate(snapshot(1, 0, 1, &[("US", 40), ("DE", 50)]))
+        .is_empty());
+    let alerts = engine.evaluate(snapshot(2, 0, 1, &[("DE", 10), ("VN", 90)]));
+    assert_eq!(
+        alerts[0].message,
+        "VN now leads with 90% of traffic (was DE)"
+    );
+}
+
+#[tokio::test]
+async fn notifiers_post_json_and_discord_embeds() {
+    let mut server = Server::new_async().await;
+
+    let webhook = server
+        .mock("POST", "/hook")
+        .match_body(Matcher::Json(json!({
+            "rule": "no sessions",
+            "severity": "critical",
+            "message": "active sessions is 0 (below 1)",
+            "fired_at_ms": 60000
+        })))
+        .with_status(204)
+        .create_async()
+        .await;
+    let discord = server
+        .mock("POST", "/discord")
+        .match_body(Matcher::Json(json!({
+            "username": "NebulAuth",
+            "embeds": [{
+                "title": "[critical] no sessions",
+                "description": "active sessions is 0 (below 1)",
+                "color": 0xe74c3c
+            }]
+        })))
+        .with_status(500)
+        .with_body("down")
+        .create_async()
+        .await;
+
+    let engine = AlertEngine::new(Vec::new(), AnalyticsRange::days(1).unwrap())
+        .with_notifier(WebhookNotifier::new(format!("{}/hook", server.url())).unwrap())
+        .with_notifier(
+            DiscordWebhookNotifier::new(format!("{}/discord", server.url()))
+                .unwrap()
+                .with_username("NebulAuth"),
+        );
+
+    let deliveries = engine
+        .notify(vec![Alert {
+            rule: "no sessions".to_string(),
+            severity: Severity::Critical,
+            message: "active sessions is 0 (below 1)".to_string(),
+            fired_at_ms: MINUTE_MS,
+        }])
+        .await;
+
+    assert_eq!(deliveries.len(), 1);
+    assert_eq!(deliveries[0].failures.len(), 1);
+    assert!(deliveries[0].failures[0].contains("down"));
+    webhook.assert_async().await;
+    discord.assert_async().await;
+}
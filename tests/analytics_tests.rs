@@ -0,0 +1,239 @@
+#![cfg(feature = "dashboard")]
+
+use chrono::{DateTime, Days, NaiveDate};
+use mockito::{Matcher, Server, ServerGuard};
+use nebulauth_sdk::analytics::{
+    self, ActivityPoint, AnalyticsRange, AnalyticsSummary, GeoEntry, MetricDelta,
+};
+use nebulauth_sdk::{
+    DashboardAuth, DashboardRequestOptions, NebulAuthDashboardClient,
+    NebulAuthDashboardClientOptions, NebulAuthError,
+};
+use serde_json::json;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+fn client_for(server: &ServerGuard) -> NebulAuthDashboardClient {
+    NebulAuthDashboardClient::new(NebulAuthDashboardClientOptions {
+        base_url: format!("{}/dashboard", server.url()),
+        auth: Some(DashboardAuth::Bearer {
+            bearer_token: "mk_at_test".to_string(),
+        }),
+        ..Default::default()
+    })
+    .expect("client init should succeed")
+}
+
+fn date(value: &str) -> NaiveDate {
+    value.parse().unwrap()
+}
+
+#[test]
+fn ranges_resolve_to_dates() {
+    let today = date("2026-03-10");
+
+    assert_eq!(
+        AnalyticsRange::days(7).unwrap().dates(today),
+        (date("2026-03-04"), date("2026-03-10"))
+    );
+    assert_eq!(
+        AnalyticsRange::days(1).unwrap().dates(today),
+        (today, today)
+    );
+    assert!(matches!(
+        AnalyticsRange::days(0),
+        Err(NebulAuthError::Config(_))
+    ));
+}
+
+#[test]
+fn earlier_counters_subtract_the_current_window() {
+    let current: AnalyticsSummary =
+        serde_json::from_str(r#"{"activeKeys":120,"verifications":30,"redemptions":4}"#).unwrap();
+    let both: AnalyticsSummary =
+        serde_json::from_str(r#"{"activeKeys":120,"verifications":50,"redemptions":4}"#).unwrap();
+
+    let previous = current.earlier_counters(&both);
+    assert_eq!(previous.verifications, Some(20));
+    assert_eq!(previous.redemptions, Some(0));
+    assert_eq!(previous.active_keys, None);
+}
+
+#[test]
+fn summary_compare_includes_unmodelled_metrics() {
+    let current: AnalyticsSummary = serde_json::from_str(
+        r#"{"activeKeys":120,"verifications":300,"failedVerifications":6,"label":"x"}"#,
+    )
+    .unwrap();
+    let previous: AnalyticsSummary =
+        serde_json::from_str(r#"{"active_keys":100,"verifications":0,"failedVerifications":8}"#)
+            .unwrap();
+
+    let deltas = current.compare(&previous);
+    assert_eq!(deltas["active_keys"], MetricDelta::new(120.0, 100.0));
+    assert_eq!(deltas["active_keys"].percent, Some(20.0));
+    assert_eq!(deltas["verifications"].percent, None);
+    assert_eq!(deltas["failedVerifications"].change, -2.0);
+    assert!(!deltas.contains_key("label"));
+}
+
+#[test]
+fn top_countries_and_daily_rollup() {
+    let entries = vec![
+        GeoEntry {
+            country: "us".to_string(),
+            count: 5,
+        },
+        GeoEntry {
+            country: "DE".to_string(),
+            count: 7,
+        },
+        GeoEntry {
+            country: "US".to_string(),
+            count: 4,
+        },
+        GeoEntry {
+            country: "FR".to_string(),
+            count: 7,
+        },
+    ];
+    let top: Vec<(String, i64)> = analytics::top_countries(&entries, 2)
+        .into_iter()
+        .map(|entry| (entry.country, entry.count))
+        .collect();
+    assert_eq!(top, vec![("US".to_string(), 9), ("DE".to_string(), 7)]);
+
+    let points: Vec<ActivityPoint> = serde_json::from_str(
+        r#"[
+            {"timestamp":"2026-03-01T10:00:00Z","verifications":3},
+            {"timestamp":"2026-03-01T23:30:00-02:00","verifications":1,"sessions":2},
+            {"date":"2026-03-01","redemptions":4},
+            {"timestamp":"soon","verifications":100}
+        ]"#,
+    )
+    .unwrap();
+    let days = analytics::daily_rollup(&points);
+    assert_eq!(days.len(), 2);
+    assert_eq!(days[0].date, date("2026-03-01"));
+    assert_eq!((days[0].verifications, days[0].redemptions), (3, 4));
+    assert_eq!((days[1].date, days[1].sessions), (date("2026-03-02"), 2));
+}
+
+#[tokio::test]
+async fn typed_requests_send_days() {
+    let mut server = Server::new_async().await;
+
+    let summary = server
+        .mock("GET", "/dashboard/analytics/summary")
+        .match_query(Matcher::UrlEncoded("days".to_string(), "7".to_string()))
+        .with_status(200)
+        .with_body(r#"{"summary":{"totalKeys":42}}"#)
+        .create_async()
+        .await;
+    let geo = server
+        .mock("GET", "/dashboard/analytics/geo")
+        .match_query(Matcher::UrlEncoded("days".to_string(), "30".to_string()))
+        .with_status(200)
+        .with_body(r#"{"countries":{"US":10,"DE":3}}"#)
+        .create_async()
+        .await;
+
+    let client = client_for(&server);
+    let result = client
+        .analytics_summary_for(
+            AnalyticsRange::days(7).unwrap(),
+            DashboardRequestOptions::default(),
+        )
+        .await
+        .expect("summary should parse");
+    assert_eq!(result.total_keys, Some(42));
+
+    let countries = client
+        .analytics_geo_for(
+            AnalyticsRange::days(30).unwrap(),
+            DashboardRequestOptions::default(),
+        )
+        .await
+        .expect("geo should parse");
+    assert_eq!(
+        analytics::top_countries(&countries, 1),
+        vec![GeoEntry {
+            country: "US".to_string(),
+            count: 10
+        }]
+    );
+
+    summary.assert_async().await;
+    geo.assert_async().await;
+}
+
+#[tokio::test]
+async fn compare_derives_the_previous_period_from_a_doubled_days_window() {
+    let mut server = Server::new_async().await;
+
+    let current = server
+        .mock("GET", "/dashboard/analytics/summary")
+        .match_query(Matcher::UrlEncoded("days".to_string(), "7".to_string()))
+        .with_status(200)
+        .with_body(r#"{"summary":{"activeKeys":120,"verifications":30}}"#)
+        .create_async()
+        .await;
+    let both = server
+        .mock("GET", "/dashboard/analytics/summary")
+        .match_query(Matcher::UrlEncoded("days".to_string(), "14".to_string()))
+        .with_status(200)
+        .with_body(r#"{"summary":{"activeKeys":120,"verifications":50}}"#)
+        .create_async()
+        .await;
+
+    let comparison = client_for(&server)
+        .compare_analytics_periods(
+            AnalyticsRange::days(7).unwrap(),
+            DashboardRequestOptions::default(),
+        )
+        .await
+        .expect("comparison should succeed");
+
+    assert_eq!(comparison.previous.verifications, Some(20));
+    assert_eq!(
+        comparison.deltas["verifications"],
+        MetricDelta::new(30.0, 20.0)
+    );
+    assert!(!comparison.deltas.contains_key("active_keys"));
+    current.assert_async().await;
+    both.assert_async().await;
+}
+
+#[tokio::test]
+async fn activity_range_is_applied_client_side() {
+    let mut server = Server::new_async().await;
+    let now = SystemTime::now().duration_since(UNIX_EPOCH).unwrap();
+    let today = DateTime::from_timestamp(now.as_secs() as i64, 0)
+        .unwrap()
+        .date_naive();
+
+    let activity = server
+        .mock("GET", "/dashboard/analytics/activity")
+        .match_query(Matcher::Missing)
+        .with_status(200)
+        .with_body(
+            json!([
+                { "date": today.to_string(), "verifications": 1 },
+                { "date": (today - Days::new(60)).to_string(), "verifications": 2 },
+                { "date": "not a date", "verifications": 3 },
+            ])
+            .to_string(),
+        )
+        .create_async()
+        .await;
+
+    let points = client_for(&server)
+        .analytics_activity_for(
+            Some(AnalyticsRange::days(30).unwrap()),
+            DashboardRequestOptions::default(),
+        )
+        .await
+        .expect("activity should parse");
+    assert_eq!(points.len(), 1);
+    assert_eq!(points[0].verifications, Some(1));
+    activity.assert_async().await;
+}
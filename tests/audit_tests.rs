@@ -0,0 +1,375 @@
+#![cfg(all(feature = "runtime", feature = "dashboard"))]
+
+use mockito::{Matcher, Server};
+use nebulauth_sdk::audit::{self, AuditLog, AuditRecord, GENESIS_HASH};
+use nebulauth_sdk::batch::{ExportFormat, KeyWriter};
+use nebulauth_sdk::{
+    BatchFormat, DashboardAuth, DashboardRequestOptions, KeyBatchCreateRequest, KeyCreateRequest,
+    KeyRevokeRequest, NebulAuthClient, NebulAuthClientOptions, NebulAuthDashboardClient,
+    NebulAuthDashboardClientOptions, RedeemKeyInput, ResetHwidInput,
+};
+use serde_json::json;
+use std::path::PathBuf;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Arc;
+
+fn log_path(server: &Server, name: &str) -> PathBuf {
+    let path = std::env::temp_dir().join(format!(
+        "nebulauth-audit-{name}-{}-{}.jsonl",
+        std::process::id(),
+        server.socket_address().port()
+    ));
+    let _ = std::fs::remove_file(&path);
+    path
+}
+
+fn read_records(path: &PathBuf) -> Vec<AuditRecord> {
+    std::fs::read_to_string(path)
+        .unwrap()
+        .lines()
+        .map(|line| serde_json::from_str(line).unwrap())
+        .collect()
+}
+
+#[tokio::test]
+async fn dashboard_mutations_are_chained_and_reads_are_skipped() {
+    let mut server = Server::new_async().await;
+    let _create = server
+        .mock("POST", "/dashboard/keys")
+        .with_status(201)
+        .with_body(r#"{"id":"key_1","key":"mk_live_created"}"#)
+        .create_async()
+        .await;
+    let _list = server
+        .mock("GET", "/dashboard/keys")
+        .with_status(200)
+        .with_body("[]")
+        .create_async()
+        .await;
+    let _revoke = server
+        .mock("DELETE", "/dashboard/keys/key_1")
+        .with_status(200)
+        .with_body("{}")
+        .create_async()
+        .await;
+
+    let path = log_path(&server, "dashboard");
+    let client = NebulAuthDashboardClient::new(NebulAuthDashboardClientOptions {
+        base_url: format!("{}/dashboard", server.url()),
+        auth: Some(DashboardAuth::Bearer {
+            bearer_token: "mk_at_test".to_string(),
+        }),
+        ..Default::default()
+    })
+    .unwrap()
+    .with_audit_log(Arc::new(
+        AuditLog::open(&path, "alice@example.com").unwrap(),
+    ));
+
+    let options = DashboardRequestOptions::default();
+    client
+        .create_key(
+            KeyCreateRequest {
+                label: Some("vip".to_string()),
+                ..Default::default()
+            },
+            options.clone(),
+        )
+        .await
+        .unwrap();
+    client.list_keys(options.clone()).await.unwrap();
+    client
+        .delete_key(
+            "key_1",
+            KeyRevokeRequest {
+                reason: Some("chargeback".to_string()),
+            },
+            options,
+        )
+        .await
+        .unwrap();
+
+    let records = read_records(&path);
+    assert_eq!(records.len(), 2);
+    assert_eq!(records[0].seq, 1);
+    assert_eq!(records[0].prev_hash, GENESIS_HASH);
+    assert_eq!(records[0].actor, "alice@example.com");
+    assert_eq!(records[0].method, "POST");
+    assert_eq!(records[0].status, Some(201));
+    assert_eq!(records[0].body, json!({ "label": "vip" }));
+    assert_eq!(records[1].path, "/keys/key_1");
+    assert_eq!(records[1].body, json!({ "reason": "chargeback" }));
+    assert_eq!(records[1].prev_hash, records[0].hash);
+
+    let verification = audit::verify(&path).unwrap();
+    assert!(verification.is_intact(), "{verification}");
+    assert_eq!(verification.records, 2);
+    assert_eq!(verification.last_hash, records[1].hash);
+
+    let _ = std::fs::remove_file(&path);
+}
+
+#[tokio::test]
+async fn runtime_redeem_and_reset_are_recorded_without_plain_keys() {
+    let mut server = Server::new_async().await;
+    let _redeem = server
+        .mock("POST", "/api/v1/keys/redeem")
+        .with_status(200)
+        .with_body("{}")
+        .create_async()
+        .await;
+    let _reset = server
+        .mock("POST", "/api/v1/keys/reset-hwid")
+        .with_status(429)
+        .with_body(r#"{"error":"slow down"}"#)
+        .create_async()
+        .await;
+
+    let path = log_path(&server, "runtime");
+    let client = NebulAuthClient::new(NebulAuthClientOptions {
+        base_url: format!("{}/api/v1", server.url()),
+        bearer_token: Some("mk_at_test".to_string()),
+        signing_secret: Some("mk_sig_test".to_string()),
+        service_slug: Some("my-app".to_string()),
+        ..Default::default()
+    })
+    .unwrap()
+    .with_audit_log(Arc::new(AuditLog::open(&path, "support-bot").unwrap()));
+
+    client
+        .redeem_key(RedeemKeyInput {
+            key: "mk_live_plaintext".to_string(),
+            discord_id: "42".to_string(),
+            ..Default::default()
+        })
+        .await
+        .unwrap();
+    client
+        .reset_hwid(ResetHwidInput {
+            key: Some("mk_live_plaintext".to_string()),
+            ..Default::default()
+        })
+        .await
+        .unwrap();
+
+    let contents = std::fs::read_to_string(&path).unwrap();
+    assert!(!contents.contains("mk_live_plaintext"));
+    assert!(!contents.contains("mk_at_test"));
+
+    let records = read_records(&path);
+    assert_eq!(records.len(), 2);
+    assert_eq!(records[0].client, "runtime");
+    assert_eq!(records[0].path, "/keys/redeem");
+    assert_eq!(records[0].body["discordId"], "42");
+    assert_eq!(records[1].status, Some(429));
+    // Same key, same fingerprint.
+    assert_eq!(records[0].body["key"], records[1].body["key"]);
+    assert!(records[0].body["key"]
+        .as_str()
+        .unwrap()
+        .starts_with("sha256:"));
+
+    let _ = std::fs::remove_file(&path);
+}
+
+#[test]
+fn verify_detects_edits_and_removed_records_and_reopen_continues_chain() {
+    let path = std::env::temp_dir().join(format!(
+        "nebulauth-audit-tamper-{}.jsonl",
+        std::process::id()
+    ));
+    let _ = std::fs::remove_file(&path);
+
+    let body = json!({ "email": "bob@example.com", "password": "hunter2" });
+    {
+        let log = AuditLog::open(&path, "alice").unwrap();
+        for _ in 0..2 {
+            log.record("dashboard", "POST", "/users", Some(&body), Ok(201))
+                .unwrap();
+        }
+    }
+    let log = AuditLog::open(&path, "alice").unwrap();
+    let third = log
+        .record("dashboard", "DELETE", "/users/u_1", None, Ok(200))
+        .unwrap();
+    assert_eq!(third.seq, 3);
+
+    let original = std::fs::read_to_string(&path).unwrap();
+    assert!(!original.contains("hunter2"));
+    assert!(audit::verify(&path).unwrap().is_intact());
+
+    std::fs::write(
+        &path,
+        original.replacen("\"status\":201", "\"status\":500", 1),
+    )
+    .unwrap();
+    let verification = audit::verify(&path).unwrap();
+    assert_eq!(verification.records, 0);
+    assert_eq!(verification.problem.unwrap().line, 1);
+
+    let lines: Vec<&str> = original.lines().collect();
+    std::fs::write(&path, format!("{}\n{}\n", lines[0], lines[2])).unwrap();
+    let verification = audit::verify(&path).unwrap();
+    assert_eq!(verification.records, 1);
+    let problem = verification.problem.unwrap();
+    assert_eq!(problem.line, 2);
+    assert!(problem.reason.contains("sequence"), "{}", problem.reason);
+
+    let _ = std::fs::remove_file(&path);
+}
+
+/// Rewrites the first record's status and recomputes every unkeyed hash after it, as anyone
+/// with write access to the file could.
+fn rewrite_chain(path: &PathBuf) {
+    let mut prev_hash = GENESIS_HASH.to_string();
+    let mut lines = Vec::new();
+    for (index, mut record) in read_records(path).into_iter().enumerate() {
+        if index == 0 {
+            record.status = Some(500);
+        }
+        record.prev_hash = prev_hash;
+        record.hash = record.compute_hash().unwrap();
+        prev_hash = record.hash.clone();
+        lines.push(serde_json::to_string(&record).unwrap());
+    }
+    std::fs::write(path, lines.join("\n") + "\n").unwrap();
+}
+
+#[test]
+fn recomputed_chains_are_caught_by_a_key_or_an_expected_head() {
+    let path = std::env::temp_dir().join(format!(
+        "nebulauth-audit-rewrite-{}.jsonl",
+        std::process::id()
+    ));
+    let _ = std::fs::remove_file(&path);
+    let keyed = audit::VerifyOptions {
+        chain_key: Some(b"audit-key".to_vec()),
+        expected_head: None,
+    };
+
+    let log = AuditLog::open(&path, "alice")
+        .unwrap()
+        .with_chain_key("audit-key");
+    for status in [201, 200] {
+        log.record("dashboard", "POST", "/keys", None, Ok(status))
+            .unwrap();
+    }
+    assert!(audit::verify_with(&path, &keyed).unwrap().is_intact());
+    assert!(!audit::verify(&path).unwrap().is_intact());
+
+    rewrite_chain(&path);
+    let verification = audit::verify_with(&path, &keyed).unwrap();
+    assert_eq!(verification.problem.unwrap().line, 1);
+
+    std::fs::remove_file(&path).unwrap();
+    let log = AuditLog::open(&path, "alice").unwrap();
+    for status in [201, 200] {
+        log.record("dashboard", "POST", "/keys", None, Ok(status))
+            .unwrap();
+    }
+    let head = audit::verify(&path).unwrap().last_hash;
+    let pinned = audit::VerifyOptions {
+        chain_key: None,
+        expected_head: Some(head),
+    };
+    assert!(audit::verify_with(&path, &pinned).unwrap().is_intact());
+
+    rewrite_chain(&path);
+    assert!(audit::verify(&path).unwrap().is_intact());
+    let verification = audit::verify_with(&path, &pinned).unwrap();
+    assert_eq!(verification.records, 2);
+    assert!(verification
+        .problem
+        .unwrap()
+        .reason
+        .contains("expected head"));
+
+    let _ = std::fs::remove_file(&path);
+}
+
+#[tokio::test]
+async fn failed_audit_writes_are_reported_without_failing_requests() {
+    let mut server = Server::new_async().await;
+    let _create = server
+        .mock("POST", "/dashboard/keys")
+        .with_status(201)
+        .with_body(r#"{"id":"key_1"}"#)
+        .create_async()
+        .await;
+
+    let path = log_path(&server, "missing-dir").join("audit.jsonl");
+    let failures = Arc::new(AtomicUsize::new(0));
+    let counter = failures.clone();
+    let client = NebulAuthDashboardClient::new(NebulAuthDashboardClientOptions {
+        base_url: format!("{}/dashboard", server.url()),
+        auth: Some(DashboardAuth::Bearer {
+            bearer_token: "mk_at_test".to_string(),
+        }),
+        ..Default::default()
+    })
+    .unwrap()
+    .with_audit_log(Arc::new(
+        AuditLog::open(&path, "alice")
+            .unwrap()
+            .with_error_handler(move |_| {
+                counter.fetch_add(1, Ordering::SeqCst);
+            }),
+    ));
+
+    let response = client
+        .create_key(
+            KeyCreateRequest::default(),
+            DashboardRequestOptions::default(),
+        )
+        .await
+        .expect("the request succeeded, so the call should too");
+
+    assert_eq!(response.status_code, 201);
+    assert_eq!(failures.load(Ordering::SeqCst), 1);
+}
+
+#[tokio::test]
+async fn streamed_key_batches_are_recorded() {
+    let mut server = Server::new_async().await;
+    let _batch = server
+        .mock("POST", "/dashboard/keys/batch")
+        .match_query(Matcher::Any)
+        .with_status(200)
+        .with_body("mk_live_a\nmk_live_b\n")
+        .create_async()
+        .await;
+
+    let path = log_path(&server, "batch");
+    let client = NebulAuthDashboardClient::new(NebulAuthDashboardClientOptions {
+        base_url: format!("{}/dashboard", server.url()),
+        auth: Some(DashboardAuth::Bearer {
+            bearer_token: "mk_at_test".to_string(),
+        }),
+        ..Default::default()
+    })
+    .unwrap()
+    .with_audit_log(Arc::new(AuditLog::open(&path, "alice").unwrap()));
+
+    let mut writer = KeyWriter::new(Vec::new(), ExportFormat::Ndjson);
+    client
+        .stream_generated_keys(
+            KeyBatchCreateRequest {
+                count: 2,
+                key_only: Some(true),
+                ..Default::default()
+            },
+            BatchFormat::Txt,
+            &mut writer,
+            DashboardRequestOptions::default(),
+        )
+        .await
+        .unwrap();
+
+    let records = read_records(&path);
+    assert_eq!(records.len(), 1);
+    assert_eq!(records[0].path, "/keys/batch");
+    assert_eq!(records[0].status, Some(200));
+    assert_eq!(records[0].body, json!({ "count": 2, "key_only": true }));
+
+    let _ = std::fs::remove_file(&path);
+}
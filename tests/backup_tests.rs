@@ -0,0 +1,324 @@
+#![cfg(feature = "dashboard")]
+
+use chrono::DateTime;
+use mockito::{Matcher, Mock, Server, ServerGuard};
+use nebulauth_sdk::backup::{self, ResourceKind, RestoreAction, RestoreOptions, Snapshot};
+use nebulauth_sdk::{
+    DashboardAuth, DashboardRequestOptions, NebulAuthDashboardClient,
+    NebulAuthDashboardClientOptions,
+};
+use serde_json::json;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+fn client_for(server: &ServerGuard) -> NebulAuthDashboardClient {
+    NebulAuthDashboardClient::new(NebulAuthDashboardClientOptions {
+        base_url: format!("{}/dashboard", server.url()),
+        auth: Some(DashboardAuth::Bearer {
+            bearer_token: "mk_at_test".to_string(),
+        }),
+        ..Default::default()
+    })
+    .expect("client init should succeed")
+}
+
+async fn mock_get(server: &mut ServerGuard, path: &str, body: &str) -> Mock {
+    server
+        .mock("GET", format!("/dashboard{path}").as_str())
+        .with_status(200)
+        .with_body(body)
+        .create_async()
+        .await
+}
+
+#[tokio::test]
+async fn backup_collects_every_resource() {
+    let mut server = Server::new_async().await;
+
+    let mocks = vec![
+        mock_get(
+            &mut server,
+            "/customer",
+            r#"{"customer":{"id":"c-1","paused":false}}"#,
+        )
+        .await,
+        mock_get(
+            &mut server,
+            "/users",
+            r#"[{"id":"u-1","email":"a@example.com"}]"#,
+        )
+        .await,
+        mock_get(
+            &mut server,
+            "/keys",
+            r#"{"keys":[{"id":"k-1"},{"id":"k-2"}]}"#,
+        )
+        .await,
+        mock_get(&mut server, "/checkpoints", "[]").await,
+        mock_get(
+            &mut server,
+            "/blacklist",
+            r#"[{"id":"bl-1","type":"ip","value":"1.1.1.1"}]"#,
+        )
+        .await,
+        mock_get(&mut server, "/api-tokens", "[]").await,
+    ];
+
+    let snapshot = backup::backup(&client_for(&server), DashboardRequestOptions::default())
+        .await
+        .expect("backup should succeed");
+
+    assert_eq!(snapshot.version, backup::SNAPSHOT_VERSION);
+    assert_eq!(snapshot.customer["id"], "c-1");
+    assert_eq!(snapshot.users.len(), 1);
+    assert_eq!(snapshot.keys.len(), 2);
+    assert_eq!(snapshot.blacklist[0]["value"], "1.1.1.1");
+    for mock in mocks {
+        mock.assert_async().await;
+    }
+}
+
+fn snapshot() -> Snapshot {
+    Snapshot {
+        version: backup::SNAPSHOT_VERSION,
+        created_at_ms: 0,
+        customer: json!({ "require_hwid": true }),
+        users: vec![json!({ "id": "u-1", "email": "support@example.com", "role": "admin" })],
+        keys: vec![
+            json!({ "id": "k-1", "label": "Existing" }),
+            json!({ "id": "k-2", "label": "Lost", "duration_hours": 48, "metadata": { "tier": "pro" } }),
+        ],
+        checkpoints: Vec::new(),
+        blacklist: vec![json!({ "id": "bl-1", "type": "hwid", "value": "HWID-1" })],
+        api_tokens: Vec::new(),
+    }
+}
+
+#[tokio::test]
+async fn restore_dry_run_reports_without_writing() {
+    let mut server = Server::new_async().await;
+
+    let _customer = mock_get(&mut server, "/customer", r#"{"require_hwid":false}"#).await;
+    let _checkpoints = mock_get(&mut server, "/checkpoints", "[]").await;
+    let _blacklist = mock_get(&mut server, "/blacklist", "[]").await;
+    let _keys = mock_get(&mut server, "/keys", r#"[{"id":"k-1"}]"#).await;
+    let _users = mock_get(&mut server, "/users", "[]").await;
+    let _tokens = mock_get(&mut server, "/api-tokens", "[]").await;
+    let writes = server
+        .mock("POST", Matcher::Any)
+        .expect(0)
+        .create_async()
+        .await;
+
+    let report = backup::restore(
+        &client_for(&server),
+        &snapshot(),
+        RestoreOptions {
+            dry_run: true,
+            user_password: None,
+        },
+        DashboardRequestOptions::default(),
+    )
+    .await
+    .expect("restore should succeed");
+
+    let summary: Vec<String> = report.items.iter().map(ToString::to_string).collect();
+    assert_eq!(
+        summary,
+        vec![
+            "customer customer: would update",
+            "blacklist entry bl-1: would create",
+            "key k-1: exists",
+            "key k-2: would create",
+            "user u-1: skipped (no user password provided)",
+        ]
+    );
+    writes.assert_async().await;
+}
+
+#[tokio::test]
+async fn restore_recreates_missing_keys_and_maps_ids() {
+    let mut server = Server::new_async().await;
+
+    let _customer = mock_get(&mut server, "/customer", r#"{"require_hwid":true}"#).await;
+    let _checkpoints = mock_get(&mut server, "/checkpoints", "[]").await;
+    let _blacklist = mock_get(
+        &mut server,
+        "/blacklist",
+        r#"[{"id":"bl-9","type":"hwid","value":"HWID-1"}]"#,
+    )
+    .await;
+    let _keys = mock_get(
+        &mut server,
+        "/keys",
+        r#"[{"id":"k-7","metadata":{"restored_from":"k-1"}}]"#,
+    )
+    .await;
+    let _users = mock_get(
+        &mut server,
+        "/users",
+        r#"[{"id":"u-5","email":"Support@example.com"}]"#,
+    )
+    .await;
+    let _tokens = mock_get(&mut server, "/api-tokens", "[]").await;
+
+    let create_key = server
+        .mock("POST", "/dashboard/keys")
+        .match_body(Matcher::Json(json!({
+            "label": "Lost",
+            "duration_hours": 48,
+            "metadata": { "tier": "pro", "restored_from": "k-2" }
+        })))
+        .with_status(201)
+        .with_body(r#"{"key":{"id":"k-8"}}"#)
+        .create_async()
+        .await;
+
+    let report = backup::restore(
+        &client_for(&server),
+        &snapshot(),
+        RestoreOptions::default(),
+        DashboardRequestOptions::default(),
+    )
+    .await
+    .expect("restore should succeed");
+
+    assert_eq!(report.id_map.get("k-1").map(String::as_str), Some("k-7"));
+    assert_eq!(report.id_map.get("k-2").map(String::as_str), Some("k-8"));
+    assert_eq!(report.id_map.get("bl-1").map(String::as_str), Some("bl-9"));
+    assert_eq!(report.id_map.get("u-1").map(String::as_str), Some("u-5"));
+    assert!(report
+        .items
+        .iter()
+        .any(|item| item.kind == ResourceKind::Key && item.action == RestoreAction::Created));
+    assert_eq!(report.failed().count(), 0);
+    create_key.assert_async().await;
+}
+
+/// RFC 3339 time `hours` from now.
+fn hours_from_now(hours: f64) -> String {
+    let now = SystemTime::now().duration_since(UNIX_EPOCH).unwrap();
+    DateTime::from_timestamp(now.as_secs() as i64 + (hours * 3600.0) as i64, 0)
+        .unwrap()
+        .to_rfc3339()
+}
+
+#[tokio::test]
+async fn restored_keys_get_only_their_remaining_time() {
+    let mut server = Server::new_async().await;
+
+    let _customer = mock_get(&mut server, "/customer", r#"{"require_hwid":true}"#).await;
+    let _checkpoints = mock_get(&mut server, "/checkpoints", "[]").await;
+    let _blacklist = mock_get(&mut server, "/blacklist", "[]").await;
+    let _keys = mock_get(&mut server, "/keys", "[]").await;
+    let _users = mock_get(&mut server, "/users", "[]").await;
+    let _tokens = mock_get(&mut server, "/api-tokens", "[]").await;
+
+    let create_key = server
+        .mock("POST", "/dashboard/keys")
+        .match_body(Matcher::PartialJson(json!({
+            "label": "Running",
+            "duration_hours": 48,
+        })))
+        .with_status(201)
+        .with_body(r#"{"id":"k-30"}"#)
+        .expect(1)
+        .create_async()
+        .await;
+
+    let mut saved = snapshot();
+    saved.users.clear();
+    saved.blacklist.clear();
+    saved.keys = vec![
+        json!({ "id": "k-3", "label": "Running", "duration_hours": 720, "expires_at": hours_from_now(47.5) }),
+        json!({ "id": "k-4", "label": "Over", "duration_hours": 720, "expiresAt": hours_from_now(-1.0) }),
+    ];
+
+    let report = backup::restore(
+        &client_for(&server),
+        &saved,
+        RestoreOptions::default(),
+        DashboardRequestOptions::default(),
+    )
+    .await
+    .expect("restore should succeed");
+
+    assert_eq!(report.id_map.get("k-3").map(String::as_str), Some("k-30"));
+    assert!(report.items.iter().any(|item| item.source_id == "k-4"
+        && item.action == RestoreAction::Skipped("expired".to_string())));
+    create_key.assert_async().await;
+}
+
+#[tokio::test]
+async fn restore_keeps_new_api_token_secrets_and_matches_tokens_by_configuration() {
+    let mut server = Server::new_async().await;
+
+    let _customer = mock_get(&mut server, "/customer", r#"{"require_hwid":true}"#).await;
+    let _checkpoints = mock_get(&mut server, "/checkpoints", "[]").await;
+    let _blacklist = mock_get(&mut server, "/blacklist", "[]").await;
+    let _keys = mock_get(&mut server, "/keys", "[]").await;
+    let _users = mock_get(&mut server, "/users", "[]").await;
+    let _tokens = mock_get(
+        &mut server,
+        "/api-tokens",
+        r#"[{"id":"t-9","scopes":["keys:redeem","keys:verify"],"replay_protection":"strict","auth_mode":"bearer"}]"#,
+    )
+    .await;
+
+    let create_token = server
+        .mock("POST", "/dashboard/api-tokens")
+        .match_body(Matcher::Json(json!({
+            "scopes": ["keys:redeem"],
+            "replay_protection": "strict",
+            "auth_mode": "bearer"
+        })))
+        .with_status(201)
+        .with_body(r#"{"id":"t-10","token":"mk_at_new_secret"}"#)
+        .expect(1)
+        .create_async()
+        .await;
+
+    let mut saved = snapshot();
+    saved.users.clear();
+    saved.keys.clear();
+    saved.blacklist.clear();
+    saved.api_tokens = vec![
+        json!({
+            "id": "t-1",
+            "scopes": ["keys:verify", "keys:redeem"],
+            "replay_protection": "strict",
+            "auth_mode": "bearer"
+        }),
+        json!({ "id": "t-2", "scopes": ["keys:redeem"] }),
+    ];
+
+    let report = backup::restore(
+        &client_for(&server),
+        &saved,
+        RestoreOptions::default(),
+        DashboardRequestOptions::default(),
+    )
+    .await
+    .expect("restore should succeed");
+
+    assert_eq!(report.id_map.get("t-1").map(String::as_str), Some("t-9"));
+    assert_eq!(report.id_map.get("t-2").map(String::as_str), Some("t-10"));
+    assert_eq!(report.api_tokens.len(), 1);
+    assert_eq!(report.api_tokens[0].source_id, "t-2");
+    assert_eq!(report.api_tokens[0].data["token"], "mk_at_new_secret");
+    create_token.assert_async().await;
+}
+
+#[cfg(unix)]
+#[test]
+fn snapshot_file_is_private_even_when_overwritten() {
+    use std::os::unix::fs::PermissionsExt;
+
+    let path = std::env::temp_dir().join(format!("nebulauth-snapshot-{}.json", std::process::id()));
+    std::fs::write(&path, "{}").unwrap();
+    std::fs::set_permissions(&path, std::fs::Permissions::from_mode(0o644)).unwrap();
+    snapshot().save(&path).expect("save should succeed");
+
+    let mode = std::fs::metadata(&path).unwrap().permissions().mode();
+    assert_eq!(mode & 0o777, 0o600);
+    let _ = std::fs::remove_file(&path);
+}
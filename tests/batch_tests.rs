@@ -0,0 +1,527 @@
+#![cfg(feature = "dashboard")]
+
+use futures_util::stream::{self, StreamExt};
+use mockito::{Matcher, Mock, Server, ServerGuard};
+use nebulauth_sdk::batch::{
+    ChunkOutcome, ChunkedBatchOptions, ExportFormat, GeneratedKey, KeyWriter,
+};
+use nebulauth_sdk::transport::{
+    HttpRequest, HttpResponse, StreamingFuture, StreamingResponse, Transport, TransportFuture,
+};
+use nebulauth_sdk::{
+    BatchFormat, DashboardAuth, DashboardRequestOptions, KeyBatchCreateRequest,
+    NebulAuthDashboardClient, NebulAuthDashboardClientOptions, NebulAuthError,
+};
+use serde_json::json;
+use std::io::Write;
+use std::sync::{Arc, Mutex};
+
+fn client_for(server: &ServerGuard) -> NebulAuthDashboardClient {
+    NebulAuthDashboardClient::new(NebulAuthDashboardClientOptions {
+        base_url: format!("{}/dashboard", server.url()),
+        auth: Some(DashboardAuth::Bearer {
+            bearer_token: "mk_at_test".to_string(),
+        }),
+        ..Default::default()
+    })
+    .expect("client init should succeed")
+}
+
+fn batch(count: i64) -> KeyBatchCreateRequest {
+    KeyBatchCreateRequest {
+        count,
+        label_prefix: Some("Promo".to_string()),
+        duration_hours: Some(24),
+        key_only: None,
+        metadata: None,
+    }
+}
+
+#[tokio::test]
+async fn generate_keys_parses_csv_output() {
+    let mut server = Server::new_async().await;
+
+    let mock = server
+        .mock("POST", "/dashboard/keys/batch")
+        .match_query(Matcher::UrlEncoded("format".to_string(), "csv".to_string()))
+        .with_status(200)
+        .with_header("content-type", "text/csv")
+        .with_body("id,key,label\r\nk-1,mk_live_a,Promo 1\r\nk-2,mk_live_b,\"Promo, 2\"\r\n")
+        .create_async()
+        .await;
+
+    let keys = client_for(&server)
+        .generate_keys(
+            batch(2),
+            BatchFormat::Csv,
+            DashboardRequestOptions::default(),
+        )
+        .await
+        .expect("request should succeed");
+
+    assert_eq!(keys.len(), 2);
+    assert_eq!(keys[0].id.as_deref(), Some("k-1"));
+    assert_eq!(keys[0].key, "mk_live_a");
+    assert_eq!(keys[1].label.as_deref(), Some("Promo, 2"));
+    mock.assert_async().await;
+}
+
+#[tokio::test]
+async fn generate_keys_reads_quoted_multiline_csv_fields() {
+    let mut server = Server::new_async().await;
+
+    let _mock = server
+        .mock("POST", "/dashboard/keys/batch")
+        .match_query(Matcher::Any)
+        .with_status(200)
+        .with_body("key,label\nmk_live_a,\"Line one\nline two\"\nmk_live_b,Plain\n")
+        .create_async()
+        .await;
+
+    let keys = client_for(&server)
+        .generate_keys(
+            batch(2),
+            BatchFormat::Csv,
+            DashboardRequestOptions::default(),
+        )
+        .await
+        .expect("request should succeed");
+
+    assert_eq!(keys.len(), 2);
+    assert_eq!(keys[0].label.as_deref(), Some("Line one\nline two"));
+    assert_eq!(keys[1].key, "mk_live_b");
+}
+
+#[tokio::test]
+async fn generate_keys_accepts_json_objects_and_bare_strings() {
+    let mut server = Server::new_async().await;
+
+    let _mock = server
+        .mock("POST", "/dashboard/keys/batch")
+        .match_query(Matcher::UrlEncoded("format".to_string(), "json".to_string()))
+        .with_status(200)
+        .with_body(r#"{"keys":[{"id":"k-1","key":"mk_live_a","expiresAt":"2026-01-01T00:00:00Z"},"mk_live_b"]}"#)
+        .create_async()
+        .await;
+
+    let keys = client_for(&server)
+        .generate_keys(
+            batch(2),
+            BatchFormat::Json,
+            DashboardRequestOptions::default(),
+        )
+        .await
+        .expect("request should succeed");
+
+    assert_eq!(keys[0].expires_at.as_deref(), Some("2026-01-01T00:00:00Z"));
+    assert_eq!(
+        keys[1],
+        GeneratedKey {
+            key: "mk_live_b".to_string(),
+            ..Default::default()
+        }
+    );
+}
+
+#[tokio::test]
+async fn stream_generated_keys_writes_chunked_output_as_ndjson() {
+    let mut server = Server::new_async().await;
+
+    let _mock = server
+        .mock("POST", "/dashboard/keys/batch")
+        .match_query(Matcher::UrlEncoded("format".to_string(), "txt".to_string()))
+        .with_status(200)
+        .with_header("content-type", "text/plain")
+        .with_chunked_body(|w| {
+            w.write_all(b"mk_live_a\nmk_li")?;
+            w.write_all(b"ve_b\n\nmk_live_c")
+        })
+        .create_async()
+        .await;
+
+    let mut writer = KeyWriter::new(Vec::new(), ExportFormat::Ndjson);
+    let written = client_for(&server)
+        .stream_generated_keys(
+            batch(3),
+            BatchFormat::Txt,
+            &mut writer,
+            DashboardRequestOptions::default(),
+        )
+        .await
+        .expect("request should succeed");
+
+    assert_eq!(written, 3);
+    let output = String::from_utf8(writer.finish().unwrap()).unwrap();
+    assert_eq!(
+        output,
+        "{\"key\":\"mk_live_a\"}\n{\"key\":\"mk_live_b\"}\n{\"key\":\"mk_live_c\"}\n"
+    );
+}
+
+#[derive(Clone, Default)]
+struct SharedBuffer(Arc<Mutex<Vec<u8>>>);
+
+impl SharedBuffer {
+    fn contents(&self) -> String {
+        String::from_utf8(self.0.lock().unwrap().clone()).unwrap()
+    }
+}
+
+impl Write for SharedBuffer {
+    fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+        self.0.lock().unwrap().extend_from_slice(buf);
+        Ok(buf.len())
+    }
+
+    fn flush(&mut self) -> std::io::Result<()> {
+        Ok(())
+    }
+}
+
+/// Streams `chunks` and notes what the writer held before each one was handed over.
+struct ChunkedTransport {
+    chunks: Vec<&'static str>,
+    output: SharedBuffer,
+    seen: Arc<Mutex<Vec<String>>>,
+}
+
+impl Transport for ChunkedTransport {
+    fn send<'a>(&'a self, _request: HttpRequest) -> TransportFuture<'a> {
+        Box::pin(async {
+            Err(NebulAuthError::Config(
+                "expected a streaming request".into(),
+            ))
+        })
+    }
+
+    fn send_streaming<'a>(&'a self, _request: HttpRequest) -> StreamingFuture<'a> {
+        let body = stream::iter(self.chunks.clone()).map(move |chunk| {
+            self.seen.lock().unwrap().push(self.output.contents());
+            Ok(chunk.as_bytes().to_vec())
+        });
+        Box::pin(async move {
+            Ok(StreamingResponse {
+                status: 200,
+                headers: Vec::new(),
+                body: Box::pin(body),
+            })
+        })
+    }
+}
+
+#[tokio::test]
+async fn stream_generated_keys_writes_keys_before_the_body_ends() {
+    let output = SharedBuffer::default();
+    let seen = Arc::new(Mutex::new(Vec::new()));
+    let transport = Arc::new(ChunkedTransport {
+        chunks: vec![
+            "key,label\nmk_live_a,\"Line one\n",
+            "line two\"\nmk_li",
+            "ve_b,B\n",
+        ],
+        output: output.clone(),
+        seen: seen.clone(),
+    });
+    let client = NebulAuthDashboardClient::new(NebulAuthDashboardClientOptions::default())
+        .unwrap()
+        .with_transport(transport);
+
+    let mut writer = KeyWriter::new(output.clone(), ExportFormat::Text);
+    let written = client
+        .stream_generated_keys(
+            batch(2),
+            BatchFormat::Csv,
+            &mut writer,
+            DashboardRequestOptions::default(),
+        )
+        .await
+        .expect("request should succeed");
+
+    assert_eq!(written, 2);
+    assert_eq!(
+        *seen.lock().unwrap(),
+        vec!["", "", "mk_live_a\n"],
+        "each key should be written as soon as its record is complete"
+    );
+    assert_eq!(output.contents(), "mk_live_a\nmk_live_b\n");
+}
+
+/// Answers with a fixed body and has no `send_streaming` of its own.
+struct BufferedTransport {
+    body: &'static str,
+    sent: Mutex<Vec<HttpRequest>>,
+}
+
+impl Transport for BufferedTransport {
+    fn send<'a>(&'a self, request: HttpRequest) -> TransportFuture<'a> {
+        self.sent.lock().unwrap().push(request);
+        Box::pin(async move {
+            Ok(HttpResponse {
+                status: 200,
+                headers: Vec::new(),
+                body: self.body.to_string(),
+            })
+        })
+    }
+}
+
+#[tokio::test]
+async fn stream_generated_keys_falls_back_to_buffered_transports() {
+    let transport = Arc::new(BufferedTransport {
+        body: "mk_live_a\nmk_live_b\n",
+        sent: Mutex::new(Vec::new()),
+    });
+    let client = NebulAuthDashboardClient::new(NebulAuthDashboardClientOptions::default())
+        .unwrap()
+        .with_transport(transport.clone());
+
+    let mut writer = KeyWriter::new(Vec::new(), ExportFormat::Text);
+    let written = client
+        .stream_generated_keys(
+            batch(2),
+            BatchFormat::Txt,
+            &mut writer,
+            DashboardRequestOptions::default(),
+        )
+        .await
+        .expect("buffered request should succeed");
+
+    assert_eq!(written, 2);
+    let sent = transport.sent.lock().unwrap();
+    assert_eq!(sent.len(), 1);
+    assert!(sent[0].url.ends_with("/dashboard/keys/batch?format=txt"));
+    assert_eq!(writer.finish().unwrap(), b"mk_live_a\nmk_live_b\n");
+}
+
+fn unlabeled(count: i64) -> KeyBatchCreateRequest {
+    KeyBatchCreateRequest {
+        label_prefix: None,
+        ..batch(count)
+    }
+}
+
+async fn mock_chunk(server: &mut ServerGuard, count: i64, keys: &str) -> Mock {
+    server
+        .mock("POST", "/dashboard/keys/batch")
+        .match_query(Matcher::Any)
+        .match_body(Matcher::PartialJson(json!({ "count": count })))
+        .with_status(200)
+        .with_body(keys)
+        .create_async()
+        .await
+}
+
+#[tokio::test]
+async fn chunked_creation_splits_the_count() {
+    let mut server = Server::new_async().await;
+
+    let mocks = vec![
+        mock_chunk(&mut server, 3, r#"["mk_a","mk_b","mk_c"]"#).await,
+        mock_chunk(&mut server, 2, r#"["mk_d","mk_e"]"#).await,
+    ];
+
+    let report = client_for(&server)
+        .bulk_create_keys_chunked(
+            unlabeled(5),
+            ChunkedBatchOptions {
+                chunk_size: 3,
+                ..Default::default()
+            },
+            DashboardRequestOptions::default(),
+        )
+        .await
+        .expect("chunked creation should succeed");
+
+    assert!(report.is_complete());
+    assert_eq!(report.chunks[1].offset, 3);
+    let keys: Vec<&str> = report.keys().map(|key| key.key.as_str()).collect();
+    assert_eq!(keys, vec!["mk_a", "mk_b", "mk_c", "mk_d", "mk_e"]);
+    for mock in mocks {
+        mock.assert_async().await;
+    }
+}
+
+#[tokio::test]
+async fn short_chunk_is_partial_and_resume_requests_only_the_rest() {
+    let mut server = Server::new_async().await;
+
+    let short = mock_chunk(&mut server, 3, r#"["mk_a","mk_b"]"#).await;
+    let client = client_for(&server);
+    let report = client
+        .bulk_create_keys_chunked(
+            unlabeled(3),
+            ChunkedBatchOptions::default(),
+            DashboardRequestOptions::default(),
+        )
+        .await
+        .expect("chunked creation should return a report");
+
+    assert!(!report.is_complete());
+    assert_eq!(
+        report.chunks[0].outcome,
+        ChunkOutcome::Partial("expected 3 keys, got 2".to_string())
+    );
+    assert_eq!(report.failed_chunks().count(), 1);
+    short.assert_async().await;
+
+    let rest = mock_chunk(&mut server, 1, r#"["mk_c"]"#).await;
+    let resumed = client
+        .bulk_create_keys_chunked(
+            unlabeled(3),
+            ChunkedBatchOptions {
+                resume: Some(report),
+                ..Default::default()
+            },
+            DashboardRequestOptions::default(),
+        )
+        .await
+        .expect("resumed creation should succeed");
+
+    assert!(resumed.is_complete());
+    let keys: Vec<&str> = resumed.keys().map(|key| key.key.as_str()).collect();
+    assert_eq!(keys, vec!["mk_a", "mk_b", "mk_c"]);
+    rest.assert_async().await;
+}
+
+#[tokio::test]
+async fn failed_chunk_can_be_resumed() {
+    let mut server = Server::new_async().await;
+
+    let first = mock_chunk(&mut server, 2, r#"["mk_a","mk_b"]"#).await;
+    let failing = server
+        .mock("POST", "/dashboard/keys/batch")
+        .match_query(Matcher::Any)
+        .match_body(Matcher::PartialJson(json!({ "count": 1 })))
+        .with_status(503)
+        .with_body(r#"{"error":"busy"}"#)
+        .create_async()
+        .await;
+
+    let client = client_for(&server);
+    let chunk_options = ChunkedBatchOptions {
+        chunk_size: 2,
+        concurrency: 1,
+        ..Default::default()
+    };
+    let report = client
+        .bulk_create_keys_chunked(
+            unlabeled(3),
+            chunk_options.clone(),
+            DashboardRequestOptions::default(),
+        )
+        .await
+        .expect("chunked creation should return a report");
+
+    assert!(!report.is_complete());
+    assert_eq!(report.chunks[0].outcome, ChunkOutcome::Succeeded);
+    assert_eq!(report.failed_chunks().count(), 1);
+    first.assert_async().await;
+    failing.remove_async().await;
+
+    let retry = mock_chunk(&mut server, 1, r#"["mk_c"]"#).await;
+    let resumed = client
+        .bulk_create_keys_chunked(
+            unlabeled(3),
+            ChunkedBatchOptions {
+                resume: Some(report),
+                ..chunk_options
+            },
+            DashboardRequestOptions::default(),
+        )
+        .await
+        .expect("resumed creation should succeed");
+
+    assert!(resumed.is_complete());
+    assert_eq!(resumed.keys().count(), 3);
+    first.expect(1).assert_async().await;
+    retry.assert_async().await;
+}
+
+#[tokio::test]
+async fn chunked_labels_are_numbered_across_chunks() {
+    let mut server = Server::new_async().await;
+
+    let mocks = vec![
+        mock_chunk(
+            &mut server,
+            2,
+            r#"[{"id":"k-1","key":"mk_a","label":"Promo1"},{"id":"k-2","key":"mk_b","label":"Promo2"}]"#,
+        )
+        .await,
+        mock_chunk(
+            &mut server,
+            1,
+            r#"[{"id":"k-3","key":"mk_c","label":"Promo1"}]"#,
+        )
+        .await,
+        server
+            .mock("PATCH", "/dashboard/keys/k-3")
+            .match_body(Matcher::JsonString(r#"{"label":"Promo3"}"#.to_string()))
+            .with_status(200)
+            .with_body(r#"{"id":"k-3"}"#)
+            .create_async()
+            .await,
+    ];
+
+    let client = client_for(&server);
+    let chunk_options = ChunkedBatchOptions {
+        chunk_size: 2,
+        ..Default::default()
+    };
+    let report = client
+        .bulk_create_keys_chunked(
+            batch(3),
+            chunk_options.clone(),
+            DashboardRequestOptions::default(),
+        )
+        .await
+        .expect("chunked creation should succeed");
+
+    assert!(report.is_complete());
+    let labels: Vec<&str> = report
+        .keys()
+        .filter_map(|key| key.label.as_deref())
+        .collect();
+    assert_eq!(labels, vec!["Promo1", "Promo2", "Promo3"]);
+    for mock in mocks {
+        mock.assert_async().await;
+    }
+
+    let err = client
+        .bulk_create_keys_chunked(
+            batch(3),
+            ChunkedBatchOptions {
+                format: BatchFormat::Txt,
+                ..chunk_options
+            },
+            DashboardRequestOptions::default(),
+        )
+        .await
+        .expect_err("txt keys have no IDs to relabel");
+    assert!(matches!(err, NebulAuthError::Config(_)));
+}
+
+#[test]
+fn csv_writer_quotes_fields_and_writes_header_once() {
+    let mut writer = KeyWriter::new(Vec::new(), ExportFormat::Csv);
+    writer
+        .write_all(&[
+            GeneratedKey {
+                key: "mk_live_a".to_string(),
+                label: Some("Promo, \"VIP\"".to_string()),
+                duration_hours: Some(24),
+                ..Default::default()
+            },
+            GeneratedKey {
+                key: "mk_live_b".to_string(),
+                ..Default::default()
+            },
+        ])
+        .expect("write should succeed");
+
+    let output = String::from_utf8(writer.finish().unwrap()).unwrap();
+    assert_eq!(
+        output,
+        "key,id,label,duration_hours,expires_at\nmk_live_a,,\"Promo, \"\"VIP\"\"\",24,\nmk_live_b,,,,\n"
+    );
+}
@@ -0,0 +1,184 @@
+#![cfg(feature = "dashboard")]
+
+use chrono::{TimeZone, Utc};
+use mockito::{Matcher, Server, ServerGuard};
+use nebulauth_sdk::bulk::{self, BulkOperation, BulkOptions, BulkOutcome, KeySelector};
+use nebulauth_sdk::{
+    DashboardAuth, DashboardKey, DashboardRequestOptions, KeyRevokeRequest,
+    NebulAuthDashboardClient, NebulAuthDashboardClientOptions,
+};
+use serde_json::json;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Arc;
+
+const KEYS: &str = r#"[
+    {"id":"k-1","label":"Promo-1","duration_hours":24,"expires_at":"2026-01-10T00:00:00Z","metadata":{"batch":"a"}},
+    {"id":"k-2","label":"Promo-2","duration_hours":24,"expires_at":"2026-03-01T00:00:00Z","metadata":{"batch":"b"}},
+    {"id":"k-3","label":"Promo-3","revoked":true},
+    {"id":"k-4","label":"Other"}
+]"#;
+
+fn client_for(server: &ServerGuard) -> NebulAuthDashboardClient {
+    NebulAuthDashboardClient::new(NebulAuthDashboardClientOptions {
+        base_url: format!("{}/dashboard", server.url()),
+        auth: Some(DashboardAuth::Bearer {
+            bearer_token: "mk_at_test".to_string(),
+        }),
+        ..Default::default()
+    })
+    .expect("client init should succeed")
+}
+
+fn key(value: serde_json::Value) -> DashboardKey {
+    serde_json::from_value(value).expect("key should parse")
+}
+
+#[test]
+fn selector_combines_prefix_metadata_and_expiry_window() {
+    let keys: Vec<DashboardKey> = serde_json::from_str(KEYS).expect("keys should parse");
+
+    let mut selector = KeySelector::label_prefix("Promo-");
+    let selected: Vec<&str> = keys
+        .iter()
+        .filter(|k| selector.matches(k))
+        .map(|k| k.id.as_str())
+        .collect();
+    assert_eq!(selected, vec!["k-1", "k-2"]);
+
+    selector.metadata.insert("batch".to_string(), json!("b"));
+    assert!(!selector.matches(&keys[0]));
+    assert!(selector.matches(&keys[1]));
+
+    let window = KeySelector {
+        expires_before: Some(Utc.with_ymd_and_hms(2026, 2, 1, 0, 0, 0).unwrap()),
+        ..Default::default()
+    };
+    assert!(window.matches(&keys[0]));
+    assert!(!window.matches(&keys[1]));
+    assert!(!window.matches(&key(json!({ "id": "k-9" }))));
+}
+
+#[tokio::test]
+async fn revoke_reports_per_key_results_and_progress() {
+    let mut server = Server::new_async().await;
+
+    let _list = server
+        .mock("GET", "/dashboard/keys")
+        .with_status(200)
+        .with_body(KEYS)
+        .create_async()
+        .await;
+    let revoke_ok = server
+        .mock("DELETE", "/dashboard/keys/k-1")
+        .match_body(Matcher::JsonString(r#"{"reason":"refund"}"#.to_string()))
+        .with_status(200)
+        .with_body(r#"{"ok":true}"#)
+        .create_async()
+        .await;
+    let revoke_fail = server
+        .mock("DELETE", "/dashboard/keys/k-2")
+        .with_status(404)
+        .with_body(r#"{"error":"not found"}"#)
+        .create_async()
+        .await;
+
+    let calls = Arc::new(AtomicUsize::new(0));
+    let progress_calls = calls.clone();
+
+    let report = bulk::run(
+        &client_for(&server),
+        &KeySelector::label_prefix("Promo-"),
+        &BulkOperation::Revoke(KeyRevokeRequest {
+            reason: Some("refund".to_string()),
+        }),
+        BulkOptions {
+            concurrency: 2,
+            on_progress: Some(Arc::new(move |progress| {
+                assert_eq!(progress.total, 2);
+                progress_calls.fetch_add(1, Ordering::SeqCst);
+            })),
+            ..Default::default()
+        },
+        DashboardRequestOptions::default(),
+    )
+    .await
+    .expect("bulk run should succeed");
+
+    assert_eq!(calls.load(Ordering::SeqCst), 2);
+    assert_eq!(report.succeeded().count(), 1);
+    let failed: Vec<_> = report.failed().collect();
+    assert_eq!(failed.len(), 1);
+    assert_eq!(failed[0].key_id, "k-2");
+    assert!(
+        matches!(&failed[0].outcome, BulkOutcome::Failed(message) if message.contains("not found"))
+    );
+    revoke_ok.assert_async().await;
+    revoke_fail.assert_async().await;
+}
+
+#[tokio::test]
+async fn dry_run_and_resume_skip_completed_keys() {
+    let mut server = Server::new_async().await;
+
+    let _list = server
+        .mock("GET", "/dashboard/keys")
+        .with_status(200)
+        .with_body(KEYS)
+        .create_async()
+        .await;
+    let extend_k2 = server
+        .mock("PATCH", "/dashboard/keys/k-2")
+        .match_body(Matcher::JsonString(r#"{"duration_hours":72}"#.to_string()))
+        .with_status(200)
+        .with_body("{}")
+        .expect(1)
+        .create_async()
+        .await;
+    let extend_k1 = server
+        .mock("PATCH", "/dashboard/keys/k-1")
+        .expect(0)
+        .create_async()
+        .await;
+
+    let client = client_for(&server);
+    let selector = KeySelector::label_prefix("Promo-");
+    let operation = BulkOperation::Extend { hours: 48 };
+
+    let plan = bulk::run(
+        &client,
+        &selector,
+        &operation,
+        BulkOptions {
+            dry_run: true,
+            ..Default::default()
+        },
+        DashboardRequestOptions::default(),
+    )
+    .await
+    .expect("dry run should succeed");
+    assert!(plan
+        .results
+        .iter()
+        .all(|result| result.outcome == BulkOutcome::Planned));
+
+    let mut previous = plan.clone();
+    previous.results[0].outcome = BulkOutcome::Succeeded;
+    previous.results[1].outcome = BulkOutcome::Failed("timeout".to_string());
+
+    let report = bulk::run(
+        &client,
+        &selector,
+        &operation,
+        BulkOptions {
+            resume: Some(previous),
+            ..Default::default()
+        },
+        DashboardRequestOptions::default(),
+    )
+    .await
+    .expect("resumed run should succeed");
+
+    assert_eq!(report.succeeded().count(), 2);
+    extend_k1.assert_async().await;
+    extend_k2.assert_async().await;
+}
@@ -1,4 +1,7 @@
+#![cfg(feature = "runtime")]
+
 use mockito::{Matcher, Server};
+use nebulauth_sdk::verifier::LicenseVerifier;
 use nebulauth_sdk::{
     AuthVerifyInput, NebulAuthClient, NebulAuthClientOptions, NebulAuthError, RedeemKeyInput,
     ReplayProtectionMode, ResetHwidInput, VerifyKeyInput,
@@ -28,6 +31,7 @@ async fn verify_key_sends_bearer_hwid_and_body() {
         service_slug: None,
         replay_protection: ReplayProtectionMode::None,
         timeout_ms: 15_000,
+        ..Default::default()
     })
     .expect("client init should succeed");
 
@@ -69,6 +73,7 @@ async fn strict_replay_adds_signature_headers() {
         service_slug: None,
         replay_protection: ReplayProtectionMode::Strict,
         timeout_ms: 15_000,
+        ..Default::default()
     })
     .expect("client init should succeed");
 
@@ -101,6 +106,7 @@ async fn pop_mode_requires_credentials() {
         service_slug: None,
         replay_protection: ReplayProtectionMode::None,
         timeout_ms: 15_000,
+        ..Default::default()
     })
     .expect("client init should succeed");
 
@@ -133,6 +139,7 @@ async fn redeem_requires_service_slug() {
         service_slug: None,
         replay_protection: ReplayProtectionMode::None,
         timeout_ms: 15_000,
+        ..Default::default()
     })
     .expect("client init should succeed");
 
@@ -165,6 +172,7 @@ async fn reset_hwid_requires_discord_or_key() {
         service_slug: None,
         replay_protection: ReplayProtectionMode::None,
         timeout_ms: 15_000,
+        ..Default::default()
     })
     .expect("client init should succeed");
 
@@ -195,6 +203,7 @@ async fn text_response_falls_back_to_error_field() {
         service_slug: None,
         replay_protection: ReplayProtectionMode::None,
         timeout_ms: 15_000,
+        ..Default::default()
     })
     .expect("client init should succeed");
 
@@ -230,6 +239,7 @@ async fn auth_verify_hits_expected_endpoint() {
         service_slug: None,
         replay_protection: ReplayProtectionMode::None,
         timeout_ms: 15_000,
+        ..Default::default()
     })
     .expect("client init should succeed");
 
@@ -245,3 +255,37 @@ async fn auth_verify_hits_expected_endpoint() {
     assert_eq!(response.data["valid"], true);
     mock.assert_async().await;
 }
+
+#[tokio::test]
+async fn client_is_usable_as_license_verifier() {
+    let mut server = Server::new_async().await;
+
+    let mock = server
+        .mock("POST", "/api/v1/keys/reset-hwid")
+        .match_body(Matcher::JsonString(r#"{"key":"mk_live_test"}"#.to_string()))
+        .with_status(200)
+        .with_body(r#"{"reset":true}"#)
+        .create_async()
+        .await;
+
+    let client = NebulAuthClient::new(NebulAuthClientOptions {
+        base_url: format!("{}/api/v1", server.url()),
+        bearer_token: Some("mk_at_test".to_string()),
+        replay_protection: ReplayProtectionMode::None,
+        timeout_ms: 15_000,
+        ..Default::default()
+    })
+    .expect("client init should succeed");
+    let verifier: &dyn LicenseVerifier = &client;
+
+    let response = verifier
+        .reset_hwid(ResetHwidInput {
+            key: Some("mk_live_test".to_string()),
+            ..Default::default()
+        })
+        .await
+        .expect("request should complete");
+
+    assert_eq!(response.data["reset"], true);
+    mock.assert_async().await;
+}
@@ -0,0 +1,263 @@
+#![cfg(feature = "config")]
+
+use mockito::{Matcher, Server};
+use nebulauth_sdk::config::{self, Change, DesiredState, LiveState, PlanOptions};
+use nebulauth_sdk::{
+    ApiAuthMode, ApiToken, ApiTokenScope, BlacklistEntry, BlacklistType, Checkpoint,
+    CheckpointStep, Customer, DashboardAuth, DashboardRequestOptions, NebulAuthDashboardClient,
+    NebulAuthDashboardClientOptions, ReplayProtectionMode, TeamMember, TeamRole,
+};
+
+const DESIRED_YAML: &str = r#"
+customer:
+  require_hwid: true
+  paused: false
+checkpoints:
+  - name: Linkvertise
+    duration_hours: 24
+    steps:
+      - https://ads.example.com/1
+blacklist:
+  - type: hwid
+    value: HWID-BANNED
+team:
+  - email: support@example.com
+    role: admin
+"#;
+
+fn live_state() -> LiveState {
+    LiveState {
+        customer: Customer {
+            require_hwid: Some(false),
+            paused: Some(false),
+            ..Default::default()
+        },
+        checkpoints: vec![
+            Checkpoint {
+                id: "cp-1".to_string(),
+                name: "Linkvertise".to_string(),
+                duration_hours: Some(12),
+                is_active: Some(true),
+                referrer_domain_only: None,
+                steps: vec![CheckpointStep {
+                    ad_url: "https://ads.example.com/1".to_string(),
+                }],
+            },
+            Checkpoint {
+                id: "cp-2".to_string(),
+                name: "Old".to_string(),
+                duration_hours: Some(1),
+                is_active: Some(false),
+                referrer_domain_only: None,
+                steps: Vec::new(),
+            },
+        ],
+        blacklist: vec![BlacklistEntry {
+            id: "bl-1".to_string(),
+            r#type: BlacklistType::Ip,
+            value: "10.0.0.1".to_string(),
+            reason: None,
+            created_at: None,
+        }],
+        api_tokens: Vec::new(),
+        team: vec![
+            TeamMember {
+                id: "u-1".to_string(),
+                email: "Support@example.com".to_string(),
+                role: Some(TeamRole::Member),
+            },
+            TeamMember {
+                id: "u-0".to_string(),
+                email: "owner@example.com".to_string(),
+                role: Some(TeamRole::Owner),
+            },
+        ],
+    }
+}
+
+#[test]
+fn diff_detects_creates_and_updates_without_prune() {
+    let desired = DesiredState::from_yaml_str(DESIRED_YAML).expect("yaml should parse");
+    let plan =
+        config::diff(&desired, &live_state(), PlanOptions::default()).expect("diff should succeed");
+
+    let lines: Vec<String> = plan.changes.iter().map(ToString::to_string).collect();
+    assert_eq!(
+        lines,
+        vec![
+            "~ customer require_hwid=true",
+            "~ checkpoint 'Linkvertise'",
+            "+ blacklist hwid 'HWID-BANNED'",
+            "~ team member Support@example.com -> admin",
+        ]
+    );
+
+    match &plan.changes[1] {
+        Change::UpdateCheckpoint { id, update, .. } => {
+            assert_eq!(id, "cp-1");
+            assert_eq!(update.duration_hours, Some(24));
+            assert!(update.steps.is_none());
+            assert!(update.is_active.is_none());
+        }
+        other => panic!("unexpected change: {other:?}"),
+    }
+}
+
+#[test]
+fn prune_deletes_unlisted_resources_but_keeps_owner() {
+    let desired = DesiredState::from_yaml_str(DESIRED_YAML).expect("yaml should parse");
+    let plan = config::diff(&desired, &live_state(), PlanOptions { prune: true })
+        .expect("diff should succeed");
+
+    let deletes: Vec<String> = plan
+        .changes
+        .iter()
+        .map(ToString::to_string)
+        .filter(|line| line.starts_with('-'))
+        .collect();
+    assert_eq!(
+        deletes,
+        vec!["- checkpoint 'Old'", "- blacklist ip '10.0.0.1'"]
+    );
+}
+
+fn verify_token(id: &str, expires_at: Option<&str>) -> ApiToken {
+    ApiToken {
+        id: id.to_string(),
+        scopes: vec![ApiTokenScope::KeysVerify],
+        replay_protection: Some(ReplayProtectionMode::Strict),
+        auth_mode: Some(ApiAuthMode::Pop),
+        expires_at: expires_at.map(str::to_string),
+        created_at: None,
+    }
+}
+
+#[test]
+fn api_tokens_without_id_match_deterministically_or_refuse() {
+    let desired = DesiredState::from_yaml_str(
+        r#"
+api_tokens:
+  - scopes: [keys:verify]
+    replay_protection: strict
+    auth_mode: pop
+    expires_at: "2027-01-01T00:00:00Z"
+"#,
+    )
+    .expect("yaml should parse");
+
+    let mut live = live_state();
+    live.api_tokens = vec![
+        verify_token("t-2", Some("2027-01-01T00:00:00Z")),
+        verify_token("t-3", None),
+        verify_token("t-1", Some("2027-01-01T00:00:00Z")),
+    ];
+    let plan =
+        config::diff(&desired, &live, PlanOptions { prune: true }).expect("diff should succeed");
+    let deleted: Vec<&str> = plan
+        .changes
+        .iter()
+        .filter_map(|change| match change {
+            Change::DeleteApiToken { id } => Some(id.as_str()),
+            _ => None,
+        })
+        .collect();
+    assert_eq!(deleted, vec!["t-2", "t-3"]);
+
+    live.api_tokens = vec![
+        verify_token("t-1", Some("2027-06-01T00:00:00Z")),
+        verify_token("t-2", None),
+    ];
+    let err = config::diff(&desired, &live, PlanOptions { prune: true })
+        .expect_err("ambiguous match should be refused");
+    assert!(err.to_string().contains("t-1, t-2"), "{err}");
+}
+
+#[test]
+fn toml_config_parses_and_rejects_unknown_fields() {
+    let desired = DesiredState::from_toml_str(
+        r#"
+[customer]
+require_discord_redeem = true
+
+[[api_tokens]]
+scopes = ["keys:verify"]
+replay_protection = "nonce"
+auth_mode = "bearer"
+"#,
+    )
+    .expect("toml should parse");
+
+    assert_eq!(
+        desired.customer.and_then(|c| c.require_discord_redeem),
+        Some(true)
+    );
+    assert_eq!(desired.api_tokens.map(|t| t.len()), Some(1));
+    assert!(desired.team.is_none());
+
+    assert!(DesiredState::from_toml_str("unknown_section = 1").is_err());
+}
+
+#[tokio::test]
+async fn plan_and_apply_call_dashboard_endpoints() {
+    let mut server = Server::new_async().await;
+
+    let list_mock = server
+        .mock("GET", "/dashboard/blacklist")
+        .with_status(200)
+        .with_body(r#"{"data":[{"id":"bl-1","type":"ip","value":"10.0.0.1"}]}"#)
+        .create_async()
+        .await;
+
+    let create_mock = server
+        .mock("POST", "/dashboard/blacklist")
+        .match_body(Matcher::JsonString(
+            r#"{"type":"hwid","value":"HWID-BANNED"}"#.to_string(),
+        ))
+        .with_status(201)
+        .with_body(r#"{"id":"bl-2"}"#)
+        .create_async()
+        .await;
+
+    let delete_mock = server
+        .mock("DELETE", "/dashboard/blacklist/bl-1")
+        .with_status(204)
+        .create_async()
+        .await;
+
+    let client = NebulAuthDashboardClient::new(NebulAuthDashboardClientOptions {
+        base_url: format!("{}/dashboard", server.url()),
+        auth: Some(DashboardAuth::Bearer {
+            bearer_token: "mk_at_test".to_string(),
+        }),
+        ..Default::default()
+    })
+    .expect("client init should succeed");
+
+    let desired = DesiredState::from_yaml_str(
+        r#"
+blacklist:
+  - type: hwid
+    value: HWID-BANNED
+"#,
+    )
+    .expect("yaml should parse");
+
+    let plan = config::plan(
+        &client,
+        &desired,
+        PlanOptions { prune: true },
+        DashboardRequestOptions::default(),
+    )
+    .await
+    .expect("plan should succeed");
+    assert_eq!(plan.changes.len(), 2);
+
+    let applied = config::apply(&client, &plan, DashboardRequestOptions::default())
+        .await
+        .expect("apply should succeed");
+    assert_eq!(applied.len(), 2);
+
+    list_mock.assert_async().await;
+    create_mock.assert_async().await;
+    delete_mock.assert_async().await;
+}
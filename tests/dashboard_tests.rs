@@ -1,7 +1,10 @@
+#![cfg(feature = "dashboard")]
+
 use mockito::{Matcher, Server};
 use nebulauth_sdk::{
-    DashboardAuth, DashboardRequestOptions, NebulAuthDashboardClient,
-    NebulAuthDashboardClientOptions,
+    ApiAuthMode, ApiToken, ApiTokenCreateRequest, ApiTokenScope, BatchFormat,
+    BlacklistCreateRequest, BlacklistType, DashboardAuth, DashboardRequestOptions, LoginRequest,
+    NebulAuthDashboardClient, NebulAuthDashboardClientOptions, ReplayProtectionMode, TeamRole,
 };
 
 #[tokio::test]
@@ -125,7 +128,7 @@ async fn bulk_create_keys_uses_format_query() {
                 key_only: Some(false),
                 metadata: None,
             },
-            "txt",
+            BatchFormat::Txt,
             DashboardRequestOptions::default(),
         )
         .await
@@ -133,3 +136,310 @@ async fn bulk_create_keys_uses_format_query() {
 
     mock.assert_async().await;
 }
+
+#[tokio::test]
+async fn login_captures_session_cookie_for_later_requests() {
+    let mut server = Server::new_async().await;
+
+    let login_mock = server
+        .mock("POST", "/dashboard/auth/login")
+        .match_body(Matcher::JsonString(
+            r#"{"email":"ops@example.com","password":"hunter2"}"#.to_string(),
+        ))
+        .with_status(200)
+        .with_header("set-cookie", "mc_session=sess-new; Path=/; HttpOnly")
+        .with_body(r#"{"ok":true}"#)
+        .create_async()
+        .await;
+
+    let me_mock = server
+        .mock("GET", "/dashboard/me")
+        .match_header("cookie", "mc_session=sess-new")
+        .with_status(200)
+        .with_body(r#"{"id":"user-1"}"#)
+        .create_async()
+        .await;
+
+    let client = NebulAuthDashboardClient::new(NebulAuthDashboardClientOptions {
+        base_url: format!("{}/dashboard", server.url()),
+        ..Default::default()
+    })
+    .expect("client init should succeed");
+
+    let login = client
+        .login(
+            LoginRequest {
+                email: "ops@example.com".to_string(),
+                password: "hunter2".to_string(),
+            },
+            DashboardRequestOptions::default(),
+        )
+        .await
+        .expect("login should succeed");
+
+    assert!(login.ok);
+    assert_eq!(client.session_cookie().as_deref(), Some("sess-new"));
+
+    let me = client
+        .me(DashboardRequestOptions::default())
+        .await
+        .expect("request should succeed");
+
+    assert_eq!(me.data["id"], "user-1");
+    login_mock.assert_async().await;
+    me_mock.assert_async().await;
+}
+
+#[tokio::test]
+async fn expired_session_relogs_in_once_on_401() {
+    let mut server = Server::new_async().await;
+
+    let expired_mock = server
+        .mock("GET", "/dashboard/keys")
+        .match_header("cookie", "mc_session=sess-old")
+        .with_status(401)
+        .with_body(r#"{"error":"unauthorized"}"#)
+        .expect(1)
+        .create_async()
+        .await;
+
+    let login_mock = server
+        .mock("POST", "/dashboard/auth/login")
+        .with_status(200)
+        .with_header("set-cookie", "mc_session=sess-fresh; Path=/; HttpOnly")
+        .with_body(r#"{"ok":true}"#)
+        .expect(1)
+        .create_async()
+        .await;
+
+    let fresh_mock = server
+        .mock("GET", "/dashboard/keys")
+        .match_header("cookie", "mc_session=sess-fresh")
+        .with_status(200)
+        .with_body("[]")
+        .expect(1)
+        .create_async()
+        .await;
+
+    let client = NebulAuthDashboardClient::new(NebulAuthDashboardClientOptions {
+        base_url: format!("{}/dashboard", server.url()),
+        auth: Some(DashboardAuth::Session {
+            session_cookie: "sess-old".to_string(),
+        }),
+        credentials: Some(LoginRequest {
+            email: "ops@example.com".to_string(),
+            password: "hunter2".to_string(),
+        }),
+        ..Default::default()
+    })
+    .expect("client init should succeed");
+
+    let response = client
+        .list_keys(DashboardRequestOptions::default())
+        .await
+        .expect("request should succeed");
+
+    assert!(response.ok);
+    assert_eq!(client.session_cookie().as_deref(), Some("sess-fresh"));
+    expired_mock.assert_async().await;
+    login_mock.assert_async().await;
+    fresh_mock.assert_async().await;
+}
+
+#[tokio::test]
+async fn session_file_is_loaded_and_cleared_on_logout() {
+    let mut server = Server::new_async().await;
+
+    let session_file = std::env::temp_dir().join(format!(
+        "nebulauth-session-{}-{}",
+        std::process::id(),
+        server.socket_address().port()
+    ));
+    std::fs::write(&session_file, "sess-saved\n").expect("session file should be writable");
+
+    let logout_mock = server
+        .mock("POST", "/dashboard/auth/logout")
+        .match_header("cookie", "mc_session=sess-saved")
+        .with_status(200)
+        .with_body(r#"{"ok":true}"#)
+        .create_async()
+        .await;
+
+    let client = NebulAuthDashboardClient::new(NebulAuthDashboardClientOptions {
+        base_url: format!("{}/dashboard", server.url()),
+        session_file: Some(session_file.clone()),
+        ..Default::default()
+    })
+    .expect("client init should succeed");
+
+    assert_eq!(client.session_cookie().as_deref(), Some("sess-saved"));
+
+    client
+        .logout(DashboardRequestOptions::default())
+        .await
+        .expect("logout should succeed");
+
+    assert!(client.session_cookie().is_none());
+    assert!(!session_file.exists());
+    logout_mock.assert_async().await;
+}
+
+#[tokio::test]
+async fn failed_logout_keeps_the_session_for_a_retry() {
+    let mut server = Server::new_async().await;
+
+    let session_file = std::env::temp_dir().join(format!(
+        "nebulauth-session-failed-logout-{}-{}",
+        std::process::id(),
+        server.socket_address().port()
+    ));
+    std::fs::write(&session_file, "sess-saved\n").expect("session file should be writable");
+
+    let failing = server
+        .mock("POST", "/dashboard/auth/logout")
+        .with_status(503)
+        .with_body(r#"{"error":"try again"}"#)
+        .create_async()
+        .await;
+
+    let client = NebulAuthDashboardClient::new(NebulAuthDashboardClientOptions {
+        base_url: format!("{}/dashboard", server.url()),
+        session_file: Some(session_file.clone()),
+        ..Default::default()
+    })
+    .expect("client init should succeed");
+
+    let err = client
+        .logout(DashboardRequestOptions::default())
+        .await
+        .expect_err("failed logout should be an error");
+    assert!(matches!(
+        err,
+        nebulauth_sdk::NebulAuthError::Api {
+            status_code: 503,
+            ..
+        }
+    ));
+    assert_eq!(client.session_cookie().as_deref(), Some("sess-saved"));
+    assert!(session_file.exists());
+    failing.assert_async().await;
+    failing.remove_async().await;
+
+    let expired = server
+        .mock("POST", "/dashboard/auth/logout")
+        .match_header("cookie", "mc_session=sess-saved")
+        .with_status(401)
+        .with_body(r#"{"error":"unauthorized"}"#)
+        .create_async()
+        .await;
+
+    client
+        .logout(DashboardRequestOptions::default())
+        .await
+        .expect("a session the server no longer knows is logged out");
+    assert!(client.session_cookie().is_none());
+    assert!(!session_file.exists());
+    expired.assert_async().await;
+}
+
+#[tokio::test]
+async fn create_requests_serialize_typed_enums() {
+    let mut server = Server::new_async().await;
+
+    let blacklist_mock = server
+        .mock("POST", "/dashboard/blacklist")
+        .match_body(Matcher::JsonString(
+            r#"{"type":"hwid","value":"HWID-1"}"#.to_string(),
+        ))
+        .with_status(201)
+        .with_body(r#"{"id":"bl-1"}"#)
+        .create_async()
+        .await;
+
+    let token_mock = server
+        .mock("POST", "/dashboard/api-tokens")
+        .match_body(Matcher::JsonString(
+            r#"{"scopes":["keys:verify","custom:scope"],"replay_protection":"strict","auth_mode":"pop"}"#
+                .to_string(),
+        ))
+        .with_status(201)
+        .with_body(r#"{"id":"tok-1"}"#)
+        .create_async()
+        .await;
+
+    let client = NebulAuthDashboardClient::new(NebulAuthDashboardClientOptions {
+        base_url: format!("{}/dashboard", server.url()),
+        auth: Some(DashboardAuth::Bearer {
+            bearer_token: "mk_at_test".to_string(),
+        }),
+        ..Default::default()
+    })
+    .expect("client init should succeed");
+
+    client
+        .create_blacklist_entry(
+            BlacklistCreateRequest {
+                r#type: BlacklistType::Hwid,
+                value: "HWID-1".to_string(),
+                reason: None,
+            },
+            DashboardRequestOptions::default(),
+        )
+        .await
+        .expect("request should succeed");
+
+    client
+        .create_api_token(
+            ApiTokenCreateRequest {
+                scopes: vec![ApiTokenScope::KeysVerify, "custom:scope".into()],
+                replay_protection: ReplayProtectionMode::Strict,
+                auth_mode: ApiAuthMode::Pop,
+                expires_at: None,
+            },
+            DashboardRequestOptions::default(),
+        )
+        .await
+        .expect("request should succeed");
+
+    blacklist_mock.assert_async().await;
+    token_mock.assert_async().await;
+}
+
+#[test]
+fn unknown_enum_values_round_trip_through_other() {
+    let role: TeamRole = serde_json::from_str(r#""billing""#).expect("role should parse");
+    assert_eq!(role, TeamRole::Other("billing".to_string()));
+    assert_eq!(serde_json::to_string(&role).unwrap(), r#""billing""#);
+
+    let known: BlacklistType = serde_json::from_str(r#""discord""#).expect("type should parse");
+    assert_eq!(known, BlacklistType::Discord);
+
+    let tokens: Vec<ApiToken> = serde_json::from_str(
+        r#"[{"id":"t-1","replay_protection":"strict"},{"id":"t-2","replay_protection":"window"}]"#,
+    )
+    .expect("tokens with a new replay mode should still parse");
+    assert_eq!(
+        tokens[1].replay_protection,
+        Some(ReplayProtectionMode::Other("window".to_string()))
+    );
+}
+
+#[test]
+fn items_reads_known_envelopes_only() {
+    let response = |data: serde_json::Value| nebulauth_sdk::DashboardResponse {
+        status_code: 200,
+        ok: true,
+        data,
+        headers: Default::default(),
+    };
+
+    let keys: Vec<serde_json::Value> = response(serde_json::json!({"keys": [{"id": "k-1"}]}))
+        .items()
+        .expect("keys envelope should decode");
+    assert_eq!(keys.len(), 1);
+
+    let err = response(serde_json::json!({"warnings": ["slow"], "total": 1}))
+        .items::<serde_json::Value>()
+        .expect_err("unknown envelope should not decode");
+    assert!(matches!(err, nebulauth_sdk::NebulAuthError::Decode(_)));
+}
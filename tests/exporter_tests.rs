@@ -0,0 +1,117 @@
+#![cfg(feature = "exporter")]
+
+use mockito::{Server, ServerGuard};
+use nebulauth_sdk::analytics::AnalyticsRange;
+use nebulauth_sdk::exporter::{self, ServiceMetrics};
+use nebulauth_sdk::{
+    DashboardAuth, DashboardRequestOptions, NebulAuthDashboardClient,
+    NebulAuthDashboardClientOptions,
+};
+
+fn client_for(server: &ServerGuard) -> NebulAuthDashboardClient {
+    NebulAuthDashboardClient::new(NebulAuthDashboardClientOptions {
+        base_url: format!("{}/dashboard", server.url()),
+        auth: Some(DashboardAuth::Bearer {
+            bearer_token: "mk_at_test".to_string(),
+        }),
+        ..Default::default()
+    })
+    .expect("client init should succeed")
+}
+
+async fn mock_get(server: &mut ServerGuard, path: &str, body: &str) -> mockito::Mock {
+    server
+        .mock("GET", format!("/dashboard{path}").as_str())
+        .match_query(mockito::Matcher::Any)
+        .with_status(200)
+        .with_body(body)
+        .create_async()
+        .await
+}
+
+#[tokio::test]
+async fn refresh_collects_counts_and_renders_prometheus_text() {
+    let mut server = Server::new_async().await;
+
+    let _summary = mock_get(
+        &mut server,
+        "/analytics/summary",
+        r#"{"activeKeys":2,"failedVerifications":5}"#,
+    )
+    .await;
+    let _geo = mock_get(&mut server, "/analytics/geo", r#"{"US":7,"DE":1}"#).await;
+    let _keys = mock_get(
+        &mut server,
+        "/keys",
+        r#"[{"id":"k-1"},{"id":"k-2"},{"id":"k-3","status":"revoked"}]"#,
+    )
+    .await;
+    let _sessions = mock_get(&mut server, "/key-sessions", r#"[{"id":"s-1"}]"#).await;
+    let _blacklist = mock_get(
+        &mut server,
+        "/blacklist",
+        r#"[{"id":"b-1","type":"ip","value":"1.1.1.1"},{"id":"b-2","type":"ip","value":"2.2.2.2"}]"#,
+    )
+    .await;
+
+    let mut metrics = ServiceMetrics::new("shop \"eu\"");
+    metrics
+        .refresh(
+            &client_for(&server),
+            AnalyticsRange::days(1).unwrap(),
+            DashboardRequestOptions::default(),
+        )
+        .await
+        .expect("refresh should succeed");
+
+    let text = exporter::render(&[metrics]);
+    for line in [
+        "# TYPE nebulauth_up gauge",
+        r#"nebulauth_up{service="shop \"eu\""} 1"#,
+        r#"nebulauth_keys{service="shop \"eu\"",status="active"} 2"#,
+        r#"nebulauth_keys{service="shop \"eu\"",status="revoked"} 1"#,
+        r#"nebulauth_key_sessions{service="shop \"eu\""} 1"#,
+        r#"nebulauth_blacklist_entries{service="shop \"eu\"",type="ip"} 2"#,
+        r#"nebulauth_analytics_country_count{service="shop \"eu\"",country="US"} 7"#,
+        r#"nebulauth_analytics_active_keys{service="shop \"eu\""} 2"#,
+        r#"nebulauth_analytics_failed_verifications{service="shop \"eu\""} 5"#,
+    ] {
+        assert!(
+            text.lines().any(|l| l == line),
+            "missing {line} in:\n{text}"
+        );
+    }
+}
+
+#[tokio::test]
+async fn failed_refresh_marks_service_down_and_keeps_last_values() {
+    let mut server = Server::new_async().await;
+
+    let _summary = server
+        .mock("GET", "/dashboard/analytics/summary")
+        .match_query(mockito::Matcher::Any)
+        .with_status(500)
+        .with_body(r#"{"error":"boom"}"#)
+        .create_async()
+        .await;
+
+    let mut metrics = ServiceMetrics {
+        up: true,
+        active_keys: 4,
+        refreshed_at_ms: 1_000,
+        ..ServiceMetrics::new("default")
+    };
+    let result = metrics
+        .refresh(
+            &client_for(&server),
+            AnalyticsRange::days(1).unwrap(),
+            DashboardRequestOptions::default(),
+        )
+        .await;
+
+    assert!(result.is_err());
+    let text = exporter::render(&[metrics]);
+    assert!(text.contains("nebulauth_up{service=\"default\"} 0\n"));
+    assert!(text.contains("nebulauth_refresh_errors_total{service=\"default\"} 1\n"));
+    assert!(text.contains("nebulauth_keys{service=\"default\",status=\"active\"} 4\n"));
+}
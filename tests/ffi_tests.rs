@@ -0,0 +1,199 @@
+#![cfg(feature = "ffi")]
+
+use mockito::{Matcher, Server};
+use nebulauth_sdk::ffi::{
+    nebulauth_client_new, nebulauth_free_client, nebulauth_free_error, nebulauth_free_response,
+    nebulauth_reset_hwid, nebulauth_verify_key, Client, ClientConfig, Error, ErrorCode,
+    ReplayProtection, Response,
+};
+use std::ffi::{CStr, CString};
+use std::ptr;
+
+fn empty_response() -> Response {
+    Response {
+        status_code: 0,
+        ok: false,
+        body: ptr::null_mut(),
+    }
+}
+
+fn empty_error() -> Error {
+    Error {
+        code: ErrorCode::Ok,
+        status_code: 0,
+        message: ptr::null_mut(),
+    }
+}
+
+fn new_client(base_url: &str, replay_protection: ReplayProtection) -> *mut Client {
+    let base_url = CString::new(base_url).unwrap();
+    let bearer_token = CString::new("mk_at_test").unwrap();
+    let signing_secret = CString::new("mk_sig_test").unwrap();
+    let config = ClientConfig {
+        base_url: base_url.as_ptr(),
+        bearer_token: bearer_token.as_ptr(),
+        signing_secret: signing_secret.as_ptr(),
+        service_slug: ptr::null(),
+        replay_protection: replay_protection as u32,
+        timeout_ms: 0,
+    };
+    let mut client = ptr::null_mut();
+    let mut error = empty_error();
+    let code = unsafe { nebulauth_client_new(&config, &mut client, &mut error) };
+    assert_eq!(code, ErrorCode::Ok);
+    assert!(!client.is_null());
+    assert!(error.message.is_null());
+    client
+}
+
+#[test]
+fn verify_key_blocks_and_returns_signed_response() {
+    let mut server = Server::new();
+    let mock = server
+        .mock("POST", "/api/v1/keys/verify")
+        .match_header("authorization", "Bearer mk_at_test")
+        .match_header("x-hwid", "HWID-1")
+        .match_header("x-signature", Matcher::Regex("^[0-9a-f]{64}$".to_string()))
+        .match_header("x-body-sha256", Matcher::Any)
+        .match_body(Matcher::JsonString(
+            r#"{"key":"mk_live_test","requestId":"req-1"}"#.to_string(),
+        ))
+        .with_status(200)
+        .with_body(r#"{"valid":true}"#)
+        .create();
+
+    let client = new_client(
+        &format!("{}/api/v1", server.url()),
+        ReplayProtection::Strict,
+    );
+    let key = CString::new("mk_live_test").unwrap();
+    let hwid = CString::new("HWID-1").unwrap();
+    let request_id = CString::new("req-1").unwrap();
+    let mut response = empty_response();
+    let mut error = empty_error();
+
+    let code = unsafe {
+        nebulauth_verify_key(
+            client,
+            key.as_ptr(),
+            hwid.as_ptr(),
+            request_id.as_ptr(),
+            &mut response,
+            &mut error,
+        )
+    };
+
+    assert_eq!(code, ErrorCode::Ok);
+    assert_eq!(response.status_code, 200);
+    assert!(response.ok);
+    let body = unsafe { CStr::from_ptr(response.body) }.to_str().unwrap();
+    assert_eq!(body, r#"{"valid":true}"#);
+    mock.assert();
+
+    unsafe {
+        nebulauth_free_response(&mut response);
+        assert!(response.body.is_null());
+        nebulauth_free_response(&mut response);
+        nebulauth_free_client(client);
+    }
+}
+
+#[test]
+fn failures_return_codes_and_messages() {
+    let client = new_client("http://127.0.0.1:1/api/v1", ReplayProtection::None);
+    let mut response = empty_response();
+    let mut error = empty_error();
+
+    let code = unsafe {
+        nebulauth_verify_key(
+            client,
+            ptr::null(),
+            ptr::null(),
+            ptr::null(),
+            &mut response,
+            &mut error,
+        )
+    };
+    assert_eq!(code, ErrorCode::InvalidArgument);
+    assert_eq!(error.code, ErrorCode::InvalidArgument);
+    let message = unsafe { CStr::from_ptr(error.message) }.to_str().unwrap();
+    assert_eq!(message, "key is null");
+    unsafe { nebulauth_free_error(&mut error) };
+    assert!(error.message.is_null());
+
+    let code = unsafe {
+        nebulauth_reset_hwid(
+            client,
+            ptr::null(),
+            ptr::null(),
+            ptr::null(),
+            &mut response,
+            &mut error,
+        )
+    };
+    assert_eq!(code, ErrorCode::Config);
+    let message = unsafe { CStr::from_ptr(error.message) }.to_str().unwrap();
+    assert!(message.contains("discord_id or key"));
+    unsafe { nebulauth_free_error(&mut error) };
+
+    let discord_id = CString::new("123").unwrap();
+    let code = unsafe {
+        nebulauth_reset_hwid(
+            client,
+            discord_id.as_ptr(),
+            ptr::null(),
+            ptr::null(),
+            &mut response,
+            ptr::null_mut(),
+        )
+    };
+    assert_eq!(code, ErrorCode::Request);
+    assert!(response.body.is_null());
+
+    unsafe {
+        nebulauth_free_error(ptr::null_mut());
+        nebulauth_free_response(ptr::null_mut());
+        nebulauth_free_client(client);
+        nebulauth_free_client(ptr::null_mut());
+    }
+}
+
+#[test]
+fn invalid_config_is_rejected() {
+    let config = ClientConfig {
+        base_url: ptr::null(),
+        bearer_token: ptr::null(),
+        signing_secret: ptr::null(),
+        service_slug: ptr::null(),
+        replay_protection: 7,
+        timeout_ms: 0,
+    };
+    let mut client = ptr::null_mut();
+    let mut error = empty_error();
+
+    let code = unsafe { nebulauth_client_new(&config, &mut client, &mut error) };
+
+    assert_eq!(code, ErrorCode::InvalidArgument);
+    assert!(client.is_null());
+    let message = unsafe { CStr::from_ptr(error.message) }.to_str().unwrap();
+    assert_eq!(message, "unknown replay_protection value 7");
+    unsafe { nebulauth_free_error(&mut error) };
+}
+
+/// Set to `1` to copy the header generated by `build.rs` over `include/nebulauth.h`.
+const UPDATE_HEADER_ENV: &str = "NEBULAUTH_UPDATE_HEADER";
+
+#[test]
+fn checked_in_header_matches_generated_one() {
+    let generated = include_str!(concat!(env!("OUT_DIR"), "/nebulauth.h"));
+    let path = concat!(env!("CARGO_MANIFEST_DIR"), "/include/nebulauth.h");
+    if std::env::var(UPDATE_HEADER_ENV).as_deref() == Ok("1") {
+        std::fs::write(path, generated).expect("include/nebulauth.h should be writable");
+    }
+
+    let checked_in = std::fs::read_to_string(path).expect("include/nebulauth.h should exist");
+    assert!(
+        checked_in == generated,
+        "include/nebulauth.h is out of date; run `{UPDATE_HEADER_ENV}=1 cargo test --features ffi --test ffi_tests`"
+    );
+}
@@ -0,0 +1,191 @@
+#![cfg(feature = "dashboard")]
+
+use mockito::{Matcher, Server, ServerGuard};
+use nebulauth_sdk::import::{self, ImportMapping, ImportOptions, ImportOutcome};
+use nebulauth_sdk::{
+    DashboardAuth, DashboardRequestOptions, NebulAuthDashboardClient,
+    NebulAuthDashboardClientOptions,
+};
+use serde_json::json;
+
+const EXPORT: &str = "\
+license_id,name,plan,expires
+old-1,Alice,pro,2999-01-01T00:00:00Z
+old-2,Bob,basic,2000-01-01T00:00:00Z
+old-3,Carol,pro,
+,Nobody,basic,
+old-3,Carol again,pro,
+";
+
+fn client_for(server: &ServerGuard) -> NebulAuthDashboardClient {
+    NebulAuthDashboardClient::new(NebulAuthDashboardClientOptions {
+        base_url: format!("{}/dashboard", server.url()),
+        auth: Some(DashboardAuth::Bearer {
+            bearer_token: "mk_at_test".to_string(),
+        }),
+        ..Default::default()
+    })
+    .expect("client init should succeed")
+}
+
+fn mapping() -> ImportMapping {
+    ImportMapping::from_json_str(
+        r#"{
+            "source": "keyvault",
+            "id": "license_id",
+            "label": "name",
+            "expires_at": "expires",
+            "metadata": { "tier": "plan" }
+        }"#,
+    )
+    .expect("mapping should parse")
+}
+
+#[tokio::test]
+async fn import_skips_already_imported_and_reports_invalid_rows() {
+    let mut server = Server::new_async().await;
+
+    let _list = server
+        .mock("GET", "/dashboard/keys")
+        .with_status(200)
+        .with_body(
+            r#"[
+                {"id":"k-1","metadata":{"imported_from":"old-1","import_source":"keyvault"}},
+                {"id":"k-2","metadata":{"imported_from":"old-3","import_source":"elsewhere"}}
+            ]"#,
+        )
+        .create_async()
+        .await;
+    let create = server
+        .mock("POST", "/dashboard/keys")
+        .match_body(Matcher::Json(json!({
+            "label": "Carol",
+            "metadata": { "tier": "pro", "imported_from": "old-3", "import_source": "keyvault" }
+        })))
+        .with_status(201)
+        .with_body(r#"{"key":{"id":"k-9"}}"#)
+        .expect(1)
+        .create_async()
+        .await;
+
+    let records = import::parse_csv_records(EXPORT.as_bytes()).expect("csv should parse");
+    let report = import::import_keys(
+        &client_for(&server),
+        &records,
+        &mapping(),
+        ImportOptions {
+            requests_per_second: 0,
+            ..Default::default()
+        },
+        DashboardRequestOptions::default(),
+    )
+    .await
+    .expect("import should succeed");
+
+    let summary: Vec<String> = report.items.iter().map(ToString::to_string).collect();
+    assert_eq!(
+        summary,
+        vec![
+            "row 1 (old-1): already imported -> k-1",
+            "row 2 (old-2): invalid (expired at 2000-01-01T00:00:00Z)",
+            "row 3 (old-3): created -> k-9",
+            "row 4: invalid (missing value for id column 'license_id')",
+            "row 5 (old-3): invalid (duplicate id in input)",
+        ]
+    );
+    assert_eq!(
+        report.to_string(),
+        "5 rows: 1 created, 1 already imported, 3 invalid, 0 failed"
+    );
+    create.assert_async().await;
+}
+
+#[tokio::test]
+async fn dry_run_reports_planned_creates_without_writing() {
+    let mut server = Server::new_async().await;
+
+    let _list = server
+        .mock("GET", "/dashboard/keys")
+        .with_status(200)
+        .with_body("[]")
+        .create_async()
+        .await;
+    let writes = server
+        .mock("POST", "/dashboard/keys")
+        .expect(0)
+        .create_async()
+        .await;
+
+    let records =
+        import::parse_json_records(r#"[{"license_id": 42, "name": "Dana", "duration": 720}]"#)
+            .expect("json should parse");
+    let report = import::import_keys(
+        &client_for(&server),
+        &records,
+        &ImportMapping {
+            id: "license_id".to_string(),
+            duration_hours: Some("duration".to_string()),
+            ..Default::default()
+        },
+        ImportOptions {
+            dry_run: true,
+            ..Default::default()
+        },
+        DashboardRequestOptions::default(),
+    )
+    .await
+    .expect("dry run should succeed");
+
+    assert_eq!(report.items[0].source_id.as_deref(), Some("42"));
+    assert_eq!(report.items[0].outcome, ImportOutcome::WouldCreate);
+    assert_eq!(
+        report.to_string(),
+        "1 rows: 1 to create, 0 already imported, 0 invalid, 0 failed"
+    );
+    writes.assert_async().await;
+}
+
+#[tokio::test]
+async fn rate_limited_create_is_retried_after_retry_after() {
+    let mut server = Server::new_async().await;
+
+    let _list = server
+        .mock("GET", "/dashboard/keys")
+        .with_status(200)
+        .with_body("[]")
+        .create_async()
+        .await;
+    let limited = server
+        .mock("POST", "/dashboard/keys")
+        .with_status(429)
+        .with_header("retry-after", "0")
+        .with_body(r#"{"error":"slow down"}"#)
+        .expect(1)
+        .create_async()
+        .await;
+
+    let created = server
+        .mock("POST", "/dashboard/keys")
+        .with_status(201)
+        .with_body(r#"{"id":"k-1"}"#)
+        .create_async()
+        .await;
+
+    let records = import::parse_csv_records("license_id\nold-1\n".as_bytes()).unwrap();
+    let report = import::import_keys(
+        &client_for(&server),
+        &records,
+        &mapping(),
+        ImportOptions {
+            rate_limit_retries: 1,
+            ..Default::default()
+        },
+        DashboardRequestOptions::default(),
+    )
+    .await
+    .expect("import should succeed");
+
+    assert_eq!(report.created().count(), 1);
+    limited.assert_async().await;
+    created.assert_async().await;
+}
@@ -1,3 +1,5 @@
+#![cfg(all(feature = "runtime", feature = "dashboard"))]
+
 use nebulauth_sdk::{
     DashboardAuth, DashboardRequestOptions, NebulAuthClient, NebulAuthClientOptions,
     NebulAuthDashboardClient, NebulAuthDashboardClientOptions, ReplayProtectionMode,
@@ -48,6 +50,7 @@ async fn verify_key_live_env_gated() {
             ReplayProtectionMode::None
         },
         timeout_ms: 15_000,
+        ..Default::default()
     })
     .expect("client init should succeed");
 
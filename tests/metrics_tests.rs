@@ -0,0 +1,178 @@
+#![cfg(all(feature = "metrics", feature = "runtime", feature = "dashboard"))]
+
+use metrics_util::debugging::{DebugValue, DebuggingRecorder, Snapshotter};
+use mockito::Server;
+use nebulauth_sdk::{
+    DashboardAuth, DashboardRequestOptions, LoginRequest, NebulAuthClient, NebulAuthClientOptions,
+    NebulAuthDashboardClient, NebulAuthDashboardClientOptions, VerifyKeyInput,
+};
+use std::future::Future;
+
+fn record<F: Future>(future: F) -> Snapshotter {
+    let recorder = DebuggingRecorder::new();
+    let snapshotter = recorder.snapshotter();
+    metrics::with_local_recorder(&recorder, || {
+        tokio::runtime::Builder::new_current_thread()
+            .enable_all()
+            .build()
+            .unwrap()
+            .block_on(future)
+    });
+    snapshotter
+}
+
+/// `name{label=value,...}` for every recorded series, labels sorted.
+fn series(snapshotter: &Snapshotter) -> Vec<(String, DebugValue)> {
+    let mut series: Vec<_> = snapshotter
+        .snapshot()
+        .into_vec()
+        .into_iter()
+        .map(|(key, _, _, value)| {
+            let mut labels: Vec<String> = key
+                .key()
+                .labels()
+                .map(|label| format!("{}={}", label.key(), label.value()))
+                .collect();
+            labels.sort();
+            (
+                format!("{}{{{}}}", key.key().name(), labels.join(",")),
+                value,
+            )
+        })
+        .collect();
+    series.sort_by(|a, b| a.0.cmp(&b.0));
+    series
+}
+
+fn counter(series: &[(String, DebugValue)], name: &str) -> Option<u64> {
+    series.iter().find_map(|(key, value)| match value {
+        DebugValue::Counter(count) if key == name => Some(*count),
+        _ => None,
+    })
+}
+
+#[test]
+fn runtime_requests_are_counted_by_endpoint_and_status_class() {
+    let mut server = Server::new();
+    let _ok = server
+        .mock("POST", "/api/v1/keys/verify")
+        .with_status(200)
+        .with_body(r#"{"valid":true}"#)
+        .expect(2)
+        .create();
+    let base_url = format!("{}/api/v1", server.url());
+
+    let snapshotter = record(async move {
+        let client = NebulAuthClient::new(NebulAuthClientOptions {
+            base_url,
+            bearer_token: Some("mk_at_test".to_string()),
+            signing_secret: Some("mk_sig_test".to_string()),
+            ..Default::default()
+        })
+        .unwrap();
+        for _ in 0..2 {
+            client
+                .verify_key(VerifyKeyInput {
+                    key: "mk_live_test".to_string(),
+                    ..Default::default()
+                })
+                .await
+                .unwrap();
+        }
+    });
+
+    let series = series(&snapshotter);
+    assert_eq!(
+        counter(
+            &series,
+            "nebulauth_client_requests_total{client=runtime,endpoint=/keys/verify,method=POST,status_class=2xx}"
+        ),
+        Some(2)
+    );
+    let histogram = series.iter().find_map(|(key, value)| match value {
+        DebugValue::Histogram(values)
+            if key
+                == "nebulauth_client_request_duration_seconds{client=runtime,endpoint=/keys/verify,method=POST}" =>
+        {
+            Some(values.len())
+        }
+        _ => None,
+    });
+    assert_eq!(histogram, Some(2));
+}
+
+#[test]
+fn dashboard_records_relogin_retry_and_session_cache() {
+    let mut server = Server::new();
+    let _expired = server
+        .mock("GET", "/dashboard/keys/key_123")
+        .match_header("cookie", "mc_session=sess-old")
+        .with_status(401)
+        .create();
+    let _login = server
+        .mock("POST", "/dashboard/auth/login")
+        .with_status(200)
+        .with_header("set-cookie", "mc_session=sess-fresh; Path=/")
+        .with_body("{}")
+        .create();
+    let _fresh = server
+        .mock("GET", "/dashboard/keys/key_123")
+        .match_header("cookie", "mc_session=sess-fresh")
+        .with_status(200)
+        .with_body(r#"{"id":"key_123"}"#)
+        .expect(2)
+        .create();
+    let base_url = format!("{}/dashboard", server.url());
+
+    let snapshotter = record(async move {
+        let client = NebulAuthDashboardClient::new(NebulAuthDashboardClientOptions {
+            base_url,
+            auth: Some(DashboardAuth::Session {
+                session_cookie: "sess-old".to_string(),
+            }),
+            credentials: Some(LoginRequest {
+                email: "ops@example.com".to_string(),
+                password: "hunter2".to_string(),
+            }),
+            ..Default::default()
+        })
+        .unwrap();
+        for _ in 0..2 {
+            client
+                .get_key("key_123", DashboardRequestOptions::default())
+                .await
+                .unwrap();
+        }
+    });
+
+    let series = series(&snapshotter);
+    assert_eq!(
+        counter(
+            &series,
+            "nebulauth_client_requests_total{client=dashboard,endpoint=/keys/:id,method=GET,status_class=2xx}"
+        ),
+        Some(2)
+    );
+    assert_eq!(
+        counter(
+            &series,
+            "nebulauth_client_retries_total{client=dashboard,endpoint=/keys/:id,method=GET}"
+        ),
+        Some(1)
+    );
+    assert_eq!(
+        counter(
+            &series,
+            "nebulauth_client_session_cache_total{client=dashboard,result=hit}"
+        ),
+        Some(1)
+    );
+    assert_eq!(
+        counter(
+            &series,
+            "nebulauth_client_session_cache_total{client=dashboard,result=miss}"
+        ),
+        Some(1)
+    );
+    assert!(series.iter().all(|(key, _)| !key.contains("key_123")));
+}
@@ -0,0 +1,98 @@
+#![cfg(feature = "mock")]
+
+use nebulauth_sdk::mock::{self, MockCall, MockLicenseVerifier, MockOperation};
+use nebulauth_sdk::verifier::LicenseVerifier;
+use nebulauth_sdk::{NebulAuthError, RedeemKeyInput, ResetHwidInput, VerifyKeyInput};
+use serde_json::json;
+
+async fn unlock(verifier: &dyn LicenseVerifier, key: &str, hwid: &str) -> bool {
+    let response = verifier
+        .verify_key(VerifyKeyInput {
+            key: key.to_string(),
+            hwid: Some(hwid.to_string()),
+            ..Default::default()
+        })
+        .await;
+    matches!(response, Ok(response) if response.ok && response.data["valid"] == true)
+}
+
+#[tokio::test]
+async fn queued_responses_are_returned_in_order_and_calls_recorded() {
+    let verifier = MockLicenseVerifier::new();
+    verifier
+        .push(
+            MockOperation::VerifyKey,
+            Ok(mock::response(200, json!({ "valid": true }))),
+        )
+        .push(
+            MockOperation::VerifyKey,
+            Ok(mock::response(403, json!({ "error": "HWID mismatch" }))),
+        );
+
+    assert!(unlock(&verifier, "mk_live_a", "HWID-1").await);
+    assert!(!unlock(&verifier, "mk_live_a", "HWID-2").await);
+
+    verifier.assert_called(MockOperation::VerifyKey, 2);
+    verifier.assert_called(MockOperation::RedeemKey, 0);
+    verifier.assert_drained();
+    let calls = verifier.calls();
+    assert_eq!(calls[1].key(), Some("mk_live_a"));
+    assert!(
+        matches!(&calls[1], MockCall::VerifyKey(input) if input.hwid.as_deref() == Some("HWID-2"))
+    );
+}
+
+#[tokio::test]
+async fn responder_answers_once_queue_is_empty() {
+    let verifier = MockLicenseVerifier::new();
+    verifier
+        .push(
+            MockOperation::RedeemKey,
+            Err(NebulAuthError::Api {
+                status_code: 409,
+                message: "already redeemed".to_string(),
+            }),
+        )
+        .respond_with(MockOperation::RedeemKey, |call| {
+            let MockCall::RedeemKey(input) = call else {
+                unreachable!()
+            };
+            Ok(mock::response(
+                200,
+                json!({ "discordId": input.discord_id }),
+            ))
+        });
+
+    let redeem = || {
+        verifier.redeem_key(RedeemKeyInput {
+            key: "mk_live_a".to_string(),
+            discord_id: "123".to_string(),
+            ..Default::default()
+        })
+    };
+    assert!(matches!(
+        redeem().await,
+        Err(NebulAuthError::Api {
+            status_code: 409,
+            ..
+        })
+    ));
+    let response = redeem().await.expect("responder should answer");
+    assert_eq!(response.data["discordId"], "123");
+    verifier.assert_called(MockOperation::RedeemKey, 2);
+}
+
+#[tokio::test]
+async fn unprogrammed_operation_fails() {
+    let verifier = MockLicenseVerifier::new();
+
+    let result = verifier
+        .reset_hwid(ResetHwidInput {
+            discord_id: Some("123".to_string()),
+            ..Default::default()
+        })
+        .await;
+
+    assert!(matches!(result, Err(NebulAuthError::Config(_))));
+    verifier.assert_called(MockOperation::ResetHwid, 1);
+}
@@ -0,0 +1,125 @@
+// Tests for the Node.js bindings. Run from nebulauth-node/ after `npm run build:debug`:
+//
+//     npm test
+
+const assert = require('node:assert/strict')
+const crypto = require('node:crypto')
+const http = require('node:http')
+const { after, before, beforeEach, describe, test } = require('node:test')
+
+const nebulauth = require('../../nebulauth-node')
+
+function startServer() {
+  const server = http.createServer((req, res) => {
+    let body = ''
+    req.on('data', (chunk) => (body += chunk))
+    req.on('end', () => {
+      server.requests.push({ method: req.method, path: req.url, headers: req.headers, body })
+      const [status, payload] = server.responses.shift()
+      const data = JSON.stringify(payload)
+      res.writeHead(status, { 'content-type': 'application/json', 'content-length': Buffer.byteLength(data) })
+      res.end(data)
+    })
+  })
+  server.requests = []
+  server.responses = []
+  return new Promise((resolve) => server.listen(0, '127.0.0.1', () => resolve(server)))
+}
+
+let server
+let url
+
+before(async () => {
+  server = await startServer()
+  url = `http://127.0.0.1:${server.address().port}`
+})
+
+after(() => server.close())
+
+beforeEach(() => {
+  server.requests = []
+  server.responses = []
+})
+
+describe('NebulAuthClient', () => {
+  test('verifyKey signs the request', async () => {
+    server.responses.push([200, { valid: true }])
+    const client = new nebulauth.NebulAuthClient({
+      baseUrl: `${url}/api/v1`,
+      bearerToken: 'mk_at_test',
+      signingSecret: 'mk_sig_test',
+    })
+
+    const response = await client.verifyKey({ key: 'mk_live_test', hwid: 'HWID-1', requestId: 'req-1' })
+
+    assert.equal(response.statusCode, 200)
+    assert.equal(response.ok, true)
+    assert.deepEqual(response.data, { valid: true })
+    const { path, headers, body } = server.requests[0]
+    assert.equal(path, '/api/v1/keys/verify')
+    assert.equal(headers.authorization, 'Bearer mk_at_test')
+    assert.equal(headers['x-hwid'], 'HWID-1')
+    const bodyHash = crypto.createHash('sha256').update(body).digest('hex')
+    assert.equal(headers['x-body-sha256'], bodyHash)
+    const canonical = ['POST', '/keys/verify', headers['x-timestamp'], headers['x-nonce'], bodyHash].join('\n')
+    const signature = crypto.createHmac('sha256', 'mk_sig_test').update(canonical).digest('hex')
+    assert.equal(headers['x-signature'], signature)
+  })
+
+  test('failures reject with typed errors', async () => {
+    assert.throws(
+      () => new nebulauth.NebulAuthClient({ replayProtection: 'sometimes' }),
+      (error) => nebulauth.isNebulAuthError(error) && error.code === 'ConfigError',
+    )
+
+    const client = new nebulauth.NebulAuthClient({ baseUrl: `${url}/api/v1`, bearerToken: 't' })
+    await assert.rejects(client.resetHwid({}), (error) => {
+      assert.ok(error instanceof Error)
+      assert.equal(error.name, 'NebulAuthError')
+      assert.equal(error.code, 'ConfigError')
+      assert.equal(error.statusCode, undefined)
+      return true
+    })
+
+    const unreachable = new nebulauth.NebulAuthClient({
+      baseUrl: 'http://127.0.0.1:1/api/v1',
+      bearerToken: 't',
+      replayProtection: 'none',
+    })
+    await assert.rejects(unreachable.verifyKey({ key: 'mk_live_test' }), { code: 'RequestError' })
+    assert.equal(nebulauth.isNebulAuthError(new Error('other')), false)
+  })
+})
+
+describe('NebulAuthDashboardClient', () => {
+  test('createKey sends the typed payload', async () => {
+    server.responses.push([201, { id: 'key_1', label: 'Promo' }])
+    const client = new nebulauth.NebulAuthDashboardClient({ baseUrl: `${url}/dashboard`, bearerToken: 'mk_at_admin' })
+
+    const response = await client.createKey({ label: 'Promo', metadata: { tier: 'gold' } })
+
+    assert.equal(response.data.id, 'key_1')
+    assert.equal(server.requests[0].path, '/dashboard/keys')
+    assert.deepEqual(JSON.parse(server.requests[0].body), { label: 'Promo', metadata: { tier: 'gold' } })
+  })
+
+  test('listKeys passes the query and checkStatus throws ApiError', async () => {
+    server.responses.push([200, { data: [{ id: 'key_1' }] }])
+    server.responses.push([404, { error: 'key not found' }])
+    const client = new nebulauth.NebulAuthDashboardClient({ baseUrl: `${url}/dashboard`, bearerToken: 'mk_at_admin' })
+
+    const keys = await client.listKeys({ status: 'active' })
+    assert.equal(keys.data.data[0].id, 'key_1')
+    assert.equal(server.requests[0].path, '/dashboard/keys?status=active')
+
+    const missing = await client.getKey('missing')
+    assert.equal(missing.ok, false)
+    assert.throws(() => nebulauth.checkStatus(missing), (error) => {
+      assert.ok(nebulauth.isNebulAuthError(error))
+      assert.equal(error.code, 'ApiError')
+      assert.equal(error.statusCode, 404)
+      assert.match(error.message, /key not found/)
+      return true
+    })
+  })
+})
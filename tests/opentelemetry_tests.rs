@@ -0,0 +1,134 @@
+#![cfg(all(feature = "opentelemetry", feature = "runtime", feature = "dashboard"))]
+
+use mockito::{Matcher, Server};
+use nebulauth_sdk::{
+    DashboardAuth, DashboardRequestOptions, NebulAuthClient, NebulAuthClientOptions,
+    NebulAuthDashboardClient, NebulAuthDashboardClientOptions, VerifyKeyInput,
+};
+use opentelemetry::trace::{
+    SpanContext, SpanId, TraceContextExt, TraceFlags, TraceId, TraceState, TracerProvider,
+};
+use opentelemetry::Context;
+use opentelemetry_sdk::trace::SdkTracerProvider;
+use tracing::Instrument;
+use tracing_opentelemetry::OpenTelemetrySpanExt;
+use tracing_subscriber::layer::SubscriberExt;
+
+const TRACE_ID: &str = "4bf92f3577b34da6a3ce929d0e0e4736";
+
+fn install() -> tracing::subscriber::DefaultGuard {
+    let provider = SdkTracerProvider::builder().build();
+    let subscriber = tracing_subscriber::registry()
+        .with(tracing_opentelemetry::layer().with_tracer(provider.tracer("nebulauth-tests")));
+    tracing::subscriber::set_default(subscriber)
+}
+
+/// A span continuing a remote trace, as an HTTP server middleware would create it.
+fn caller_span() -> tracing::Span {
+    let span = tracing::info_span!("checkout");
+    let remote = SpanContext::new(
+        TraceId::from_hex(TRACE_ID).unwrap(),
+        SpanId::from_hex("00f067aa0ba902b7").unwrap(),
+        TraceFlags::SAMPLED,
+        true,
+        TraceState::from_key_value([("vendor", "abc")]).unwrap(),
+    );
+    let _ = span.set_parent(Context::new().with_remote_span_context(remote));
+    span
+}
+
+fn traceparent() -> Matcher {
+    Matcher::Regex(format!("^00-{TRACE_ID}-[0-9a-f]{{16}}-01$"))
+}
+
+#[tokio::test]
+async fn runtime_request_carries_trace_context() {
+    let mut server = Server::new_async().await;
+    let mock = server
+        .mock("POST", "/api/v1/keys/verify")
+        .match_header("traceparent", traceparent())
+        .match_header("tracestate", "vendor=abc")
+        .with_status(200)
+        .with_body(r#"{"valid":true}"#)
+        .create_async()
+        .await;
+
+    let _guard = install();
+    let client = NebulAuthClient::new(NebulAuthClientOptions {
+        base_url: format!("{}/api/v1", server.url()),
+        bearer_token: Some("mk_at_test".to_string()),
+        signing_secret: Some("mk_sig_test".to_string()),
+        ..Default::default()
+    })
+    .unwrap();
+
+    let response = client
+        .verify_key(VerifyKeyInput {
+            key: "mk_live_test".to_string(),
+            ..Default::default()
+        })
+        .instrument(caller_span())
+        .await
+        .unwrap();
+
+    assert_eq!(response.status_code, 200);
+    mock.assert_async().await;
+}
+
+#[tokio::test]
+async fn dashboard_request_carries_trace_context() {
+    let mut server = Server::new_async().await;
+    let mock = server
+        .mock("GET", "/dashboard/keys")
+        .match_header("traceparent", traceparent())
+        .with_status(200)
+        .with_body("[]")
+        .create_async()
+        .await;
+
+    let _guard = install();
+    let client = NebulAuthDashboardClient::new(NebulAuthDashboardClientOptions {
+        base_url: format!("{}/dashboard", server.url()),
+        auth: Some(DashboardAuth::Bearer {
+            bearer_token: "mk_at_test".to_string(),
+        }),
+        ..Default::default()
+    })
+    .unwrap();
+
+    client
+        .list_keys(DashboardRequestOptions::default())
+        .instrument(caller_span())
+        .await
+        .unwrap();
+
+    mock.assert_async().await;
+}
+
+#[tokio::test]
+async fn no_headers_without_an_active_trace() {
+    let mut server = Server::new_async().await;
+    let mock = server
+        .mock("GET", "/dashboard/keys")
+        .match_header("traceparent", Matcher::Missing)
+        .with_status(200)
+        .with_body("[]")
+        .create_async()
+        .await;
+
+    let client = NebulAuthDashboardClient::new(NebulAuthDashboardClientOptions {
+        base_url: format!("{}/dashboard", server.url()),
+        auth: Some(DashboardAuth::Bearer {
+            bearer_token: "mk_at_test".to_string(),
+        }),
+        ..Default::default()
+    })
+    .unwrap();
+
+    client
+        .list_keys(DashboardRequestOptions::default())
+        .await
+        .unwrap();
+
+    mock.assert_async().await;
+}
@@ -0,0 +1,186 @@
+"""Tests for the Python bindings. Run after `maturin develop`:
+
+    python -m unittest discover tests/python
+"""
+
+import asyncio
+import hashlib
+import hmac
+import json
+import threading
+import unittest
+from http.server import BaseHTTPRequestHandler, HTTPServer
+
+import nebulauth
+
+
+class _Handler(BaseHTTPRequestHandler):
+    def do_POST(self):
+        self._handle("POST")
+
+    def do_GET(self):
+        self._handle("GET")
+
+    def _handle(self, method):
+        length = int(self.headers.get("content-length") or 0)
+        body = self.rfile.read(length).decode() if length else ""
+        self.server.requests.append(
+            {"method": method, "path": self.path, "headers": dict(self.headers), "body": body}
+        )
+        status, payload = self.server.responses.pop(0)
+        data = json.dumps(payload).encode()
+        self.send_response(status)
+        self.send_header("content-type", "application/json")
+        self.send_header("content-length", str(len(data)))
+        self.end_headers()
+        self.wfile.write(data)
+
+    def log_message(self, *args):
+        pass
+
+
+class _Server:
+    def __init__(self):
+        self.httpd = HTTPServer(("127.0.0.1", 0), _Handler)
+        self.httpd.requests = []
+        self.httpd.responses = []
+        threading.Thread(target=self.httpd.serve_forever, daemon=True).start()
+
+    @property
+    def url(self):
+        return f"http://127.0.0.1:{self.httpd.server_port}"
+
+    def respond(self, status, payload):
+        self.httpd.responses.append((status, payload))
+
+    @property
+    def requests(self):
+        return self.httpd.requests
+
+    def close(self):
+        self.httpd.shutdown()
+        self.httpd.server_close()
+
+
+class RuntimeClientTests(unittest.TestCase):
+    def setUp(self):
+        self.server = _Server()
+        self.client = nebulauth.NebulAuthClient(
+            base_url=f"{self.server.url}/api/v1",
+            bearer_token="mk_at_test",
+            signing_secret="mk_sig_test",
+        )
+
+    def tearDown(self):
+        self.server.close()
+
+    def test_verify_key_signs_request(self):
+        self.server.respond(200, {"valid": True})
+
+        response = self.client.verify_key("mk_live_test", hwid="HWID-1", request_id="req-1")
+
+        self.assertIsInstance(response, nebulauth.Response)
+        self.assertEqual(response.status_code, 200)
+        self.assertTrue(response.ok)
+        self.assertEqual(response.data, {"valid": True})
+        request = self.server.requests[0]
+        headers = {name.lower(): value for name, value in request["headers"].items()}
+        self.assertEqual(request["path"], "/api/v1/keys/verify")
+        self.assertEqual(headers["authorization"], "Bearer mk_at_test")
+        self.assertEqual(headers["x-hwid"], "HWID-1")
+        body_hash = hashlib.sha256(request["body"].encode()).hexdigest()
+        self.assertEqual(headers["x-body-sha256"], body_hash)
+        canonical = "\n".join(
+            ["POST", "/keys/verify", headers["x-timestamp"], headers["x-nonce"], body_hash]
+        )
+        expected = hmac.new(b"mk_sig_test", canonical.encode(), hashlib.sha256).hexdigest()
+        self.assertEqual(headers["x-signature"], expected)
+
+    def test_async_method_returns_awaitable(self):
+        self.server.respond(409, {"error": "already redeemed"})
+
+        async def redeem():
+            return await self.client.redeem_key_async(
+                "mk_live_test", "123", service_slug="my-app"
+            )
+
+        response = asyncio.run(redeem())
+
+        self.assertFalse(response.ok)
+        self.assertEqual(json.loads(self.server.requests[0]["body"])["serviceSlug"], "my-app")
+        with self.assertRaises(nebulauth.ApiError) as raised:
+            response.raise_for_status()
+        self.assertEqual(raised.exception.status_code, 409)
+        self.assertIn("already redeemed", str(raised.exception))
+
+    def test_errors_map_to_exceptions(self):
+        with self.assertRaises(nebulauth.ConfigError):
+            self.client.reset_hwid()
+        with self.assertRaises(nebulauth.ConfigError):
+            nebulauth.NebulAuthClient(replay_protection="sometimes")
+
+        unreachable = nebulauth.NebulAuthClient(
+            base_url="http://127.0.0.1:1/api/v1", bearer_token="t", replay_protection="none"
+        )
+        with self.assertRaises(nebulauth.RequestError) as raised:
+            unreachable.verify_key("mk_live_test")
+        self.assertIsInstance(raised.exception, nebulauth.NebulAuthError)
+
+
+class DashboardClientTests(unittest.TestCase):
+    def setUp(self):
+        self.server = _Server()
+        self.client = nebulauth.NebulAuthDashboardClient(
+            base_url=f"{self.server.url}/dashboard", bearer_token="mk_at_admin"
+        )
+
+    def tearDown(self):
+        self.server.close()
+
+    def test_create_key_sends_typed_payload(self):
+        self.server.respond(201, {"id": "key_1", "label": "Promo"})
+
+        response = self.client.create_key(label="Promo", metadata={"tier": "gold"})
+
+        self.assertEqual(response.to_dict()["data"]["id"], "key_1")
+        request = self.server.requests[0]
+        self.assertEqual(request["path"], "/dashboard/keys")
+        self.assertEqual(
+            json.loads(request["body"]), {"label": "Promo", "metadata": {"tier": "gold"}}
+        )
+
+    def test_async_list_keys_with_query(self):
+        self.server.respond(200, {"data": [{"id": "key_1"}]})
+
+        async def list_keys():
+            return await self.client.list_keys_async(query={"status": "active"})
+
+        response = asyncio.run(list_keys())
+
+        self.assertEqual(response.data["data"][0]["id"], "key_1")
+        self.assertEqual(self.server.requests[0]["path"], "/dashboard/keys?status=active")
+
+    def test_list_key_sessions(self):
+        self.server.respond(200, [{"id": "s-1", "keyId": "key_1"}])
+
+        response = self.client.list_key_sessions()
+
+        self.assertEqual(response.data[0]["id"], "s-1")
+        self.assertEqual(self.server.requests[0]["path"], "/dashboard/key-sessions")
+
+    def test_repr_redacts_secrets_and_truncates(self):
+        self.server.respond(
+            201, {"id": "key_1", "key": "mk_live_secret", "token": "mk_at_secret", "notes": "x" * 500}
+        )
+
+        text = repr(self.client.create_key(label="Promo"))
+
+        self.assertTrue(text.startswith("Response(status_code=201, ok=True, data="))
+        self.assertNotIn("mk_live_secret", text)
+        self.assertNotIn("mk_at_secret", text)
+        self.assertIn("...", text)
+        self.assertLess(len(text), 300)
+
+
+if __name__ == "__main__":
+    unittest.main()
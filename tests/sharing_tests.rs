@@ -0,0 +1,226 @@
+#![cfg(feature = "dashboard")]
+
+use chrono::{Duration, TimeZone, Utc};
+use mockito::{Matcher, Server, ServerGuard};
+use nebulauth_sdk::bulk::BulkOutcome;
+use nebulauth_sdk::sharing::{
+    self, EnforceOptions, EnforcementPolicy, SharingThresholds, Violation,
+};
+use nebulauth_sdk::{
+    DashboardAuth, DashboardRequestOptions, KeySession, NebulAuthDashboardClient,
+    NebulAuthDashboardClientOptions,
+};
+use serde_json::json;
+
+const SESSIONS: &str = r#"[
+    {"id":"s-1","keyId":"k-1","hwid":"H-1","ip":"10.0.0.1"},
+    {"id":"s-2","keyId":"k-1","hwid":"H-2","ip":"10.0.0.2"},
+    {"id":"s-3","keyId":"k-1","hwid":"H-3","ip":"10.0.0.2"},
+    {"id":"s-4","keyId":"k-2","hwid":"H-9","ip":"10.0.0.9"}
+]"#;
+
+fn client_for(server: &ServerGuard) -> NebulAuthDashboardClient {
+    NebulAuthDashboardClient::new(NebulAuthDashboardClientOptions {
+        base_url: format!("{}/dashboard", server.url()),
+        auth: Some(DashboardAuth::Bearer {
+            bearer_token: "mk_at_test".to_string(),
+        }),
+        ..Default::default()
+    })
+    .expect("client init should succeed")
+}
+
+fn thresholds() -> SharingThresholds {
+    SharingThresholds {
+        max_distinct_hwids: Some(2),
+        max_distinct_ips: Some(2),
+        ..Default::default()
+    }
+}
+
+#[test]
+fn analyze_counts_only_live_sessions_inside_the_window() {
+    let now = Utc.with_ymd_and_hms(2026, 5, 1, 12, 0, 0).unwrap();
+    let sessions: Vec<KeySession> = serde_json::from_value(json!([
+        { "id": "s-1", "key_id": "k-1", "hwid": "H-1", "last_seen_at": "2026-05-01T11:50:00Z" },
+        { "id": "s-2", "key_id": "k-1", "hwid": "H-2", "last_seen_at": "2026-05-01T11:40:00Z" },
+        { "id": "s-3", "key_id": "k-1", "hwid": "H-3", "last_seen_at": "2026-05-01T08:00:00Z" },
+        { "id": "s-4", "key_id": "k-1", "hwid": "H-4", "expires_at": "2026-05-01T11:00:00Z" },
+        { "id": "s-5", "hwid": "H-5" },
+        { "id": "s-6", "key_id": "k-1", "hwid": "H-1" }
+    ]))
+    .unwrap();
+
+    let limits = SharingThresholds {
+        window: Duration::minutes(30),
+        max_sessions_in_window: Some(1),
+        max_distinct_hwids: Some(2),
+        ..Default::default()
+    };
+    let findings = sharing::analyze(&sessions, &limits, now);
+
+    assert_eq!(findings.len(), 1);
+    let ids: Vec<&str> = findings[0].sessions.iter().map(|s| s.id.as_str()).collect();
+    assert_eq!(ids, vec!["s-2", "s-1", "s-6"]);
+    assert_eq!(
+        findings[0].violations,
+        vec![Violation::SessionsInWindow { count: 3, limit: 1 }]
+    );
+}
+
+#[tokio::test]
+async fn dry_run_writes_audit_log_without_revoking() {
+    let mut server = Server::new_async().await;
+
+    let _sessions = server
+        .mock("GET", "/dashboard/key-sessions")
+        .with_status(200)
+        .with_body(SESSIONS)
+        .create_async()
+        .await;
+    let revokes = server
+        .mock("DELETE", Matcher::Any)
+        .expect(0)
+        .create_async()
+        .await;
+
+    let audit_log = std::env::temp_dir().join(format!(
+        "nebulauth-sharing-{}-{}.jsonl",
+        std::process::id(),
+        server.socket_address().port()
+    ));
+    let _ = std::fs::remove_file(&audit_log);
+
+    let report = sharing::enforce(
+        &client_for(&server),
+        &thresholds(),
+        &EnforcementPolicy {
+            reset_hwid: true,
+            ..Default::default()
+        },
+        EnforceOptions {
+            dry_run: true,
+            audit_log: Some(audit_log.clone()),
+        },
+        DashboardRequestOptions::default(),
+    )
+    .await
+    .expect("enforce should succeed");
+
+    assert_eq!(report.findings.len(), 1);
+    assert_eq!(
+        report.findings[0].violations,
+        vec![Violation::DistinctHwids { count: 3, limit: 2 }]
+    );
+    let planned: Vec<&str> = report
+        .actions
+        .iter()
+        .filter(|action| action.outcome == BulkOutcome::Planned)
+        .map(|action| action.session_id.as_str())
+        .collect();
+    assert_eq!(planned, vec!["s-2", "s-3"]);
+
+    let lines: Vec<serde_json::Value> = std::fs::read_to_string(&audit_log)
+        .unwrap()
+        .lines()
+        .map(|line| serde_json::from_str(line).unwrap())
+        .collect();
+    assert_eq!(lines.len(), 2);
+    assert_eq!(lines[0]["dry_run"], true);
+    assert_eq!(lines[0]["key_id"], "k-1");
+    assert_eq!(lines[0]["request"], json!({ "reset_hwid": true }));
+    assert_eq!(lines[0]["outcome"], json!({ "status": "planned" }));
+    assert_eq!(lines[1]["request"], json!({}));
+    revokes.assert_async().await;
+    let _ = std::fs::remove_file(&audit_log);
+}
+
+#[tokio::test]
+async fn terminate_all_revokes_once_per_flagged_key() {
+    let mut server = Server::new_async().await;
+
+    let _sessions = server
+        .mock("GET", "/dashboard/key-sessions")
+        .with_status(200)
+        .with_body(SESSIONS)
+        .create_async()
+        .await;
+    let revoke = server
+        .mock("DELETE", "/dashboard/key-sessions/s-3")
+        .match_body(Matcher::Json(json!({
+            "reason": "key sharing",
+            "revoke_key": true,
+            "terminate_all_for_key": true
+        })))
+        .with_status(200)
+        .with_body(r#"{"ok":true}"#)
+        .expect(1)
+        .create_async()
+        .await;
+
+    let report = sharing::enforce(
+        &client_for(&server),
+        &thresholds(),
+        &EnforcementPolicy {
+            reason: Some("key sharing".to_string()),
+            revoke_key: true,
+            terminate_all_for_key: true,
+            ..Default::default()
+        },
+        EnforceOptions::default(),
+        DashboardRequestOptions::default(),
+    )
+    .await
+    .expect("enforce should succeed");
+
+    assert_eq!(report.actions.len(), 1);
+    assert_eq!(report.actions[0].outcome, BulkOutcome::Succeeded);
+    revoke.assert_async().await;
+}
+
+#[tokio::test]
+async fn key_actions_are_retried_when_the_first_revoke_fails() {
+    let mut server = Server::new_async().await;
+
+    let _sessions = server
+        .mock("GET", "/dashboard/key-sessions")
+        .with_status(200)
+        .with_body(SESSIONS)
+        .create_async()
+        .await;
+    let failed = server
+        .mock("DELETE", "/dashboard/key-sessions/s-2")
+        .match_body(Matcher::Json(json!({ "reset_hwid": true })))
+        .with_status(500)
+        .with_body(r#"{"error":"boom"}"#)
+        .expect(1)
+        .create_async()
+        .await;
+    let retried = server
+        .mock("DELETE", "/dashboard/key-sessions/s-3")
+        .match_body(Matcher::Json(json!({ "reset_hwid": true })))
+        .with_status(200)
+        .with_body(r#"{"ok":true}"#)
+        .expect(1)
+        .create_async()
+        .await;
+
+    let report = sharing::enforce(
+        &client_for(&server),
+        &thresholds(),
+        &EnforcementPolicy {
+            reset_hwid: true,
+            ..Default::default()
+        },
+        EnforceOptions::default(),
+        DashboardRequestOptions::default(),
+    )
+    .await
+    .expect("enforce should succeed");
+
+    let outcomes: Vec<&BulkOutcome> = report.actions.iter().map(|a| &a.outcome).collect();
+    assert!(matches!(outcomes[0], BulkOutcome::Failed(_)));
+    assert_eq!(outcomes[1], &BulkOutcome::Succeeded);
+    failed.assert_async().await;
+    retried.assert_async().await;
+}
@@ -0,0 +1,118 @@
+#![cfg(feature = "runtime")]
+
+use mockito::{Matcher, Server};
+use nebulauth_sdk::signing::{self, FixedClock, FixedNonce};
+use nebulauth_sdk::{
+    GenericPostOptions, NebulAuthClient, NebulAuthClientOptions, ReplayProtectionMode,
+};
+use serde::Deserialize;
+use serde_json::Value;
+use std::collections::BTreeMap;
+use std::sync::Arc;
+
+#[derive(Debug, Deserialize)]
+struct Vector {
+    name: String,
+    mode: String,
+    method: String,
+    base_path: String,
+    endpoint: String,
+    body: String,
+    secret: String,
+    timestamp_ms: u128,
+    nonce: String,
+    canonical_path: String,
+    body_sha256: String,
+    canonical: String,
+    signature: String,
+    headers: BTreeMap<String, String>,
+}
+
+#[derive(Debug, Deserialize)]
+struct VectorFile {
+    vectors: Vec<Vector>,
+}
+
+fn vectors() -> Vec<Vector> {
+    let contents = include_str!("vectors/signing.json");
+    serde_json::from_str::<VectorFile>(contents)
+        .expect("vector file should parse")
+        .vectors
+}
+
+#[test]
+fn canonical_strings_and_signatures_match_vectors() {
+    for vector in vectors() {
+        let body_hash = signing::body_sha256(&vector.body);
+        assert_eq!(body_hash, vector.body_sha256, "{}", vector.name);
+
+        let canonical = signing::canonical_string(
+            &vector.method,
+            &vector.canonical_path,
+            &vector.timestamp_ms.to_string(),
+            &vector.nonce,
+            &body_hash,
+        );
+        assert_eq!(canonical, vector.canonical, "{}", vector.name);
+        assert_eq!(
+            signing::sign(&vector.secret, &canonical).unwrap(),
+            vector.signature,
+            "{}",
+            vector.name
+        );
+    }
+}
+
+#[tokio::test]
+async fn client_sends_vector_headers_with_fixed_clock_and_nonce() {
+    for vector in vectors() {
+        let mut server = Server::new_async().await;
+        let mut mock = server
+            .mock(
+                vector.method.as_str(),
+                format!("{}{}", vector.base_path, vector.endpoint).as_str(),
+            )
+            .match_body(Matcher::Exact(vector.body.clone()));
+        for (name, value) in &vector.headers {
+            mock = mock.match_header(name.as_str(), value.as_str());
+        }
+        if !vector.headers.contains_key("x-body-sha256") {
+            mock = mock.match_header("x-body-sha256", Matcher::Missing);
+        }
+        let mock = mock.with_status(200).with_body("{}").create_async().await;
+
+        let pop = vector.mode == "pop";
+        let client = NebulAuthClient::new(NebulAuthClientOptions {
+            base_url: format!("{}{}", server.url(), vector.base_path),
+            bearer_token: Some("mk_at_test".to_string()),
+            signing_secret: (!pop).then(|| vector.secret.clone()),
+            replay_protection: match vector.mode.as_str() {
+                "strict" => ReplayProtectionMode::Strict,
+                "nonce" => ReplayProtectionMode::Nonce,
+                _ => ReplayProtectionMode::None,
+            },
+            clock: Arc::new(FixedClock(vector.timestamp_ms)),
+            nonce_source: Arc::new(FixedNonce(vector.nonce.clone())),
+            ..Default::default()
+        })
+        .unwrap();
+
+        let payload: Value = serde_json::from_str(&vector.body).unwrap();
+        let response = client
+            .post(
+                &vector.endpoint,
+                &payload,
+                GenericPostOptions {
+                    use_pop: pop,
+                    access_token: pop.then(|| "mk_at_pop".to_string()),
+                    pop_key: pop.then(|| vector.secret.clone()),
+                    ..Default::default()
+                },
+            )
+            .await
+            .unwrap();
+
+        assert_eq!(response.status_code, 200, "{}", vector.name);
+        mock.assert_async().await;
+    }
+}
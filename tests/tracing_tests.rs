@@ -0,0 +1,157 @@
+#![cfg(all(feature = "tracing", feature = "runtime", feature = "dashboard"))]
+
+use mockito::Server;
+use nebulauth_sdk::{
+    DashboardAuth, DashboardRequestOptions, LoginRequest, NebulAuthClient, NebulAuthClientOptions,
+    NebulAuthDashboardClient, NebulAuthDashboardClientOptions, ReplayProtectionMode,
+    VerifyKeyInput,
+};
+use std::io::Write;
+use std::sync::{Arc, Mutex};
+use tracing_subscriber::fmt::format::FmtSpan;
+use tracing_subscriber::fmt::MakeWriter;
+
+#[derive(Clone, Default)]
+struct Capture(Arc<Mutex<Vec<u8>>>);
+
+impl Capture {
+    fn output(&self) -> String {
+        String::from_utf8(self.0.lock().unwrap().clone()).unwrap()
+    }
+}
+
+impl Write for Capture {
+    fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+        self.0.lock().unwrap().extend_from_slice(buf);
+        Ok(buf.len())
+    }
+
+    fn flush(&mut self) -> std::io::Result<()> {
+        Ok(())
+    }
+}
+
+impl<'a> MakeWriter<'a> for Capture {
+    type Writer = Capture;
+
+    fn make_writer(&'a self) -> Self::Writer {
+        self.clone()
+    }
+}
+
+fn capture() -> (Capture, tracing::subscriber::DefaultGuard) {
+    let capture = Capture::default();
+    let subscriber = tracing_subscriber::fmt()
+        .with_writer(capture.clone())
+        .with_max_level(tracing::Level::DEBUG)
+        .with_span_events(FmtSpan::CLOSE)
+        .finish();
+    let guard = tracing::subscriber::set_default(subscriber);
+    (capture, guard)
+}
+
+#[tokio::test]
+async fn runtime_span_records_request_metadata_but_no_secrets() {
+    let mut server = Server::new_async().await;
+    let _mock = server
+        .mock("POST", "/api/v1/keys/verify")
+        .with_status(200)
+        .with_header("x-request-id", "srv-7f3a")
+        .with_body(r#"{"valid":true}"#)
+        .create_async()
+        .await;
+
+    let (capture, _guard) = capture();
+    let client = NebulAuthClient::new(NebulAuthClientOptions {
+        base_url: format!("{}/api/v1", server.url()),
+        bearer_token: Some("mk_at_secret_token".to_string()),
+        signing_secret: Some("mk_sig_secret".to_string()),
+        replay_protection: ReplayProtectionMode::Strict,
+        ..Default::default()
+    })
+    .expect("client init should succeed");
+
+    client
+        .verify_key(VerifyKeyInput {
+            key: "mk_live_secret_key".to_string(),
+            request_id: Some("req-42".to_string()),
+            hwid: Some("HWID-SECRET".to_string()),
+            ..Default::default()
+        })
+        .await
+        .expect("request should succeed");
+
+    let output = capture.output();
+    assert!(output.contains("nebulauth.request"), "{output}");
+    assert!(output.contains("client=\"runtime\""), "{output}");
+    assert!(output.contains("endpoint=\"/keys/verify\""), "{output}");
+    assert!(output.contains("request_id=\"req-42\""), "{output}");
+    assert!(output.contains("status=200"), "{output}");
+    assert!(output.contains("latency_ms="), "{output}");
+    assert!(
+        output.contains("server_request_id=\"srv-7f3a\""),
+        "{output}"
+    );
+    for secret in [
+        "mk_at_secret",
+        "mk_sig_secret",
+        "mk_live_secret",
+        "HWID-SECRET",
+    ] {
+        assert!(!output.contains(secret), "{secret} leaked into:\n{output}");
+    }
+}
+
+#[tokio::test]
+async fn dashboard_span_counts_relogin_retry() {
+    let mut server = Server::new_async().await;
+    let _expired = server
+        .mock("GET", "/dashboard/keys")
+        .match_header("cookie", "mc_session=sess-old")
+        .with_status(401)
+        .create_async()
+        .await;
+    let _login = server
+        .mock("POST", "/dashboard/auth/login")
+        .with_status(200)
+        .with_header("set-cookie", "mc_session=sess-fresh; Path=/")
+        .with_body("{}")
+        .create_async()
+        .await;
+    let _fresh = server
+        .mock("GET", "/dashboard/keys")
+        .match_header("cookie", "mc_session=sess-fresh")
+        .with_status(200)
+        .with_body("[]")
+        .create_async()
+        .await;
+
+    let (capture, _guard) = capture();
+    let client = NebulAuthDashboardClient::new(NebulAuthDashboardClientOptions {
+        base_url: format!("{}/dashboard", server.url()),
+        auth: Some(DashboardAuth::Session {
+            session_cookie: "sess-old".to_string(),
+        }),
+        credentials: Some(LoginRequest {
+            email: "ops@example.com".to_string(),
+            password: "hunter2".to_string(),
+        }),
+        ..Default::default()
+    })
+    .expect("client init should succeed");
+
+    client
+        .list_keys(DashboardRequestOptions::default())
+        .await
+        .expect("request should succeed");
+
+    let output = capture.output();
+    assert!(output.contains("client=\"dashboard\""), "{output}");
+    assert!(output.contains("method=\"GET\""), "{output}");
+    assert!(output.contains("endpoint=\"/keys\""), "{output}");
+    assert!(output.contains("retries=1"), "{output}");
+    assert!(output.contains("status=200"), "{output}");
+    for secret in ["hunter2", "sess-old", "sess-fresh"] {
+        assert!(!output.contains(secret), "{secret} leaked into:\n{output}");
+    }
+}
@@ -0,0 +1,210 @@
+#![cfg(all(feature = "vcr", feature = "runtime", feature = "dashboard"))]
+
+use mockito::Server;
+use nebulauth_sdk::transport::{HttpRequest, ReqwestTransport, Transport};
+use nebulauth_sdk::vcr::{Cassette, RecordingTransport, ReplayTransport, SignatureMatcher};
+use nebulauth_sdk::{
+    ApiAuthMode, ApiTokenCreateRequest, ApiTokenScope, DashboardAuth, DashboardRequestOptions,
+    NebulAuthClient, NebulAuthClientOptions, NebulAuthDashboardClient,
+    NebulAuthDashboardClientOptions, NebulAuthError, ReplayProtectionMode, VerifyKeyInput,
+};
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Arc;
+
+fn runtime_client(base_url: String, mode: ReplayProtectionMode) -> NebulAuthClient {
+    NebulAuthClient::new(NebulAuthClientOptions {
+        base_url,
+        bearer_token: Some("mk_at_recorded_secret".to_string()),
+        signing_secret: Some("mk_sig_recorded_secret".to_string()),
+        replay_protection: mode,
+        ..Default::default()
+    })
+    .unwrap()
+}
+
+fn verify_input() -> VerifyKeyInput {
+    VerifyKeyInput {
+        key: "mk_live_cassette".to_string(),
+        request_id: Some("req-1".to_string()),
+        hwid: Some("HWID-1".to_string()),
+        ..Default::default()
+    }
+}
+
+fn cassette_path(name: &str) -> PathBuf {
+    Path::new(env!("CARGO_MANIFEST_DIR"))
+        .join("tests/cassettes")
+        .join(name)
+}
+
+#[tokio::test]
+async fn replays_checked_in_cassette_in_order_without_network() {
+    let replay =
+        Arc::new(ReplayTransport::load(&cassette_path("runtime_verify_key.json")).unwrap());
+    let client = runtime_client(
+        "https://api.nebulauth.com/api/v1".to_string(),
+        ReplayProtectionMode::Strict,
+    )
+    .with_transport(replay.clone());
+
+    let first = client.verify_key(verify_input()).await.unwrap();
+    assert_eq!(first.status_code, 200);
+    assert_eq!(first.data["valid"], true);
+    assert_eq!(first.headers.get("x-request-id").unwrap(), "srv-01");
+
+    let second = client.verify_key(verify_input()).await.unwrap();
+    assert_eq!(second.status_code, 409);
+    assert_eq!(replay.remaining(), 0);
+
+    let err = client
+        .verify_key(verify_input())
+        .await
+        .expect_err("cassette is used up");
+    assert!(matches!(err, NebulAuthError::Config(message) if message.contains("/keys/verify")));
+}
+
+#[tokio::test]
+async fn records_scrubbed_cassette_that_replays_offline() {
+    let mut server = Server::new_async().await;
+    let _verify = server
+        .mock("POST", "/api/v1/keys/verify")
+        .with_status(200)
+        .with_header("content-type", "application/json")
+        .with_body(r#"{"valid":true}"#)
+        .create_async()
+        .await;
+    let _token = server
+        .mock("POST", "/dashboard/api-tokens")
+        .with_status(201)
+        .with_header("set-cookie", "mc_session=sess-recorded; Path=/; HttpOnly")
+        .with_body(r#"{"id":"tok_1","token":"mk_at_fresh_secret"}"#)
+        .create_async()
+        .await;
+
+    let path = std::env::temp_dir().join(format!(
+        "nebulauth-vcr-{}-{}.json",
+        std::process::id(),
+        server.socket_address().port()
+    ));
+    let recorder = Arc::new(RecordingTransport::new(
+        Arc::new(ReqwestTransport::new(reqwest::Client::new())),
+        &path,
+    ));
+
+    let runtime = runtime_client(
+        format!("{}/api/v1", server.url()),
+        ReplayProtectionMode::Strict,
+    )
+    .with_transport(recorder.clone());
+    runtime.verify_key(verify_input()).await.unwrap();
+
+    let dashboard_options = NebulAuthDashboardClientOptions {
+        base_url: format!("{}/dashboard", server.url()),
+        auth: Some(DashboardAuth::Session {
+            session_cookie: "sess-recorded-secret".to_string(),
+        }),
+        ..Default::default()
+    };
+    let token_request = || ApiTokenCreateRequest {
+        scopes: vec![ApiTokenScope::KeysVerify],
+        replay_protection: ReplayProtectionMode::Strict,
+        auth_mode: ApiAuthMode::Bearer,
+        expires_at: None,
+    };
+    NebulAuthDashboardClient::new(dashboard_options.clone())
+        .unwrap()
+        .with_transport(recorder.clone())
+        .create_api_token(token_request(), DashboardRequestOptions::default())
+        .await
+        .unwrap();
+
+    let contents = std::fs::read_to_string(&path).unwrap();
+    for secret in [
+        "mk_at_recorded_secret",
+        "mk_live_cassette",
+        "sess-recorded",
+        "mk_at_fresh_secret",
+    ] {
+        assert!(!contents.contains(secret), "{secret} leaked into cassette");
+    }
+    assert!(contents.contains("Path=/"));
+    assert_eq!(Cassette::load(&path).unwrap(), recorder.cassette());
+    drop(server);
+
+    let replay = Arc::new(ReplayTransport::load(&path).unwrap());
+    let verified = runtime_client(
+        "https://elsewhere.example/api/v1".to_string(),
+        ReplayProtectionMode::Strict,
+    )
+    .with_transport(replay.clone())
+    .verify_key(verify_input())
+    .await
+    .unwrap();
+    assert_eq!(verified.data["valid"], true);
+
+    let created = NebulAuthDashboardClient::new(dashboard_options)
+        .unwrap()
+        .with_transport(replay.clone())
+        .create_api_token(token_request(), DashboardRequestOptions::default())
+        .await
+        .unwrap();
+    assert_eq!(created.status_code, 201);
+    assert_eq!(created.data["token"], "[redacted]");
+    assert_eq!(replay.remaining(), 0);
+
+    let _ = std::fs::remove_file(&path);
+}
+
+#[tokio::test]
+async fn signature_matcher_is_configurable() {
+    let path = cassette_path("runtime_verify_key.json");
+    let nonce_client = || {
+        runtime_client(
+            "https://api.nebulauth.com/api/v1".to_string(),
+            ReplayProtectionMode::Nonce,
+        )
+    };
+
+    // Nonce mode sends no X-Body-Sha256, unlike the strict-mode recording.
+    let strict = Arc::new(ReplayTransport::load(&path).unwrap());
+    assert!(nonce_client()
+        .with_transport(strict)
+        .verify_key(verify_input())
+        .await
+        .is_err());
+
+    let lenient = Arc::new(
+        ReplayTransport::load(&path)
+            .unwrap()
+            .with_matcher(SignatureMatcher::Ignore),
+    );
+    assert_eq!(
+        nonce_client()
+            .with_transport(lenient)
+            .verify_key(verify_input())
+            .await
+            .unwrap()
+            .status_code,
+        200
+    );
+
+    let calls = Arc::new(AtomicUsize::new(0));
+    let seen = calls.clone();
+    let custom = Arc::new(ReplayTransport::load(&path).unwrap().with_matcher(
+        SignatureMatcher::Custom(Arc::new(
+            move |_recorded: &HttpRequest, live: &HttpRequest| {
+                seen.fetch_add(1, Ordering::Relaxed);
+                live.header("x-signature")
+                    .is_some_and(|sig| sig.len() == 64)
+            },
+        )),
+    ));
+    let transport: Arc<dyn Transport> = custom;
+    nonce_client()
+        .with_transport(transport)
+        .verify_key(verify_input())
+        .await
+        .unwrap();
+    assert!(calls.load(Ordering::Relaxed) > 0);
+}
@@ -0,0 +1,130 @@
+#![cfg(all(target_arch = "wasm32", feature = "runtime"))]
+// Runs under Node: cargo test --target wasm32-unknown-unknown --no-default-features
+//   --features runtime --test wasm_tests
+
+use nebulauth_sdk::signing::{self, Clock, NonceSource, RandomNonce, SystemClock};
+use nebulauth_sdk::{
+    NebulAuthClient, NebulAuthClientOptions, NebulAuthError, ReplayProtectionMode, VerifyKeyInput,
+};
+use serde_json::Value;
+use wasm_bindgen::prelude::*;
+use wasm_bindgen_futures::JsFuture;
+use wasm_bindgen_test::wasm_bindgen_test;
+
+#[wasm_bindgen(inline_js = r#"
+export async function startServer(status, body) {
+  const http = await import('node:http');
+  const requests = [];
+  const server = http.createServer((req, res) => {
+    let data = '';
+    req.on('data', (chunk) => (data += chunk));
+    req.on('end', () => {
+      requests.push({ method: req.method, path: req.url, headers: req.headers, body: data });
+      res.writeHead(status, { 'content-type': 'application/json' });
+      res.end(body, () => server.close());
+    });
+  });
+  await new Promise((resolve) => server.listen(0, '127.0.0.1', resolve));
+  return {
+    url: `http://127.0.0.1:${server.address().port}`,
+    requests: () => JSON.stringify(requests),
+  };
+}
+"#)]
+extern "C" {
+    #[wasm_bindgen(js_name = startServer)]
+    fn start_server(status: u16, body: &str) -> js_sys::Promise;
+}
+
+struct TestServer(JsValue);
+
+impl TestServer {
+    async fn start(status: u16, body: &str) -> Self {
+        Self(JsFuture::from(start_server(status, body)).await.unwrap())
+    }
+
+    fn url(&self) -> String {
+        js_sys::Reflect::get(&self.0, &"url".into())
+            .unwrap()
+            .as_string()
+            .unwrap()
+    }
+
+    fn requests(&self) -> Vec<Value> {
+        let requests: js_sys::Function = js_sys::Reflect::get(&self.0, &"requests".into())
+            .unwrap()
+            .into();
+        let json = requests.call0(&JsValue::NULL).unwrap().as_string().unwrap();
+        serde_json::from_str(&json).unwrap()
+    }
+}
+
+#[wasm_bindgen_test]
+fn clock_and_nonce_work_on_wasm() {
+    assert!(SystemClock.now_ms() > 1_600_000_000_000);
+    assert_eq!(RandomNonce.nonce().len(), 22);
+    assert_ne!(RandomNonce.nonce(), RandomNonce.nonce());
+}
+
+#[wasm_bindgen_test]
+async fn verify_key_is_signed_and_sent_with_fetch() {
+    let server = TestServer::start(200, r#"{"valid":true}"#).await;
+    let client = NebulAuthClient::new(NebulAuthClientOptions {
+        base_url: format!("{}/api/v1", server.url()),
+        bearer_token: Some("mk_at_test".to_string()),
+        signing_secret: Some("mk_sig_test".to_string()),
+        replay_protection: ReplayProtectionMode::Strict,
+        ..Default::default()
+    })
+    .unwrap();
+
+    let response = client
+        .verify_key(VerifyKeyInput {
+            key: "mk_live_test".to_string(),
+            hwid: Some("HWID-1".to_string()),
+            ..Default::default()
+        })
+        .await
+        .unwrap();
+
+    assert_eq!(response.status_code, 200);
+    assert_eq!(response.data["valid"], true);
+    let requests = server.requests();
+    let request = &requests[0];
+    assert_eq!(request["path"], "/api/v1/keys/verify");
+    let headers = &request["headers"];
+    assert_eq!(headers["authorization"], "Bearer mk_at_test");
+    assert_eq!(headers["x-hwid"], "HWID-1");
+    let body_sha256 = signing::body_sha256(request["body"].as_str().unwrap());
+    assert_eq!(headers["x-body-sha256"], body_sha256.as_str());
+    let canonical = signing::canonical_string(
+        "POST",
+        "/keys/verify",
+        headers["x-timestamp"].as_str().unwrap(),
+        headers["x-nonce"].as_str().unwrap(),
+        &body_sha256,
+    );
+    let signature = signing::sign("mk_sig_test", &canonical).unwrap();
+    assert_eq!(headers["x-signature"], signature.as_str());
+}
+
+#[wasm_bindgen_test]
+async fn fetch_failures_are_request_errors() {
+    let client = NebulAuthClient::new(NebulAuthClientOptions {
+        base_url: "http://127.0.0.1:1/api/v1".to_string(),
+        bearer_token: Some("t".to_string()),
+        replay_protection: ReplayProtectionMode::None,
+        ..Default::default()
+    })
+    .unwrap();
+
+    let error = client
+        .verify_key(VerifyKeyInput {
+            key: "mk_live_test".to_string(),
+            ..Default::default()
+        })
+        .await
+        .unwrap_err();
+
+    assert!(matches!(error, NebulAuthError::Request(_)), "{error:?}");
+}
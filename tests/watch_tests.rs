@@ -0,0 +1,225 @@
+#![cfg(feature = "dashboard")]
+
+use futures_util::StreamExt;
+use mockito::{Mock, Server, ServerGuard};
+use nebulauth_sdk::watch::{ChangeEvent, WatchCursor, WatchOptions, Watcher};
+use nebulauth_sdk::{
+    DashboardAuth, DashboardRequestOptions, NebulAuthDashboardClient,
+    NebulAuthDashboardClientOptions,
+};
+use serde_json::json;
+use std::time::Duration;
+
+fn client_for(server: &ServerGuard) -> NebulAuthDashboardClient {
+    NebulAuthDashboardClient::new(NebulAuthDashboardClientOptions {
+        base_url: format!("{}/dashboard", server.url()),
+        auth: Some(DashboardAuth::Bearer {
+            bearer_token: "mk_at_test".to_string(),
+        }),
+        ..Default::default()
+    })
+    .expect("client init should succeed")
+}
+
+async fn mock_get(server: &mut ServerGuard, path: &str, body: &str) -> Mock {
+    server
+        .mock("GET", format!("/dashboard{path}").as_str())
+        .with_status(200)
+        .with_body(body)
+        .create_async()
+        .await
+}
+
+fn event_names(events: &[ChangeEvent]) -> Vec<String> {
+    events
+        .iter()
+        .map(|event| {
+            let value = serde_json::to_value(event).unwrap();
+            format!(
+                "{} {}",
+                value["type"].as_str().unwrap(),
+                value["resource"]["id"].as_str().unwrap()
+            )
+        })
+        .collect()
+}
+
+#[tokio::test]
+async fn poll_diffs_against_cursor() {
+    let mut server = Server::new_async().await;
+
+    let _keys = mock_get(
+        &mut server,
+        "/keys",
+        r#"[
+            {"id":"k-1","key":"mk_live_a","revoked":true},
+            {"id":"k-2","key":"mk_live_b","label":"Same"},
+            {"id":"k-4","key":"mk_live_d","label":"Renamed"},
+            {"id":"k-5","key":"mk_live_e"}
+        ]"#,
+    )
+    .await;
+    let _sessions = mock_get(
+        &mut server,
+        "/key-sessions",
+        r#"[{"id":"s-2","keyId":"k-2"}]"#,
+    )
+    .await;
+    let _blacklist = mock_get(
+        &mut server,
+        "/blacklist",
+        r#"[{"id":"bl-1","type":"ip","value":"1.1.1.1"},{"id":"bl-2","type":"hwid","value":"H-2"}]"#,
+    )
+    .await;
+
+    let cursor: WatchCursor = serde_json::from_value(json!({
+        "keys": {
+            "k-1": { "id": "k-1" },
+            "k-2": { "id": "k-2", "label": "Same" },
+            "k-3": { "id": "k-3" },
+            "k-4": { "id": "k-4", "label": "Original" }
+        },
+        "sessions": { "s-1": { "id": "s-1", "key_id": "k-1" } },
+        "blacklist": { "bl-1": { "id": "bl-1", "type": "ip", "value": "1.1.1.1" } }
+    }))
+    .expect("cursor should parse");
+
+    let client = client_for(&server);
+    let mut watcher = Watcher::new(
+        &client,
+        WatchOptions {
+            cursor: Some(cursor),
+            ..Default::default()
+        },
+        DashboardRequestOptions::default(),
+    )
+    .expect("watcher should start");
+
+    let events = watcher.poll().await.expect("poll should succeed");
+    assert_eq!(
+        event_names(&events),
+        vec![
+            "key_revoked k-1",
+            "key_updated k-4",
+            "key_created k-5",
+            "key_deleted k-3",
+            "session_started s-2",
+            "session_ended s-1",
+            "blacklist_added bl-2",
+        ]
+    );
+    assert!(
+        matches!(&events[2], ChangeEvent::KeyCreated(key) if key.key.as_deref() == Some("mk_live_e"))
+    );
+
+    let stored = watcher.cursor().keys.as_ref().unwrap();
+    assert!(stored.values().all(|key| key.key.is_none()));
+    assert!(watcher
+        .poll()
+        .await
+        .expect("poll should succeed")
+        .is_empty());
+}
+
+#[tokio::test]
+async fn stream_resumes_from_cursor_file_without_replaying() {
+    let mut server = Server::new_async().await;
+
+    let cursor_file = std::env::temp_dir().join(format!(
+        "nebulauth-watch-{}-{}.json",
+        std::process::id(),
+        server.socket_address().port()
+    ));
+    let _ = std::fs::remove_file(&cursor_file);
+    let options = WatchOptions {
+        interval: Duration::from_millis(10),
+        sessions: false,
+        blacklist: false,
+        cursor_file: Some(cursor_file.clone()),
+        ..Default::default()
+    };
+
+    let first = mock_get(&mut server, "/keys", r#"[{"id":"k-1"}]"#).await;
+    let client = client_for(&server);
+    let mut watcher = Watcher::new(&client, options.clone(), DashboardRequestOptions::default())
+        .expect("watcher should start");
+    assert!(watcher
+        .poll()
+        .await
+        .expect("baseline should succeed")
+        .is_empty());
+    assert!(cursor_file.exists());
+    first.remove_async().await;
+
+    let _second = mock_get(&mut server, "/keys", r#"[{"id":"k-1"},{"id":"k-2"}]"#).await;
+    let restarted = Watcher::new(&client, options, DashboardRequestOptions::default())
+        .expect("watcher should restart");
+    let events: Vec<ChangeEvent> = restarted
+        .into_stream()
+        .take(1)
+        .map(|event| event.expect("poll should succeed"))
+        .collect()
+        .await;
+
+    assert_eq!(event_names(&events), vec!["key_created k-2"]);
+    let _ = std::fs::remove_file(&cursor_file);
+}
+
+#[tokio::test]
+async fn stream_saves_cursor_only_after_events_are_taken() {
+    let mut server = Server::new_async().await;
+
+    let cursor_file = std::env::temp_dir().join(format!(
+        "nebulauth-watch-drain-{}-{}.json",
+        std::process::id(),
+        server.socket_address().port()
+    ));
+    let _ = std::fs::remove_file(&cursor_file);
+    let saved_keys = || -> Vec<String> {
+        let cursor = WatchCursor::load(&cursor_file).expect("cursor should load");
+        cursor.keys.unwrap_or_default().into_keys().collect()
+    };
+
+    let baseline = mock_get(&mut server, "/keys", r#"[{"id":"k-1"}]"#).await;
+    let client = client_for(&server);
+    let options = WatchOptions {
+        interval: Duration::from_millis(10),
+        sessions: false,
+        blacklist: false,
+        cursor_file: Some(cursor_file.clone()),
+        ..Default::default()
+    };
+    let mut watcher = Watcher::new(&client, options, DashboardRequestOptions::default())
+        .expect("watcher should start");
+    watcher.poll().await.expect("baseline should succeed");
+    baseline.remove_async().await;
+
+    let _changed = mock_get(
+        &mut server,
+        "/keys",
+        r#"[{"id":"k-1"},{"id":"k-2"},{"id":"k-3"}]"#,
+    )
+    .await;
+    let mut stream = Box::pin(watcher.into_stream());
+    for expected in ["key_created k-2", "key_created k-3"] {
+        let event = stream.next().await.unwrap().expect("poll should succeed");
+        assert_eq!(event_names(&[event]), vec![expected]);
+        assert_eq!(saved_keys(), vec!["k-1"]);
+    }
+
+    // Asking for more means both events were handled, so the cursor is saved before the
+    // next poll.
+    let _ = tokio::time::timeout(Duration::from_millis(50), stream.next()).await;
+    assert_eq!(saved_keys(), vec!["k-1", "k-2", "k-3"]);
+
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::PermissionsExt;
+        let mode = std::fs::metadata(&cursor_file)
+            .unwrap()
+            .permissions()
+            .mode();
+        assert_eq!(mode & 0o777, 0o600);
+    }
+    let _ = std::fs::remove_file(&cursor_file);
+}